@@ -0,0 +1,83 @@
+//! Implementations for the dates and times of [`chrono`], enabled with the `chrono` feature.
+//!
+//! All values are serialized and deserialized as RFC 3339, e.g. `2024-01-15T10:30:00Z` for
+//! [`DateTime<Utc>`], `2024-01-15T10:30:00` for [`NaiveDateTime`] and `2024-01-15` for
+//! [`NaiveDate`].
+
+use std::io::Write;
+
+use ::chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+
+use crate::de::{de_with_parser, DeserializeBuf};
+use crate::ser::{Serialize, XmlWriter};
+use crate::Error;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+impl DeserializeBuf for NaiveDate {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        de_with_parser(buf, "NaiveDate", |s| {
+            NaiveDate::parse_from_str(s, DATE_FORMAT)
+        })
+    }
+}
+
+impl DeserializeBuf for NaiveDateTime {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        de_with_parser(buf, "NaiveDateTime", |s| {
+            NaiveDateTime::parse_from_str(s, DATE_TIME_FORMAT)
+        })
+    }
+}
+
+impl DeserializeBuf for DateTime<FixedOffset> {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        de_with_parser(buf, "DateTime", DateTime::parse_from_rfc3339)
+    }
+}
+
+impl DeserializeBuf for DateTime<Utc> {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        DateTime::<FixedOffset>::de_buf(buf).map(|date| date.with_timezone(&Utc))
+    }
+}
+
+impl<W: Write> Serialize<W> for NaiveDate {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        write!(writer, "{}", self.format(DATE_FORMAT))?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Serialize<W> for NaiveDateTime {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        write!(writer, "{}", self.format(DATE_TIME_FORMAT))?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Serialize<W> for DateTime<FixedOffset> {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        writer.write_all(
+            self.to_rfc3339_opts(SecondsFormat::AutoSi, false)
+                .as_bytes(),
+        )?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Serialize<W> for DateTime<Utc> {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        writer.write_all(self.to_rfc3339_opts(SecondsFormat::AutoSi, true).as_bytes())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,556 @@
+//! Adapters for types implementing the traits of [`serde`](https://docs.rs/serde), enabled with
+//! the `serde` feature.
+//!
+//! The values are wrapped, since a blanket implementation would overlap with the implementations
+//! of this crate. Only a subset of the serde data model can be represented. Other shapes cause an
+//! [`Error::Unrepresentable`] at runtime.
+
+use std::io::Write;
+
+use ::serde::de::{self as serde_de, IntoDeserializer, Visitor};
+use ::serde::ser::{self, Impossible};
+
+use crate::de::DeserializeBuf;
+use crate::ser::{Error, Serialize, XmlWriter};
+
+impl ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Unrepresentable {
+            ty_name: String::from("serde"),
+            reason: msg.to_string(),
+        }
+    }
+}
+
+impl serde_de::Error for crate::Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self {
+            ty_name: String::from("serde"),
+            kind: crate::ErrorKind::InvalidType(msg.to_string()),
+        }
+    }
+}
+
+// Error for values whose shape has no representation
+fn unsupported(ty_name: &str, shape: &str) -> Error {
+    Error::Unrepresentable {
+        ty_name: String::from(ty_name),
+        reason: format!("{} is not supported by the serde adapter", shape),
+    }
+}
+
+/// Serializes a flat struct implementing [`serde::Serialize`](::serde::Serialize) as element.
+///
+/// All fields are written as attributes. Their values must be scalars like numbers, strings,
+/// bools or unit variants of enums, which are written like the implementations of [`Serialize`]
+/// for the primitives. Fields containing `None` are skipped. Newtype structs are serialized as
+/// their inner value.
+///
+/// The names are used as given by serde, so the element has the name of the struct. Use
+/// `#[serde(rename = "...")]` and `#[serde(rename_all = "camelCase")]` to match the names of the
+/// derive.
+///
+/// Nested structs, sequences, maps and enum variants with data aren't supported and return
+/// [`Error::Unrepresentable`].
+///
+/// # Example
+/// ```
+/// use xmlib::compat::SerdeElement;
+///
+/// #[derive(serde::Serialize)]
+/// #[serde(rename = "rectangle")]
+/// struct Rectangle {
+///     width: u32,
+///     height: u32,
+///     label: Option<String>,
+/// }
+///
+/// let rect = Rectangle { width: 13, height: 42, label: None };
+/// let serialized = xmlib::ser::write_to_string(SerdeElement(rect)).unwrap();
+/// assert_eq!(serialized, r#"<rectangle width="13" height="42"/>"#);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SerdeElement<T>(pub T);
+
+impl<W: Write, T: ::serde::Serialize> Serialize<W> for SerdeElement<T> {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        self.0.serialize(ElementSerializer { writer })
+    }
+}
+
+// Writes a struct as element with attributes
+struct ElementSerializer<'a, W: Write> {
+    writer: &'a mut XmlWriter<W>,
+}
+
+// Writes the attributes of a struct and ends the element
+struct AttrSerializer<'a, W: Write> {
+    writer: &'a mut XmlWriter<W>,
+    ty_name: &'static str,
+}
+
+// Writes a scalar value, returns whether anything was written to skip `None`
+struct ScalarSerializer<'a, W: Write> {
+    writer: &'a mut XmlWriter<W>,
+}
+
+macro_rules! unsupported_element {
+    ($($method:ident($($arg:ty),*) => $shape:expr;)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Self::Ok, Self::Error> {
+                Err(unsupported("serde", $shape))
+            }
+        )*
+    };
+}
+
+impl<'a, W: Write> ser::Serializer for ElementSerializer<'a, W> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = AttrSerializer<'a, W>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    unsupported_element! {
+        serialize_bool(bool) => "a bool as element";
+        serialize_i8(i8) => "an integer as element";
+        serialize_i16(i16) => "an integer as element";
+        serialize_i32(i32) => "an integer as element";
+        serialize_i64(i64) => "an integer as element";
+        serialize_u8(u8) => "an integer as element";
+        serialize_u16(u16) => "an integer as element";
+        serialize_u32(u32) => "an integer as element";
+        serialize_u64(u64) => "an integer as element";
+        serialize_f32(f32) => "a float as element";
+        serialize_f64(f64) => "a float as element";
+        serialize_char(char) => "a char as element";
+        serialize_str(&str) => "a string as element";
+        serialize_bytes(&[u8]) => "bytes";
+        serialize_none() => "an option as element";
+        serialize_unit() => "a unit as element";
+        serialize_unit_struct(&'static str) => "a unit struct";
+        serialize_unit_variant(&'static str, u32, &'static str) => "an enum as element";
+    }
+
+    fn serialize_some<T: ::serde::Serialize + ?Sized>(self, _: &T) -> Result<(), Error> {
+        Err(unsupported("serde", "an option as element"))
+    }
+
+    fn serialize_newtype_struct<T: ::serde::Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ::serde::Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), Error> {
+        Err(unsupported(name, "an enum variant with data"))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(unsupported("serde", "a sequence"))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(unsupported("serde", "a tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(unsupported(name, "a tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(unsupported(name, "an enum variant with data"))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(unsupported("serde", "a map"))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        self.writer.start_element()?;
+        self.writer.write_all(b"<")?;
+        self.writer.write_all(name.as_bytes())?;
+        Ok(AttrSerializer {
+            writer: self.writer,
+            ty_name: name,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(unsupported(name, "an enum variant with data"))
+    }
+}
+
+impl<'a, W: Write> ser::SerializeStruct for AttrSerializer<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ::serde::Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        // the value is written first to skip the attribute of `None`
+        let mut value_writer =
+            XmlWriter::new(Vec::new())?.with_bool_style(self.writer.bool_style());
+        let written = value
+            .serialize(ScalarSerializer {
+                writer: &mut value_writer,
+            })
+            .map_err(|e| match e {
+                Error::Unrepresentable { reason, .. } => Error::Unrepresentable {
+                    ty_name: format!("{}.{}", self.ty_name, key),
+                    reason,
+                },
+                e => e,
+            })?;
+        if written {
+            let quote = self.writer.quote();
+            self.writer.write_all(b" ")?;
+            self.writer.write_all(key.as_bytes())?;
+            self.writer.write_all(b"=")?;
+            self.writer.write_all(quote)?;
+            self.writer.write_all(&value_writer.into_inner())?;
+            self.writer.write_all(quote)?;
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.writer.write_all(b"/>")?;
+        self.writer.end_element(true)?;
+        Ok(())
+    }
+}
+
+macro_rules! scalar_primitives {
+    ($($method:ident($t:ty);)*) => {
+        $(
+            fn $method(self, v: $t) -> Result<bool, Error> {
+                Serialize::ser(&v, self.writer)?;
+                Ok(true)
+            }
+        )*
+    };
+}
+
+impl<'a, W: Write> ser::Serializer for ScalarSerializer<'a, W> {
+    type Ok = bool;
+    type Error = Error;
+    type SerializeSeq = Impossible<bool, Error>;
+    type SerializeTuple = Impossible<bool, Error>;
+    type SerializeTupleStruct = Impossible<bool, Error>;
+    type SerializeTupleVariant = Impossible<bool, Error>;
+    type SerializeMap = Impossible<bool, Error>;
+    type SerializeStruct = Impossible<bool, Error>;
+    type SerializeStructVariant = Impossible<bool, Error>;
+
+    scalar_primitives! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_i128(i128);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_u128(u128);
+        serialize_f32(f32);
+        serialize_f64(f64);
+    }
+
+    fn serialize_char(self, v: char) -> Result<bool, Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<bool, Error> {
+        self.writer
+            .write_all(&quick_xml::escape::escape(v.as_bytes()))?;
+        Ok(true)
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<bool, Error> {
+        Err(unsupported("serde", "bytes"))
+    }
+
+    fn serialize_none(self) -> Result<bool, Error> {
+        Ok(false)
+    }
+
+    fn serialize_some<T: ::serde::Serialize + ?Sized>(self, value: &T) -> Result<bool, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<bool, Error> {
+        Err(unsupported("serde", "a unit"))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<bool, Error> {
+        Err(unsupported(name, "a unit struct"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<bool, Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: ::serde::Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<bool, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ::serde::Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<bool, Error> {
+        Err(unsupported(name, "an enum variant with data"))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(unsupported("serde", "a sequence"))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(unsupported("serde", "a tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(unsupported(name, "a tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(unsupported(name, "an enum variant with data"))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(unsupported("serde", "a map"))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(unsupported(name, "a nested struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(unsupported(name, "an enum variant with data"))
+    }
+}
+
+/// A scalar value implementing the traits of [`serde`](https://docs.rs/serde), which is used as
+/// attribute or text.
+///
+/// The value is deserialized from the text like with
+/// [`serde_plain`](https://docs.rs/serde_plain): numbers and bools are parsed like the
+/// implementations of [`DeserializeBuf`] for the primitives, strings are taken as they are and
+/// enums must be unit variants named like the text. Options are always `Some`, since missing
+/// values are handled by `#[xmlib(default)]`. Serializing works like the attributes of
+/// [`SerdeElement`].
+///
+/// Annotate fields with `#[xmlib(serde)]` to use the adapter without wrapping the type. Only
+/// scalars are supported, structs, sequences and enum variants with data cause an error.
+///
+/// # Example
+/// ```
+/// use xmlib::compat::SerdeBuf;
+/// use xmlib_derive::{Deserialize, Serialize};
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// #[serde(rename_all = "lowercase")]
+/// enum Level {
+///     Debug,
+///     Info,
+/// }
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Log {
+///     level: SerdeBuf<Level>,
+///     #[xmlib(serde)]
+///     min_level: Level,
+/// }
+///
+/// let log: Log = xmlib::de::from_str(r#"<log level="info" minLevel="debug"/>"#).unwrap();
+/// assert_eq!(log.level, SerdeBuf(Level::Info));
+/// assert_eq!(log.min_level, Level::Debug);
+///
+/// let serialized = xmlib::ser::write_to_string(log).unwrap();
+/// assert_eq!(serialized, r#"<log level="info" minLevel="debug"/>"#);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct SerdeBuf<T>(pub T);
+
+impl<T: ::serde::de::DeserializeOwned> DeserializeBuf for SerdeBuf<T> {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, crate::Error> {
+        serde_buf::de_buf(buf).map(Self)
+    }
+}
+
+impl<W: Write, T: ::serde::Serialize> Serialize<W> for SerdeBuf<T> {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        serde_buf::ser(&self.0, writer)
+    }
+}
+
+/// Module for `#[xmlib(serde)]` fields, see [`SerdeBuf`].
+pub mod serde_buf {
+    use std::io::Write;
+
+    use super::{ScalarDeserializer, ScalarSerializer};
+    use crate::ser::XmlWriter;
+
+    /// Deserializes the scalar value from the text.
+    pub fn de_buf<T: ::serde::de::DeserializeOwned>(buf: &[u8]) -> Result<T, crate::Error> {
+        T::deserialize(ScalarDeserializer { buf }).map_err(|e| crate::Error {
+            ty_name: String::from(std::any::type_name::<T>()),
+            kind: e.kind,
+        })
+    }
+
+    /// Writes the scalar value as escaped text.
+    pub fn ser<T: ::serde::Serialize, W: Write>(
+        value: &T,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), crate::ser::Error> {
+        value.serialize(ScalarSerializer { writer })?;
+        Ok(())
+    }
+}
+
+// Reads a scalar value from the text of an attribute or element
+struct ScalarDeserializer<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> ScalarDeserializer<'a> {
+    fn as_str(&self) -> Result<&'a str, crate::Error> {
+        std::str::from_utf8(self.buf).map_err(|e| crate::Error {
+            ty_name: String::from("str"),
+            kind: crate::ErrorKind::InvalidType(e.to_string()),
+        })
+    }
+}
+
+macro_rules! de_primitives {
+    ($($method:ident => $visit:ident($t:ty);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, crate::Error> {
+                visitor.$visit(<$t as DeserializeBuf>::de_buf(self.buf)?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> serde_de::Deserializer<'de> for ScalarDeserializer<'a> {
+    type Error = crate::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, crate::Error> {
+        visitor.visit_str(self.as_str()?)
+    }
+
+    de_primitives! {
+        deserialize_bool => visit_bool(bool);
+        deserialize_i8 => visit_i8(i8);
+        deserialize_i16 => visit_i16(i16);
+        deserialize_i32 => visit_i32(i32);
+        deserialize_i64 => visit_i64(i64);
+        deserialize_i128 => visit_i128(i128);
+        deserialize_u8 => visit_u8(u8);
+        deserialize_u16 => visit_u16(u16);
+        deserialize_u32 => visit_u32(u32);
+        deserialize_u64 => visit_u64(u64);
+        deserialize_u128 => visit_u128(u128);
+        deserialize_f32 => visit_f32(f32);
+        deserialize_f64 => visit_f64(f64);
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, crate::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, crate::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, crate::Error> {
+        visitor.visit_enum(self.as_str()?.into_deserializer())
+    }
+
+    ::serde::forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
@@ -1,10 +1,44 @@
 //! Deserialize rust datastructures into XML data.
 
+use std::borrow::Cow;
+
 pub use crate::{Error, ErrorKind};
 
 /// Wrapper for [`quick_xml::Reader`] but adds and specialized some methods to improve performance.
 pub struct XmlReader<R: std::io::BufRead> {
     reader: quick_xml::Reader<R>,
+    /// Name of the empty element whose end wasn't returned yet.
+    pending_end: Option<Vec<u8>>,
+    /// The complete input if the reader reads from a slice, used to borrow from it.
+    input: Option<R>,
+    /// Number of currently open elements.
+    depth: usize,
+    /// Maximal number of open elements while deserializing, see [`XmlReader::with_max_depth`].
+    max_depth: usize,
+    /// Namespace bindings `(depth, prefix, uri)` of the currently open elements.
+    namespaces: Vec<(usize, Vec<u8>, Vec<u8>)>,
+    /// Buffers which can be reused, see [`XmlReader::take_buf`].
+    buffers: Vec<Vec<u8>>,
+}
+
+/// The namespace of the XML Schema instance attributes like `xsi:nil`.
+pub const XSI_NAMESPACE: &[u8] = b"http://www.w3.org/2001/XMLSchema-instance";
+
+/// The default maximal nesting depth of elements, see [`XmlReader::with_max_depth`].
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// The UTF-8 encoded byte order mark.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+// Consumes a leading byte order mark of the input.
+//
+// Errors are ignored here, they are returned again by the next read.
+fn skip_bom<R: std::io::BufRead>(reader: &mut R) {
+    if let Ok(buf) = reader.fill_buf() {
+        if buf.starts_with(UTF8_BOM) {
+            reader.consume(UTF8_BOM.len());
+        }
+    }
 }
 
 impl<'a> XmlReader<std::io::BufReader<zip::read::ZipFile<'a>>> {
@@ -16,12 +50,82 @@ impl<'a> XmlReader<std::io::BufReader<zip::read::ZipFile<'a>>> {
     }
 }
 
+impl<'a> XmlReader<std::io::BufReader<&'a [u8]>> {
+    /// Creates a new [`XmlReader`] from a byte slice.
+    ///
+    /// Use [`XmlReader::from_slice`] to borrow from the input.
+    pub fn from_bytes(input: &'a [u8]) -> Self {
+        Self::new(std::io::BufReader::new(input))
+    }
+
+    /// Creates a new [`XmlReader`] from a string slice.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &'a str) -> Self {
+        Self::from_bytes(input.as_bytes())
+    }
+}
+
+impl<'a> XmlReader<&'a [u8]> {
+    /// Creates a new [`XmlReader`] reading directly from a byte slice.
+    ///
+    /// In contrast to [`XmlReader::from_bytes`] the reader can borrow from the input,
+    /// see [`XmlReader::borrow_input`].
+    pub fn from_slice(input: &'a [u8]) -> Self {
+        let input = input.strip_prefix(UTF8_BOM).unwrap_or(input);
+        let mut reader = Self::new(input);
+        reader.input = Some(input);
+        reader
+    }
+
+    /// Reads the next event without copying it into a buffer,
+    /// see [`quick_xml::Reader::read_event_unbuffered`].
+    ///
+    /// Empty elements are handled like in [`XmlReader::read_event`].
+    pub fn read_event_unbuffered(&mut self) -> quick_xml::Result<quick_xml::events::Event<'a>> {
+        use quick_xml::events::{BytesEnd, Event};
+
+        if let Some(name) = self.pending_end.take() {
+            self.leave();
+            return Ok(Event::End(BytesEnd::owned(name)));
+        }
+        let event = match self.reader.read_event_unbuffered() {
+            Ok(Event::Empty(e)) => {
+                self.pending_end = Some(e.name().to_vec());
+                Ok(Event::Start(e))
+            }
+            event => event,
+        };
+        self.track(&event);
+        event
+    }
+
+    /// Extends the lifetime of `part` to the lifetime of the input if it was borrowed from it.
+    ///
+    /// This is only possible for events read with [`XmlReader::read_event_unbuffered`] from a
+    /// reader created by [`XmlReader::from_slice`].
+    /// Otherwise the data is copied.
+    pub fn borrow_input(&self, part: Cow<'_, [u8]>) -> Cow<'a, [u8]> {
+        if let (Some(input), Cow::Borrowed(part)) = (self.input, &part) {
+            let start = input.as_ptr() as usize;
+            let part_start = part.as_ptr() as usize;
+            if part_start >= start && part_start + part.len() <= start + input.len() {
+                let offset = part_start - start;
+                return Cow::Borrowed(&input[offset..offset + part.len()]);
+            }
+        }
+        Cow::Owned(part.into_owned())
+    }
+}
+
 impl<R: std::io::BufRead> XmlReader<R> {
     /// Creates a new [`XmlReader`] from a [`std::io::BufRead`].
     ///
+    /// A leading UTF-8 byte order mark is skipped.
+    ///
     /// To speed the reading up most checks from `quick_xml` are disabled.
     /// If you want to enable them, consider using [`XmlReader::from_xml_reader`].
-    pub fn new(reader: R) -> Self {
+    pub fn new(mut reader: R) -> Self {
+        skip_bom(&mut reader);
         let mut reader = quick_xml::Reader::from_reader(reader);
         reader
             .check_end_names(false)
@@ -31,12 +135,261 @@ impl<R: std::io::BufRead> XmlReader<R> {
         Self::from_xml_reader(reader)
     }
 
+    /// Creates a new [`XmlReader`] from a [`std::io::BufRead`] which checks that the names of end
+    /// tags match the names of the start tags.
+    ///
+    /// Mismatched tags like `<a><b></a>` are reported as
+    /// [`quick_xml::Error::EndEventMismatch`]. This is slower than [`XmlReader::new`],
+    /// which tolerates them.
+    pub fn new_checked(reader: R) -> Self {
+        let mut reader = Self::new(reader);
+        reader.reader.check_end_names(true);
+        reader
+    }
+
     /// Creates a new [`XmlReader`] from a [`quick_xml::Reader`].
     ///
     /// Consider using [`XmlReader::new`] instead, if you don't want to customize the
     /// [`quick_xml::Reader`].
     pub fn from_xml_reader(reader: quick_xml::Reader<R>) -> Self {
-        Self { reader }
+        Self {
+            reader,
+            pending_end: None,
+            input: None,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            namespaces: Vec::new(),
+            buffers: Vec::new(),
+        }
+    }
+
+    /// Sets the maximal number of nested elements which may be deserialized, which is
+    /// [`DEFAULT_MAX_DEPTH`] by default.
+    ///
+    /// Every child is deserialized by a recursive call, so deeply nested documents would overflow
+    /// the stack otherwise. Deeper elements cause [`ErrorKind::DepthLimitExceeded`].
+    ///
+    /// # Example
+    /// ```
+    /// use xmlib::de::XmlReader;
+    ///
+    /// let reader = XmlReader::new("<a><a><a/></a></a>".as_bytes()).with_max_depth(2);
+    /// let result: Result<xmlib::Element, _> = xmlib::de::deserialize_single_struct(reader);
+    /// assert!(matches!(
+    ///     result.unwrap_err().kind,
+    ///     xmlib::ErrorKind::DepthLimitExceeded(2)
+    /// ));
+    /// ```
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Checks that the current element isn't nested deeper than allowed by
+    /// [`XmlReader::with_max_depth`].
+    ///
+    /// Implementations of [`DeserializeElement::de`] which deserialize children should call this
+    /// before reading them.
+    #[inline]
+    pub fn check_depth(&self, name: &[u8]) -> Result<(), Error> {
+        if self.depth > self.max_depth {
+            return Err(Error {
+                ty_name: String::from_utf8_lossy(name).to_string(),
+                kind: ErrorKind::DepthLimitExceeded(self.max_depth),
+            });
+        }
+        Ok(())
+    }
+
+    /// Takes an empty buffer from the reader, allocating a new one only if none is left.
+    ///
+    /// Return it with [`XmlReader::recycle_buf`] when you are done, so that the
+    /// [`DeserializeElement::de`] calls of the following elements can reuse it instead of
+    /// allocating their own.
+    #[inline]
+    pub fn take_buf(&mut self) -> Vec<u8> {
+        self.buffers.pop().unwrap_or_else(|| Vec::with_capacity(64))
+    }
+
+    /// Clears the buffer and gives it back to the reader, see [`XmlReader::take_buf`].
+    #[inline]
+    pub fn recycle_buf(&mut self, mut buf: Vec<u8>) {
+        buf.clear();
+        self.buffers.push(buf);
+    }
+
+    /// Reads the next event, see [`quick_xml::Reader::read_event`].
+    ///
+    /// Empty elements (`<name/>`) are always returned as [`Event::Start`] followed by
+    /// [`Event::End`], independent of [`quick_xml::Reader::expand_empty_elements`].
+    ///
+    /// [`Event::Start`]: quick_xml::events::Event::Start
+    /// [`Event::End`]: quick_xml::events::Event::End
+    pub fn read_event<'b>(
+        &mut self,
+        buf: &'b mut Vec<u8>,
+    ) -> quick_xml::Result<quick_xml::events::Event<'b>> {
+        use quick_xml::events::{BytesEnd, Event};
+
+        if let Some(name) = self.pending_end.take() {
+            self.leave();
+            return Ok(Event::End(BytesEnd::owned(name)));
+        }
+        let event = match self.reader.read_event(buf) {
+            Ok(Event::Empty(e)) => {
+                self.pending_end = Some(e.name().to_vec());
+                Ok(Event::Start(e))
+            }
+            event => event,
+        };
+        self.track(&event);
+        event
+    }
+
+    /// Reads until the end of the element, see [`quick_xml::Reader::read_to_end`].
+    ///
+    /// This respects the end of empty elements returned by [`XmlReader::read_event`].
+    pub fn read_to_end<K: AsRef<[u8]>>(
+        &mut self,
+        end: K,
+        buf: &mut Vec<u8>,
+    ) -> quick_xml::Result<()> {
+        match self.pending_end.take() {
+            Some(name) if name == end.as_ref() => {}
+            _ => self.reader.read_to_end(end, buf)?,
+        }
+        self.leave();
+        Ok(())
+    }
+
+    /// Resolves the namespace uri of the qualified name of an element,
+    /// e.g. `a:rect` inside of `<root xmlns:a="uri">` is resolved to `uri`.
+    ///
+    /// Only the namespaces of the elements read by [`XmlReader`] are taken into account.
+    pub fn resolve_namespace(&self, qname: &[u8]) -> Option<&[u8]> {
+        let prefix = match memchr::memchr(b':', qname) {
+            Some(i) => &qname[..i],
+            None => &[][..],
+        };
+        if prefix == b"xml" {
+            return Some(b"http://www.w3.org/XML/1998/namespace");
+        }
+        self.namespaces
+            .iter()
+            .rev()
+            .find(|(_, p, _)| p == prefix)
+            .map(|(_, _, uri)| &uri[..])
+            // `xmlns=""` removes the default namespace
+            .filter(|uri| !uri.is_empty())
+    }
+
+    /// Resolves the prefix of a qualified name to its namespace uri, e.g. `r:foo` inside of
+    /// `<root xmlns:r="uri">` is resolved to `uri`.
+    ///
+    /// Names without a prefix are resolved to `None`, which is their namespace as attribute. Use
+    /// [`XmlReader::resolve_namespace`] to get the default namespace of elements instead.
+    ///
+    /// Returns [`ErrorKind::UnboundPrefix`] if the prefix isn't declared by the current element
+    /// or one of its parents.
+    ///
+    /// # Example
+    /// ```
+    /// use quick_xml::events::Event;
+    /// use xmlib::de::XmlReader;
+    ///
+    /// let mut reader = XmlReader::new(r#"<root xmlns:r="urn:r"><r:foo/></root>"#.as_bytes());
+    /// let mut buf = Vec::new();
+    /// loop {
+    ///     match reader.read_event(&mut buf).unwrap() {
+    ///         Event::Start(e) if e.name() == b"r:foo" => {
+    ///             assert_eq!(reader.resolve_prefix(e.name()).unwrap(), Some(&b"urn:r"[..]));
+    ///             break;
+    ///         }
+    ///         _ => {}
+    ///     }
+    /// }
+    /// assert_eq!(reader.resolve_prefix(b"foo").unwrap(), None);
+    /// assert!(reader.resolve_prefix(b"x:foo").is_err());
+    /// ```
+    pub fn resolve_prefix(&self, qname: &[u8]) -> Result<Option<&[u8]>, Error> {
+        let prefix = match memchr::memchr(b':', qname) {
+            Some(i) => &qname[..i],
+            None => return Ok(None),
+        };
+        match prefix {
+            b"xml" => return Ok(Some(b"http://www.w3.org/XML/1998/namespace")),
+            b"xmlns" => return Ok(Some(b"http://www.w3.org/2000/xmlns/")),
+            _ => {}
+        }
+        self.namespaces
+            .iter()
+            .rev()
+            .find(|(_, p, _)| p == prefix)
+            .map(|(_, _, uri)| &uri[..])
+            .filter(|uri| !uri.is_empty())
+            .map(Some)
+            .ok_or_else(|| Error {
+                ty_name: String::from_utf8_lossy(qname).to_string(),
+                kind: ErrorKind::UnboundPrefix(String::from_utf8_lossy(prefix).to_string()),
+            })
+    }
+
+    /// Checks whether the qualified name of an element belongs to the namespace.
+    ///
+    /// Every element belongs to the namespace `None`.
+    #[inline]
+    pub fn is_in_namespace(&self, qname: &[u8], namespace: Option<&[u8]>) -> bool {
+        namespace.map_or(true, |namespace| {
+            self.resolve_namespace(qname) == Some(namespace)
+        })
+    }
+
+    /// Checks whether the element is marked as absent with `xsi:nil="true"`.
+    ///
+    /// The prefix of the attribute must be bound to [`XSI_NAMESPACE`] by the element itself or
+    /// one of its parents.
+    pub fn is_nil(&self, start: &quick_xml::events::BytesStart) -> bool {
+        start.attributes().flatten().any(|attr| {
+            matches!(memchr::memchr(b':', attr.key), Some(i) if &attr.key[i + 1..] == b"nil")
+                && self.resolve_namespace(attr.key) == Some(XSI_NAMESPACE)
+                && matches!(&*attr.value, b"true" | b"1")
+        })
+    }
+
+    // Updates the depth and namespaces after an event
+    fn track(&mut self, event: &quick_xml::Result<quick_xml::events::Event>) {
+        use quick_xml::events::Event;
+
+        match event {
+            Ok(Event::Start(e)) => {
+                self.depth += 1;
+                if memchr::memmem::find(e.attributes_raw(), b"xmlns").is_none() {
+                    return;
+                }
+                for attr in e.attributes().flatten() {
+                    let prefix = match attr.key {
+                        b"xmlns" => &[][..],
+                        key if key.starts_with(b"xmlns:") => &key[6..],
+                        _ => continue,
+                    };
+                    let uri = match attr.unescaped_value() {
+                        Ok(uri) => uri.into_owned(),
+                        Err(_) => attr.value.into_owned(),
+                    };
+                    self.namespaces.push((self.depth, prefix.to_vec(), uri));
+                }
+            }
+            Ok(Event::End(_)) => self.leave(),
+            _ => {}
+        }
+    }
+
+    // Closes the current element
+    fn leave(&mut self) {
+        while matches!(self.namespaces.last(), Some((depth, _, _)) if *depth >= self.depth) {
+            self.namespaces.pop();
+        }
+        self.depth = self.depth.saturating_sub(1);
     }
 
     /// Specialized version from [`quick_xml::Reader::read_text`] because it took around 24 % of
@@ -64,26 +417,43 @@ impl<R: std::io::BufRead> XmlReader<R> {
         s
     }
 
-    /// Specialized version from [`quick_xml::Reader::read_text`]
-    /// because it took noticable CPU time for a microbenchmark.
+    /// Reads the text until the end tag `end` like [`quick_xml::Reader::read_text`].
     ///
-    /// See it's documentation for more information.
+    /// The text may be split into multiple events, e.g. `abc<![CDATA[&]]>def`. Text is unescaped,
+    /// CDATA sections are taken verbatim and comments are skipped. Child elements are an error.
+    ///
+    /// `other_buf` is used to collect the text. Its allocation is moved into the returned string
+    /// and can be given back with [`String::into_bytes`] to reuse it for the next text.
     pub fn read_text<K: AsRef<[u8]>>(
         &mut self,
         end: K,
         buf: &mut Vec<u8>,
         other_buf: &mut Vec<u8>,
     ) -> Result<String, ErrorKind> {
-        let bytes = match self.read_text_bytes(end, buf, other_buf) {
-            Ok(bytes) => bytes,
-            Err(e) => return Err(ErrorKind::XmlError(e)),
-        };
-        let unescaped_bytes = match quick_xml::escape::unescape(&bytes) {
-            Ok(bytes) => bytes,
-            Err(e) => return Err(ErrorKind::XmlError(quick_xml::Error::EscapeError(e))),
-        };
+        use quick_xml::events::Event;
+
+        other_buf.clear();
+        loop {
+            buf.clear();
+            match self.read_event(buf) {
+                Ok(Event::Text(e)) => match quick_xml::escape::unescape(&e) {
+                    Ok(unescaped) => other_buf.extend_from_slice(&unescaped),
+                    Err(e) => return Err(ErrorKind::XmlError(quick_xml::Error::EscapeError(e))),
+                },
+                Ok(Event::CData(e)) => other_buf.extend_from_slice(&e),
+                Ok(Event::Comment(_)) => {}
+                Ok(Event::End(ref e)) if e.name() == end.as_ref() => break,
+                Err(e) => return Err(ErrorKind::XmlError(e)),
+                Ok(Event::Eof) => {
+                    return Err(ErrorKind::XmlError(quick_xml::Error::UnexpectedEof(
+                        "Text".to_string(),
+                    )))
+                }
+                _ => return Err(ErrorKind::XmlError(quick_xml::Error::TextNotFound)),
+            }
+        }
 
-        String::from_utf8(unescaped_bytes.to_vec()).map_err(ErrorKind::FromUtf8Error)
+        String::from_utf8(std::mem::take(other_buf)).map_err(ErrorKind::FromUtf8Error)
     }
 }
 
@@ -101,6 +471,17 @@ impl<R: std::io::BufRead> std::ops::DerefMut for XmlReader<R> {
     }
 }
 
+/// The name of an element independent of the reader type.
+///
+/// This is implemented by the derive in addition to [`DeserializeElement`], but handwritten
+/// implementations of [`DeserializeElement`] don't need to implement it.
+pub trait XmlName {
+    /// The name of the element.
+    ///
+    /// The slice should be valid utf-8, but isn't currently required to do so.
+    const NAME: &'static [u8];
+}
+
 /// Deserialize an element.
 pub trait DeserializeElement<R: std::io::BufRead>
 where
@@ -112,6 +493,15 @@ where
     /// The slice should be valid utf-8, but isn't currently required to do so.
     fn name() -> &'static [u8];
 
+    /// Checks whether an element with the given local name can be deserialized as this type.
+    ///
+    /// Defaults to comparing with [`DeserializeElement::name`]. This is overridden by types
+    /// matching multiple elements, e.g. tagged enums.
+    #[inline]
+    fn accepts_name(local_name: &[u8]) -> bool {
+        local_name == <Self as DeserializeElement<R>>::name()
+    }
+
     /// Gets the name of the element as string.
     ///
     /// This performs an allocation and converts to the string lossily.
@@ -120,8 +510,92 @@ where
         String::from_utf8_lossy(<Self as DeserializeElement<R>>::name()).to_string()
     }
 
+    /// Gets the namespace uri of the element.
+    ///
+    /// Elements are only matched if they belong to this namespace, see
+    /// [`XmlReader::is_in_namespace`]. `None` matches all elements.
+    #[inline]
+    fn namespace() -> Option<&'static [u8]> {
+        None
+    }
+
     /// Deserializes the element from the reader.
     fn de(reader: &mut XmlReader<R>, start: quick_xml::events::BytesStart) -> Result<Self, Error>;
+
+    /// Deserializes the first element with the name and namespace of this type from the input.
+    ///
+    /// All other elements before it are skipped, so the element doesn't have to be the root.
+    /// The input is read buffered, use [`from_str`] to borrow from a slice.
+    ///
+    /// ```
+    /// use xmlib::de::DeserializeElement;
+    /// use xmlib_derive::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Rectangle {
+    ///     width: u32,
+    ///     height: u32,
+    /// }
+    ///
+    /// let serialized = r#"<shapes><circle/><rectangle width="13" height="42"/></shapes>"#;
+    /// let deserialized = Rectangle::de_from_reader(serialized.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(deserialized.width, 13);
+    /// assert_eq!(deserialized.height, 42);
+    /// ```
+    fn de_from_reader(input: R) -> Result<Self, Error> {
+        use quick_xml::events::Event;
+
+        let mut reader = XmlReader::new(input);
+        let mut buf = Vec::with_capacity(32);
+        loop {
+            buf.clear();
+            let event = reader.read_event(&mut buf).map_err(|e| Error {
+                ty_name: <Self as DeserializeElement<R>>::name_string(),
+                kind: e.into(),
+            })?;
+            match event {
+                Event::Start(e)
+                    if <Self as DeserializeElement<R>>::accepts_name(e.local_name())
+                        && reader.is_in_namespace(
+                            e.name(),
+                            <Self as DeserializeElement<R>>::namespace(),
+                        ) =>
+                {
+                    return <Self as DeserializeElement<R>>::de(&mut reader, e);
+                }
+                Event::Eof => {
+                    return Err(Error {
+                        ty_name: <Self as DeserializeElement<R>>::name_string(),
+                        kind: ErrorKind::XmlError(quick_xml::Error::UnexpectedEof(String::from(
+                            "no element found",
+                        ))),
+                    })
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Deserialize a node of mixed content, i.e. either text or one of several child elements.
+///
+/// This is implemented by the derive for enums annotated with `#[xmlib(mixed)]`.
+pub trait DeserializeMixed<R: std::io::BufRead>
+where
+    Self: Sized,
+{
+    /// Deserializes the (unescaped) text between the child elements.
+    fn de_text(text: &[u8]) -> Result<Self, Error>;
+
+    /// Checks whether the child element can be deserialized as this type.
+    fn accepts(reader: &XmlReader<R>, start: &quick_xml::events::BytesStart) -> bool;
+
+    /// Deserializes the child element, see [`DeserializeElement::de`].
+    fn de_child(
+        reader: &mut XmlReader<R>,
+        start: quick_xml::events::BytesStart,
+    ) -> Result<Self, Error>;
 }
 
 /// Deserialize an attribute/ text.
@@ -133,6 +607,67 @@ where
     fn de_buf(buf: &[u8]) -> Result<Self, Error>;
 }
 
+/// Deserialize an attribute which may borrow from the input.
+///
+/// This is implemented for all types implementing [`DeserializeBuf`] as well as for
+/// [`Cow<str>`] and `&str`.
+///
+/// [`Cow<str>`] doesn't implement [`DeserializeBuf`], not even `Cow<'static, str>`, because that
+/// would overlap with its implementation of this trait. Fields of structs without a lifetime can
+/// use `String` or `Box<str>` instead.
+pub trait DeserializeBufBorrowed<'de>
+where
+    Self: Sized,
+{
+    /// Deserializes the value from the given bytes.
+    fn de_buf_borrowed(buf: Cow<'de, [u8]>) -> Result<Self, Error>;
+}
+
+impl<'de, T> DeserializeBufBorrowed<'de> for T
+where
+    T: DeserializeBuf,
+{
+    #[inline]
+    fn de_buf_borrowed(buf: Cow<'de, [u8]>) -> Result<Self, Error> {
+        T::de_buf(&buf)
+    }
+}
+
+impl<'de> DeserializeBufBorrowed<'de> for Cow<'de, str> {
+    #[inline]
+    fn de_buf_borrowed(buf: Cow<'de, [u8]>) -> Result<Self, Error> {
+        let buf = match buf {
+            Cow::Borrowed(buf) => match std::str::from_utf8(buf) {
+                Ok(s) => return Ok(Cow::Borrowed(s)),
+                // the conversion below fails as well and creates the error
+                Err(_) => buf.to_vec(),
+            },
+            Cow::Owned(buf) => buf,
+        };
+        String::from_utf8(buf).map(Cow::Owned).map_err(|e| Error {
+            ty_name: String::from("Cow<str>"),
+            kind: ErrorKind::FromUtf8Error(e),
+        })
+    }
+}
+
+impl<'de> DeserializeBufBorrowed<'de> for &'de str {
+    #[inline]
+    fn de_buf_borrowed(buf: Cow<'de, [u8]>) -> Result<Self, Error> {
+        match <Cow<'de, str>>::de_buf_borrowed(buf)? {
+            Cow::Borrowed(s) => Ok(s),
+            Cow::Owned(s) => Err(Error {
+                ty_name: String::from("&str"),
+                kind: ErrorKind::InvalidType(format!("can't borrow unescaped value {}", s)),
+            }),
+        }
+    }
+}
+
+impl<T: XmlName> XmlName for Vec<T> {
+    const NAME: &'static [u8] = T::NAME;
+}
+
 impl<R: std::io::BufRead, T> DeserializeElement<R> for Vec<T>
 where
     T: DeserializeElement<R>,
@@ -142,6 +677,16 @@ where
         T::name()
     }
 
+    #[inline]
+    fn accepts_name(local_name: &[u8]) -> bool {
+        T::accepts_name(local_name)
+    }
+
+    #[inline]
+    fn namespace() -> Option<&'static [u8]> {
+        T::namespace()
+    }
+
     #[inline]
     fn de(
         _reader: &mut XmlReader<R>,
@@ -156,6 +701,10 @@ where
     }
 }
 
+impl<T: XmlName> XmlName for Option<T> {
+    const NAME: &'static [u8] = T::NAME;
+}
+
 impl<R: std::io::BufRead, T> DeserializeElement<R> for Option<T>
 where
     T: DeserializeElement<R>,
@@ -165,12 +714,26 @@ where
         T::name()
     }
 
+    #[inline]
+    fn accepts_name(local_name: &[u8]) -> bool {
+        T::accepts_name(local_name)
+    }
+
+    #[inline]
+    fn namespace() -> Option<&'static [u8]> {
+        T::namespace()
+    }
+
     #[inline]
     fn de(reader: &mut XmlReader<R>, start: quick_xml::events::BytesStart) -> Result<Self, Error> {
         T::de(reader, start).map(Some)
     }
 }
 
+impl<T: XmlName> XmlName for Box<T> {
+    const NAME: &'static [u8] = T::NAME;
+}
+
 impl<R: std::io::BufRead, T> DeserializeElement<R> for Box<T>
 where
     T: DeserializeElement<R>,
@@ -180,12 +743,117 @@ where
         T::name()
     }
 
+    #[inline]
+    fn accepts_name(local_name: &[u8]) -> bool {
+        T::accepts_name(local_name)
+    }
+
+    #[inline]
+    fn namespace() -> Option<&'static [u8]> {
+        T::namespace()
+    }
+
     #[inline]
     fn de(reader: &mut XmlReader<R>, start: quick_xml::events::BytesStart) -> Result<Self, Error> {
         T::de(reader, start).map(Box::new)
     }
 }
 
+impl<T: XmlName> XmlName for std::rc::Rc<T> {
+    const NAME: &'static [u8] = T::NAME;
+}
+
+impl<R: std::io::BufRead, T> DeserializeElement<R> for std::rc::Rc<T>
+where
+    T: DeserializeElement<R>,
+{
+    #[inline]
+    fn name() -> &'static [u8] {
+        T::name()
+    }
+
+    #[inline]
+    fn accepts_name(local_name: &[u8]) -> bool {
+        T::accepts_name(local_name)
+    }
+
+    #[inline]
+    fn namespace() -> Option<&'static [u8]> {
+        T::namespace()
+    }
+
+    #[inline]
+    fn de(reader: &mut XmlReader<R>, start: quick_xml::events::BytesStart) -> Result<Self, Error> {
+        T::de(reader, start).map(std::rc::Rc::new)
+    }
+}
+
+impl<T: XmlName> XmlName for std::sync::Arc<T> {
+    const NAME: &'static [u8] = T::NAME;
+}
+
+impl<R: std::io::BufRead, T> DeserializeElement<R> for std::sync::Arc<T>
+where
+    T: DeserializeElement<R>,
+{
+    #[inline]
+    fn name() -> &'static [u8] {
+        T::name()
+    }
+
+    #[inline]
+    fn accepts_name(local_name: &[u8]) -> bool {
+        T::accepts_name(local_name)
+    }
+
+    #[inline]
+    fn namespace() -> Option<&'static [u8]> {
+        T::namespace()
+    }
+
+    #[inline]
+    fn de(reader: &mut XmlReader<R>, start: quick_xml::events::BytesStart) -> Result<Self, Error> {
+        T::de(reader, start).map(std::sync::Arc::new)
+    }
+}
+
+/// Deserializes a value with its [`FromStr`](std::str::FromStr) implementation.
+///
+/// Returns [`ErrorKind::InvalidType`] containing the raw value if the input isn't valid utf-8 or
+/// can't be parsed.
+pub(crate) fn de_from_str<T: std::str::FromStr>(buf: &[u8], ty_name: &str) -> Result<T, Error> {
+    std::str::from_utf8(buf)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| Error {
+            ty_name: String::from(ty_name),
+            kind: ErrorKind::InvalidType(String::from_utf8_lossy(buf).to_string()),
+        })
+}
+
+/// Deserializes a value from a string with `parse`, e.g. for the types of optional dependencies.
+///
+/// Returns [`ErrorKind::InvalidType`] containing the raw value and the reason if the input isn't
+/// valid utf-8 or can't be parsed.
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub(crate) fn de_with_parser<T, E: std::fmt::Display>(
+    buf: &[u8],
+    ty_name: &str,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<T, Error> {
+    let invalid = |reason: String| Error {
+        ty_name: String::from(ty_name),
+        kind: ErrorKind::InvalidType(format!(
+            "invalid {} {}: {}",
+            ty_name,
+            String::from_utf8_lossy(buf),
+            reason
+        )),
+    };
+    let s = std::str::from_utf8(buf).map_err(|e| invalid(e.to_string()))?;
+    parse(s).map_err(|e| invalid(e.to_string()))
+}
+
 impl DeserializeBuf for String {
     #[inline]
     fn de_buf(buf: &[u8]) -> Result<Self, Error> {
@@ -196,6 +864,31 @@ impl DeserializeBuf for String {
     }
 }
 
+/// Only valid utf-8 is accepted, since xml text is always utf-8.
+impl DeserializeBuf for std::path::PathBuf {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        String::from_utf8(buf.to_vec())
+            .map(Self::from)
+            .map_err(|e| Error {
+                ty_name: String::from("PathBuf"),
+                kind: ErrorKind::FromUtf8Error(e),
+            })
+    }
+}
+
+impl DeserializeBuf for std::ffi::OsString {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        String::from_utf8(buf.to_vec())
+            .map(Self::from)
+            .map_err(|e| Error {
+                ty_name: String::from("OsString"),
+                kind: ErrorKind::FromUtf8Error(e),
+            })
+    }
+}
+
 impl<T> DeserializeBuf for Option<T>
 where
     T: DeserializeBuf,
@@ -206,6 +899,27 @@ where
     }
 }
 
+/// Accepts only empty values, e.g. `<flag/>`.
+///
+/// Use `Option<()>` for a child element annotated with `#[xmlib(element)]` to model the presence
+/// of an empty element.
+impl DeserializeBuf for () {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        if buf.is_empty() {
+            Ok(())
+        } else {
+            Err(Error {
+                ty_name: String::from("()"),
+                kind: ErrorKind::InvalidType(format!(
+                    "expected empty value, got {}",
+                    String::from_utf8_lossy(buf)
+                )),
+            })
+        }
+    }
+}
+
 impl DeserializeBuf for bool {
     #[inline]
     fn de_buf(buf: &[u8]) -> Result<Self, Error> {
@@ -220,13 +934,171 @@ impl DeserializeBuf for bool {
     }
 }
 
-impl<T> DeserializeBuf for Box<T>
-where
-    T: DeserializeBuf,
-{
-    #[inline]
-    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
-        T::de_buf(buf).map(Box::new)
+/// Deserializes a [`bool`] from the spellings of sloppy producers.
+///
+/// In addition to `0`, `1`, `true` and `false` this accepts `yes`, `no`, `y`, `n`, `on` and `off`,
+/// all compared case-insensitively. This is used for fields annotated with
+/// `#[xmlib(lenient_bool)]`.
+///
+/// ```
+/// assert_eq!(xmlib::de::de_bool_lenient(b"Yes").unwrap(), true);
+/// assert_eq!(xmlib::de::de_bool_lenient(b"OFF").unwrap(), false);
+/// assert!(xmlib::de::de_bool_lenient(b"maybe").is_err());
+/// ```
+pub fn de_bool_lenient(buf: &[u8]) -> Result<bool, Error> {
+    const TRUE: &[&[u8]] = &[b"1", b"true", b"yes", b"y", b"on"];
+    const FALSE: &[&[u8]] = &[b"0", b"false", b"no", b"n", b"off"];
+
+    if TRUE.iter().any(|v| v.eq_ignore_ascii_case(buf)) {
+        Ok(true)
+    } else if FALSE.iter().any(|v| v.eq_ignore_ascii_case(buf)) {
+        Ok(false)
+    } else {
+        Err(Error {
+            ty_name: String::from("bool"),
+            kind: ErrorKind::InvalidType(String::from_utf8_lossy(buf).to_string()),
+        })
+    }
+}
+
+impl<T> DeserializeBuf for Box<T>
+where
+    T: DeserializeBuf,
+{
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        T::de_buf(buf).map(Box::new)
+    }
+}
+
+impl<T> DeserializeBuf for std::rc::Rc<T>
+where
+    T: DeserializeBuf,
+{
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        T::de_buf(buf).map(std::rc::Rc::new)
+    }
+}
+
+impl<T> DeserializeBuf for std::sync::Arc<T>
+where
+    T: DeserializeBuf,
+{
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        T::de_buf(buf).map(std::sync::Arc::new)
+    }
+}
+
+// `Cow<str>` is deserialized with `DeserializeBufBorrowed` instead, since it can borrow from the
+// input. `DeserializeBuf` can't be implemented for `Cow<'static, str>` either: every
+// `DeserializeBuf` type implements `DeserializeBufBorrowed<'de>`, which would overlap with the
+// borrowing implementation for `Cow<'de, str>` if `'de` is `'static`.
+macro_rules! impl_de_str_ptr {
+    ($($t:ty),+$(,)?) => {
+        $(
+            impl DeserializeBuf for $t {
+                #[inline]
+                fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+                    String::de_buf(buf).map(<$t>::from)
+                }
+            }
+        )+
+    };
+}
+
+impl_de_str_ptr!(Box<str>, std::rc::Rc<str>, std::sync::Arc<str>);
+
+/// Removes the attribute `key` from the start tag, e.g. the discriminant of enums with
+/// `#[xmlib(tag_attr = "...")]`.
+///
+/// Returns the raw value of the attribute, if it exists, and the start tag without it.
+///
+/// ```
+/// use xmlib::exports::events::BytesStart;
+///
+/// let start = BytesStart::borrowed(br#"node type="circle" r='1'"#, 4);
+/// let (value, start) = xmlib::de::split_attribute(&start, b"type").unwrap();
+/// assert_eq!(value.as_deref(), Some(&b"circle"[..]));
+/// assert_eq!(&*start, br#"node r="1""#);
+/// ```
+pub fn split_attribute(
+    start: &quick_xml::events::BytesStart,
+    key: &[u8],
+) -> Result<(Option<Vec<u8>>, quick_xml::events::BytesStart<'static>), quick_xml::Error> {
+    let mut value = None;
+    let mut rest = start.name().to_vec();
+    for attr in start.attributes() {
+        let attr = attr?;
+        if attr.key == key {
+            value = Some(attr.value.into_owned());
+            continue;
+        }
+        // keeps values containing the other quote valid
+        let quote = if attr.value.contains(&b'"') {
+            b'\''
+        } else {
+            b'"'
+        };
+        rest.push(b' ');
+        rest.extend_from_slice(attr.key);
+        rest.push(b'=');
+        rest.push(quote);
+        rest.extend_from_slice(&attr.value);
+        rest.push(quote);
+    }
+    let name_len = start.name().len();
+    Ok((value, quick_xml::events::BytesStart::owned(rest, name_len)))
+}
+
+/// Removes surrounding ASCII whitespace.
+///
+/// Attribute values are trimmed before deserializing if the field is annotated with
+/// `#[xmlib(trim)]`.
+///
+/// ```
+/// assert_eq!(xmlib::de::trim_whitespace(b" \t42\n"), b"42");
+/// assert_eq!(xmlib::de::trim_whitespace(b"  "), b"");
+/// ```
+#[inline]
+pub fn trim_whitespace(buf: &[u8]) -> &[u8] {
+    match (buf.first(), buf.last()) {
+        (Some(first), Some(last))
+            if !first.is_ascii_whitespace() && !last.is_ascii_whitespace() =>
+        {
+            buf
+        }
+        _ => {
+            let start = buf
+                .iter()
+                .position(|b| !b.is_ascii_whitespace())
+                .unwrap_or(buf.len());
+            let end = buf
+                .iter()
+                .rposition(|b| !b.is_ascii_whitespace())
+                .map_or(start, |i| i + 1);
+            &buf[start..end]
+        }
+    }
+}
+
+/// Removes surrounding ASCII whitespace and a `+` in front of a digit.
+///
+/// The integers of xml schema may have both, but they are only accepted when deserializing if
+/// the field is annotated with `#[xmlib(lenient)]`.
+///
+/// ```
+/// assert_eq!(xmlib::de::lenient_number(b" +42\n"), b"42");
+/// assert_eq!(xmlib::de::lenient_number(b"\t-7 "), b"-7");
+/// assert_eq!(xmlib::de::lenient_number(b"+-1"), b"+-1");
+/// ```
+#[inline]
+pub fn lenient_number(buf: &[u8]) -> &[u8] {
+    let buf = trim_whitespace(buf);
+    match buf {
+        [b'+', digit, ..] if digit.is_ascii_digit() => &buf[1..],
+        _ => buf,
     }
 }
 
@@ -235,7 +1107,7 @@ macro_rules! impl_de_num_signed {
         impl DeserializeBuf for $t {
             #[inline]
             fn de_buf(buf: &[u8]) -> Result<Self, Error> {
-                let (s, read) = atoi::FromRadix10Signed::from_radix_10_signed(buf);
+                let (s, read) = atoi::FromRadix10SignedChecked::from_radix_10_signed_checked(buf);
 
                 if read != buf.len() {
                     Err(Error {
@@ -244,7 +1116,13 @@ macro_rules! impl_de_num_signed {
                                                              read, buf.len(), String::from_utf8_lossy(buf))),
                     })
                 } else {
-                    Ok(s)
+                    s.ok_or_else(|| Error {
+                        ty_name: String::from(stringify!($t)),
+                        kind: ErrorKind::Overflow {
+                            ty: String::from(stringify!($t)),
+                            value: String::from_utf8_lossy(buf).to_string(),
+                        },
+                    })
                 }
             }
         }
@@ -259,7 +1137,7 @@ macro_rules! impl_de_num_unsigned {
         impl DeserializeBuf for $t {
             #[inline]
             fn de_buf(buf: &[u8]) -> Result<Self, Error> {
-                let (s, read) = atoi::FromRadix10::from_radix_10(&buf);
+                let (s, read) = atoi::FromRadix10Checked::from_radix_10_checked(buf);
 
                 if read != buf.len() {
                     Err(Error {
@@ -268,7 +1146,13 @@ macro_rules! impl_de_num_unsigned {
                                                              read, buf.len(), String::from_utf8_lossy(buf))),
                     })
                 } else {
-                    Ok(s)
+                    s.ok_or_else(|| Error {
+                        ty_name: String::from(stringify!($t)),
+                        kind: ErrorKind::Overflow {
+                            ty: String::from(stringify!($t)),
+                            value: String::from_utf8_lossy(buf).to_string(),
+                        },
+                    })
                 }
             }
         }
@@ -301,6 +1185,37 @@ impl_de_num_signed!(i8, i16, i32, i64, i128, isize);
 impl_de_num_unsigned!(u8, u16, u32, u64, u128, usize);
 impl_de_float!(f32, f64);
 
+macro_rules! impl_de_non_zero {
+    ($($t:ident($inner:ty)),+$(,)?) => {
+        $(
+            impl DeserializeBuf for std::num::$t {
+                #[inline]
+                fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+                    Self::new(<$inner>::de_buf(buf)?).ok_or_else(|| Error {
+                        ty_name: String::from(stringify!($t)),
+                        kind: ErrorKind::InvalidType(String::from("value must be non-zero")),
+                    })
+                }
+            }
+        )+
+    };
+}
+
+impl_de_non_zero!(
+    NonZeroU8(u8),
+    NonZeroU16(u16),
+    NonZeroU32(u32),
+    NonZeroU64(u64),
+    NonZeroU128(u128),
+    NonZeroUsize(usize),
+    NonZeroI8(i8),
+    NonZeroI16(i16),
+    NonZeroI32(i32),
+    NonZeroI64(i64),
+    NonZeroI128(i128),
+    NonZeroIsize(isize),
+);
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __const_concat {
@@ -332,6 +1247,17 @@ macro_rules! __const_concat {
 /// The outer type must be a newtype with a single field of type [`std::vec::Vec`]. The inner type
 /// must implement [`DeserializeElement`] and [`Serialize`](crate::ser::Serialize).
 ///
+/// Consider deriving the implementations with `#[xmlib(list)]` instead.
+///
+/// Use `ser_deser_vec!(Bar, b"bar", b"foo", count = b"count")` to write the number of children
+/// into the attribute `count`. If the attribute is present while deserializing, it must match the
+/// number of children.
+///
+/// Children are matched by their local name, so a namespace prefix like `<ns:foo/>` is ignored.
+/// Use `ser_deser_vec!(Bar, b"bar", inner = Foo)` to take the name of the children from
+/// [`DeserializeElement::accepts_name`] instead. This also only matches children in the
+/// [namespace](DeserializeElement::namespace) of `Foo`.
+///
 /// # Example
 /// ```
 /// use xmlib_derive::{Deserialize, Serialize};
@@ -361,21 +1287,89 @@ macro_rules! __const_concat {
 /// ```
 #[macro_export]
 macro_rules! ser_deser_vec {
+    ($name:ident, $tag_name:expr, inner = $inner:ty) => {
+        $crate::ser_deser_vec!(@inner $name, $tag_name, $inner, []);
+    };
+    ($name:ident, $tag_name:expr, inner = $inner:ty, count = $count_name:expr) => {
+        $crate::ser_deser_vec!(@inner $name, $tag_name, $inner, [$count_name]);
+    };
     ($name:ident, $tag_name:expr, $inner_tag_name:expr) => {
+        $crate::ser_deser_vec!(@tag $name, $tag_name, $inner_tag_name, []);
+    };
+    ($name:ident, $tag_name:expr, $inner_tag_name:expr, count = $count_name:expr) => {
+        $crate::ser_deser_vec!(@tag $name, $tag_name, $inner_tag_name, [$count_name]);
+    };
+    (@inner $name:ident, $tag_name:expr, $inner:ty, [$($count_name:expr)?]) => {
+        $crate::ser_deser_vec!(
+            @impl $name,
+            $tag_name,
+            <$inner as $crate::de::XmlName>::NAME,
+            |reader: &$crate::de::XmlReader<R>, e: &$crate::exports::events::BytesStart| {
+                <$inner as $crate::de::DeserializeElement<R>>::accepts_name(e.local_name())
+                    && reader.is_in_namespace(
+                        e.name(),
+                        <$inner as $crate::de::DeserializeElement<R>>::namespace(),
+                    )
+            },
+            [$($count_name)?]
+        );
+    };
+    (@tag $name:ident, $tag_name:expr, $inner_tag_name:expr, [$($count_name:expr)?]) => {
+        $crate::ser_deser_vec!(
+            @impl $name,
+            $tag_name,
+            $inner_tag_name,
+            |_: &$crate::de::XmlReader<R>, e: &$crate::exports::events::BytesStart| {
+                e.local_name() == $inner_tag_name
+            },
+            [$($count_name)?]
+        );
+    };
+    (@impl $name:ident, $tag_name:expr, $inner_tag_name:expr, $accepts:expr, [$($count_name:expr)?]) => {
         impl<W: std::io::Write> $crate::ser::Serialize<W> for $name {
             #[inline]
-            fn ser(&self, writer: &mut $crate::ser::XmlWriter<W>) -> std::io::Result<()> {
+            fn ser(
+                &self,
+                writer: &mut $crate::ser::XmlWriter<W>,
+            ) -> ::std::result::Result<(), $crate::ser::Error> {
                 const START: &[u8] = $crate::__const_concat!(b"<", $tag_name, b">");
-                writer.write_all(START)?;
+                // the start without `>` to allow adding attributes
+                writer.start_element()?;
+                writer.write_all(&START[..START.len() - 1])?;
+                $(
+                    let quote = writer.quote();
+                    writer.write_all(b" ")?;
+                    writer.write_all($count_name)?;
+                    writer.write_all(b"=")?;
+                    writer.write_all(quote)?;
+                    $crate::ser::Serialize::ser(&self.0.len(), writer)?;
+                    writer.write_all(quote)?;
+                )?
+                if self.0.is_empty() {
+                    writer.write_all(b"/>")?;
+                    writer.end_element(true)?;
+                    return Ok(());
+                }
+                writer.write_all(b">")?;
 
                 for inner in &self.0 {
                     inner.ser(writer)?;
                 }
 
                 const END: &[u8] = $crate::__const_concat!(b"</", $tag_name, b">");
+                writer.end_element(false)?;
                 writer.write_all(END)?;
                 Ok(())
             }
+
+            #[inline]
+            fn element_name() -> Option<&'static [u8]> {
+                Some($tag_name)
+            }
+        }
+
+        impl $crate::de::XmlName for $name {
+            const NAME: &'static [u8] = $tag_name;
         }
 
         impl<R: std::io::BufRead> $crate::de::DeserializeElement<R> for $name {
@@ -387,11 +1381,26 @@ macro_rules! ser_deser_vec {
             #[inline]
             fn de(
                 reader: &mut $crate::de::XmlReader<R>,
-                _start: quick_xml::events::BytesStart,
+                start: $crate::exports::events::BytesStart,
             ) -> Result<Self, $crate::de::Error> {
-                use quick_xml::events::Event;
+                use $crate::exports::events::Event;
+
+                reader.check_depth(start.name())?;
+                #[allow(unused_mut)]
+                let mut count: Option<usize> = None;
+                $(
+                    for attr in start.attributes() {
+                        let attr = attr.map_err(|e| $crate::Error {
+                            ty_name: String::from_utf8_lossy($tag_name).to_string(),
+                            kind: e.into(),
+                        })?;
+                        if attr.key == $count_name {
+                            count = Some($crate::de::DeserializeBuf::de_buf(&attr.value)?);
+                        }
+                    }
+                )?
 
-                let mut buf = Vec::with_capacity(64);
+                let mut buf = reader.take_buf();
                 let mut inner = Vec::new();
 
                 loop {
@@ -405,10 +1414,13 @@ macro_rules! ser_deser_vec {
                         }
                     };
                     match event {
-                        Event::Start(e) if e.local_name() == $inner_tag_name => {
-                            inner.push($crate::de::DeserializeElement::de(reader, e)?);
+                        Event::Start(e) if ($accepts)(reader, &e) => {
+                            inner.push(
+                                $crate::de::DeserializeElement::de(reader, e)
+                                    .map_err(|e| e.in_parent(&String::from_utf8_lossy($tag_name)))?,
+                            );
                         }
-                        Event::End(e) if e.local_name() == $tag_name => {
+                        Event::End(e) if e.local_name() == start.local_name() => {
                             break;
                         }
                         Event::Text(e) if e.is_empty() => {}
@@ -420,6 +1432,21 @@ macro_rules! ser_deser_vec {
                         }
                     }
                 }
+                reader.recycle_buf(buf);
+
+                if let Some(count) = count {
+                    if count != inner.len() {
+                        return Err($crate::Error {
+                            ty_name: String::from_utf8_lossy($tag_name).to_string(),
+                            kind: $crate::ErrorKind::Cardinality {
+                                name: String::from_utf8_lossy($inner_tag_name).to_string(),
+                                got: inner.len(),
+                                min: Some(count),
+                                max: Some(count),
+                            },
+                        });
+                    }
+                }
 
                 Ok(Self(inner))
             }
@@ -427,49 +1454,285 @@ macro_rules! ser_deser_vec {
     };
 }
 
+/// Implements [`DeserializeBuf`] with [`FromStr`](std::str::FromStr) and
+/// [`Serialize`](crate::ser::Serialize) with [`Display`](std::fmt::Display) for a type.
+///
+/// The input is decoded as utf-8 before parsing and the output of `Display` is escaped. The
+/// error of `FromStr` must implement [`Debug`](std::fmt::Debug), it is contained in the
+/// [`ErrorKind::InvalidType`] together with the name of the type.
+///
+/// This is the same as annotating every field of the type with `#[xmlib(display)]`.
+///
+/// # Example
+/// ```
+/// use xmlib_derive::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq)]
+/// pub struct Version(u32, u32);
+///
+/// impl std::fmt::Display for Version {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "{}.{}", self.0, self.1)
+///     }
+/// }
+///
+/// impl std::str::FromStr for Version {
+///     type Err = std::num::ParseIntError;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         let (major, minor) = s.split_once('.').unwrap_or((s, "0"));
+///         Ok(Version(major.parse()?, minor.parse()?))
+///     }
+/// }
+///
+/// xmlib::impl_buf_via_str!(Version);
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Package {
+///     version: Version,
+///     #[xmlib(element, multiple)]
+///     supports: Vec<Version>,
+/// }
+///
+/// let input = r#"<package version="1.2"><supports>1.0</supports></package>"#;
+/// let package: Package = xmlib::de::from_str(input).unwrap();
+/// assert_eq!(package.version, Version(1, 2));
+/// assert_eq!(package.supports, [Version(1, 0)]);
+/// assert_eq!(xmlib::ser::write_to_string(package).unwrap(), input);
+///
+/// let err = xmlib::de::from_str::<Package>(r#"<package version="x"/>"#).unwrap_err();
+/// assert_eq!(err.ty_name, "Version");
+/// ```
+#[macro_export]
+macro_rules! impl_buf_via_str {
+    ($t:ty) => {
+        impl $crate::de::DeserializeBuf for $t {
+            #[inline]
+            fn de_buf(buf: &[u8]) -> ::std::result::Result<Self, $crate::Error> {
+                $crate::types::display::de_buf_named(buf, stringify!($t))
+            }
+        }
+
+        impl<W: ::std::io::Write> $crate::ser::Serialize<W> for $t {
+            #[inline]
+            fn ser(
+                &self,
+                writer: &mut $crate::ser::XmlWriter<W>,
+            ) -> ::std::result::Result<(), $crate::ser::Error> {
+                $crate::types::display::ser(self, writer)
+            }
+        }
+    };
+}
+
+/// Implements [`DeserializeBuf`] and [`Serialize`](crate::ser::Serialize) for a type by
+/// converting it from and into a primitive, e.g. for ids which are newtypes of integers.
+///
+/// `impl_buf_via_conv!(Id as u32)` requires the implementations `TryFrom<u32> for Id`, whose
+/// error implements [`Debug`](std::fmt::Debug), and `From<Id> for u32`. `Id` must implement
+/// [`Clone`], since it is converted by value when serializing. `From<u32> for Id` provides the
+/// `TryFrom` implementation.
+///
+/// Errors contain the name of the type. A value which isn't a valid primitive causes the error
+/// of the primitive, a rejected conversion [`ErrorKind::InvalidType`].
+///
+/// # Example
+/// ```
+/// use xmlib_derive::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// pub struct Port(u16);
+///
+/// impl TryFrom<u16> for Port {
+///     type Error = &'static str;
+///
+///     fn try_from(port: u16) -> Result<Self, Self::Error> {
+///         if port == 0 {
+///             Err("port 0 is reserved")
+///         } else {
+///             Ok(Port(port))
+///         }
+///     }
+/// }
+///
+/// impl From<Port> for u16 {
+///     fn from(port: Port) -> u16 {
+///         port.0
+///     }
+/// }
+///
+/// xmlib::impl_buf_via_conv!(Port as u16);
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Server {
+///     port: Port,
+/// }
+///
+/// let server: Server = xmlib::de::from_str(r#"<server port="8080"/>"#).unwrap();
+/// assert_eq!(server.port, Port(8080));
+/// assert_eq!(
+///     xmlib::ser::write_to_string(server).unwrap(),
+///     r#"<server port="8080"/>"#
+/// );
+///
+/// let err = xmlib::de::from_str::<Server>(r#"<server port="0"/>"#).unwrap_err();
+/// assert_eq!(err.ty_name, "Port");
+/// assert!(err.to_string().contains("port 0 is reserved"));
+/// ```
+#[macro_export]
+macro_rules! impl_buf_via_conv {
+    ($t:ty as $prim:ty) => {
+        impl $crate::de::DeserializeBuf for $t {
+            #[inline]
+            fn de_buf(buf: &[u8]) -> ::std::result::Result<Self, $crate::Error> {
+                let value = <$prim as $crate::de::DeserializeBuf>::de_buf(buf).map_err(|e| {
+                    $crate::Error {
+                        ty_name: ::std::string::String::from(stringify!($t)),
+                        kind: e.kind,
+                    }
+                })?;
+                <$t as ::std::convert::TryFrom<$prim>>::try_from(value).map_err(|e| $crate::Error {
+                    ty_name: ::std::string::String::from(stringify!($t)),
+                    kind: $crate::ErrorKind::InvalidType(format!(
+                        "{} is not a valid {}: {:?}",
+                        value,
+                        stringify!($t),
+                        e
+                    )),
+                })
+            }
+        }
+
+        impl<W: ::std::io::Write> $crate::ser::Serialize<W> for $t {
+            #[inline]
+            fn ser(
+                &self,
+                writer: &mut $crate::ser::XmlWriter<W>,
+            ) -> ::std::result::Result<(), $crate::ser::Error> {
+                let value =
+                    <$prim as ::std::convert::From<$t>>::from(::std::clone::Clone::clone(self));
+                $crate::ser::Serialize::ser(&value, writer)
+            }
+        }
+    };
+}
+
+// Reads the root element of type `$t` using `$read_event` to get the next event.
+// This is a macro to support buffered and unbuffered reading.
+// Declarations, processing instructions, comments and whitespace around the root are skipped.
+macro_rules! read_single_struct {
+    ($t:ty, $reader:ident, $read_event:expr) => {{
+        use quick_xml::events::Event;
+        let mut s = None;
+
+        let mut round = 0;
+        loop {
+            let event = $read_event.map_err(|e| Error {
+                ty_name: <$t>::name_string(),
+                kind: e.into(),
+            })?;
+            match event {
+                Event::Decl(_) | Event::PI(_) | Event::Comment(_) | Event::DocType(_) => {}
+                Event::Start(e)
+                    if <$t>::accepts_name(e.local_name())
+                        && $reader.is_in_namespace(e.name(), <$t>::namespace()) =>
+                {
+                    s = Some(<$t>::de(&mut $reader, e)?);
+                }
+                Event::Eof => {
+                    break;
+                }
+                Event::Text(e) if e.iter().all(u8::is_ascii_whitespace) => {}
+                e => {
+                    round += 1;
+                    if round > 10 {
+                        panic!(
+                            "expected {} got {:?}",
+                            String::from_utf8_lossy(<$t>::name()),
+                            e
+                        );
+                    }
+                }
+            }
+        }
+        s.ok_or_else(|| Error {
+            ty_name: <$t>::name_string(),
+            kind: ErrorKind::XmlError(quick_xml::Error::UnexpectedEof(String::from(
+                "no element found",
+            ))),
+        })
+    }};
+}
+
 /// Deserializes a single struct from a given reader.
 ///
 /// See [`from_str`] for an example.
 pub fn deserialize_single_struct<R: std::io::BufRead, T: DeserializeElement<R>>(
     mut reader: XmlReader<R>,
+) -> Result<T, Error> {
+    let mut buf = Vec::with_capacity(32);
+    read_single_struct!(T, reader, reader.read_event(&mut buf))
+}
+
+/// Deserializes a single struct from the first element with the given name.
+///
+/// In contrast to [`deserialize_single_struct`] the name of the element doesn't have to match
+/// [`DeserializeElement::name`] and all other elements are skipped.
+///
+/// See [`from_str_at`] for an example.
+pub fn deserialize_at<R: std::io::BufRead, T: DeserializeElement<R>>(
+    mut reader: XmlReader<R>,
+    name: &[u8],
 ) -> Result<T, Error> {
     use quick_xml::events::Event;
     let mut buf = Vec::with_capacity(32);
-    let mut s = None;
 
-    let mut round = 0;
     loop {
         let event = reader.read_event(&mut buf).map_err(|e| Error {
             ty_name: T::name_string(),
             kind: e.into(),
         })?;
         match event {
-            Event::Decl(_) => {}
-            Event::Start(e) if e.local_name() == T::name() => {
-                s = Some(T::de(&mut reader, e)?);
+            Event::Start(e) if e.local_name() == name => {
+                return T::de(&mut reader, e);
             }
-            Event::Eof if s.is_some() => {
-                break;
-            }
-            Event::Text(e) if e.is_empty() => {}
-            e => {
-                round += 1;
-                if round > 10 {
-                    panic!(
-                        "expected {} got {:?}",
-                        String::from_utf8_lossy(T::name()),
-                        e
-                    );
-                }
+            Event::Eof => {
+                return Err(Error {
+                    ty_name: T::name_string(),
+                    kind: ErrorKind::XmlError(quick_xml::Error::UnexpectedEof(format!(
+                        "no element {} found",
+                        String::from_utf8_lossy(name)
+                    ))),
+                })
             }
+            _ => {}
         }
+        buf.clear();
     }
-    s.ok_or_else(|| Error {
-        ty_name: T::name_string(),
-        kind: ErrorKind::XmlError(quick_xml::Error::UnexpectedEof(String::from(
-            "no element found",
-        ))),
-    })
+}
+
+/// Deserializes a single struct from the first element with the given name in a &str.
+///
+/// ```
+/// use xmlib_derive::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Rectangle {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// let serialized = r#"<envelope><body><shape width="13" height="42"/></body></envelope>"#;
+/// let deserialized: Rectangle = xmlib::de::from_str_at(&serialized, b"shape").unwrap();
+///
+/// assert_eq!(deserialized.width, 13);
+/// assert_eq!(deserialized.height, 42);
+/// ```
+pub fn from_str_at<'a, T: DeserializeElement<std::io::BufReader<&'a [u8]>>>(
+    input: &'a str,
+    name: &[u8],
+) -> Result<T, Error> {
+    deserialize_at(XmlReader::from_str(input), name)
 }
 
 /// Deserializes a single struct from a &str.
@@ -492,8 +1755,183 @@ pub fn deserialize_single_struct<R: std::io::BufRead, T: DeserializeElement<R>>(
 pub fn from_str<'a, T: DeserializeElement<std::io::BufReader<&'a [u8]>>>(
     input: &'a str,
 ) -> Result<T, Error> {
-    let reader = XmlReader::new(std::io::BufReader::new(input.as_bytes()));
-    deserialize_single_struct(reader)
+    from_bytes(input.as_bytes())
+}
+
+/// Deserializes a single struct from a &[u8].
+///
+/// In contrast to [`from_str`] the input doesn't have to be checked for valid utf-8 upfront.
+///
+/// ```
+/// use xmlib_derive::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Rectangle {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// let serialized = br#"<rectangle width="13" height="42"/>"#;
+/// let deserialized: Rectangle = xmlib::de::from_bytes(serialized).unwrap();
+///
+/// assert_eq!(deserialized.width, 13);
+/// assert_eq!(deserialized.height, 42);
+/// ```
+pub fn from_bytes<'a, T: DeserializeElement<std::io::BufReader<&'a [u8]>>>(
+    input: &'a [u8],
+) -> Result<T, Error> {
+    deserialize_single_struct(XmlReader::from_bytes(input))
+}
+
+/// Deserializes a single struct from a &str, borrowing from the input.
+///
+/// Structs with a lifetime can borrow attributes from the input, see
+/// [`DeserializeBufBorrowed`]. They only implement [`DeserializeElement<&[u8]>`](DeserializeElement)
+/// and can't be deserialized with [`from_str`].
+///
+/// ```
+/// use std::borrow::Cow;
+/// use xmlib_derive::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Person<'a> {
+///     #[xmlib(borrow)]
+///     name: Cow<'a, str>,
+/// }
+///
+/// let serialized = r#"<person name="Jane"/>"#;
+/// let deserialized: Person = xmlib::de::from_str_borrowed(&serialized).unwrap();
+///
+/// assert!(matches!(deserialized.name, Cow::Borrowed("Jane")));
+/// ```
+pub fn from_str_borrowed<'a, T: DeserializeElement<&'a [u8]>>(input: &'a str) -> Result<T, Error> {
+    from_bytes_borrowed(input.as_bytes())
+}
+
+/// Deserializes a single struct from a &[u8], borrowing from the input.
+///
+/// See [`from_str_borrowed`].
+pub fn from_bytes_borrowed<'a, T: DeserializeElement<&'a [u8]>>(
+    input: &'a [u8],
+) -> Result<T, Error> {
+    let mut reader = XmlReader::from_slice(input);
+    read_single_struct!(T, reader, reader.read_event_unbuffered())
+}
+
+/// Reader type of types deserialized with a type-erased reader.
+///
+/// Types annotated with `#[xmlib(erased)]` only implement
+/// [`DeserializeElement<ErasedReader>`](DeserializeElement), so their deserialization code is
+/// compiled only once instead of once per reader type.
+pub type ErasedReader<'r> = &'r mut dyn std::io::BufRead;
+
+/// Deserializes a single struct from a given reader using an [`ErasedReader`].
+///
+/// ```
+/// use xmlib_derive::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// #[xmlib(erased)]
+/// struct Rectangle {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// let serialized = r#"<rectangle width="13" height="42"/>"#;
+/// let deserialized: Rectangle = xmlib::de::from_reader_erased(serialized.as_bytes()).unwrap();
+///
+/// assert_eq!(deserialized.width, 13);
+/// assert_eq!(deserialized.height, 42);
+/// ```
+pub fn from_reader_erased<R, T>(mut reader: R) -> Result<T, Error>
+where
+    R: std::io::BufRead,
+    T: for<'r> DeserializeElement<ErasedReader<'r>>,
+{
+    deserialize_single_struct(XmlReader::new(&mut reader as ErasedReader))
+}
+
+pub(crate) mod sealed {
+    pub trait Sealed {}
+}
+
+/// Collections which can hold the children of fields annotated with `#[xmlib(multiple)]`.
+///
+/// This is implemented for [`Vec`], [`std::collections::VecDeque`],
+/// [`std::collections::LinkedList`], [`std::collections::HashSet`] and
+/// [`std::collections::BTreeSet`]. With the feature `smallvec` it is also implemented for
+/// `smallvec::SmallVec`. Sets drop duplicate children.
+///
+/// The collection is created with [`Default::default`] and every child is added with
+/// [`CollectChild::push_child`]. Serializing iterates over the children by reference.
+///
+/// # Example
+/// ```
+/// use std::collections::BTreeSet;
+/// use xmlib_derive::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Tags {
+///     #[xmlib(element, multiple, rename = "tag")]
+///     tags: BTreeSet<String>,
+/// }
+///
+/// let tags: Tags = xmlib::de::from_str("<tags><tag>b</tag><tag>a</tag><tag>b</tag></tags>").unwrap();
+/// let serialized = xmlib::ser::write_to_string(tags).unwrap();
+/// assert_eq!(serialized, "<tags><tag>a</tag><tag>b</tag></tags>");
+/// ```
+pub trait CollectChild<T>: sealed::Sealed {
+    /// Adds the deserialized child to the collection.
+    fn push_child(&mut self, child: T);
+}
+
+impl<T> sealed::Sealed for Vec<T> {}
+
+impl<T> CollectChild<T> for Vec<T> {
+    #[inline]
+    fn push_child(&mut self, child: T) {
+        self.push(child);
+    }
+}
+
+impl<T> sealed::Sealed for std::collections::VecDeque<T> {}
+
+impl<T> CollectChild<T> for std::collections::VecDeque<T> {
+    #[inline]
+    fn push_child(&mut self, child: T) {
+        self.push_back(child);
+    }
+}
+
+impl<T> sealed::Sealed for std::collections::LinkedList<T> {}
+
+impl<T> CollectChild<T> for std::collections::LinkedList<T> {
+    #[inline]
+    fn push_child(&mut self, child: T) {
+        self.push_back(child);
+    }
+}
+
+impl<T, S> sealed::Sealed for std::collections::HashSet<T, S> {}
+
+impl<T, S> CollectChild<T> for std::collections::HashSet<T, S>
+where
+    T: Eq + std::hash::Hash,
+    S: std::hash::BuildHasher,
+{
+    #[inline]
+    fn push_child(&mut self, child: T) {
+        self.insert(child);
+    }
+}
+
+impl<T> sealed::Sealed for std::collections::BTreeSet<T> {}
+
+impl<T: Ord> CollectChild<T> for std::collections::BTreeSet<T> {
+    #[inline]
+    fn push_child(&mut self, child: T) {
+        self.insert(child);
+    }
 }
 
 /// Type which is used to deserialize the namespaces of an element.
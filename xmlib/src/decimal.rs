@@ -0,0 +1,27 @@
+//! Implementations for [`rust_decimal::Decimal`], enabled with the `decimal` feature.
+//!
+//! Decimals are written in the plain decimal form without exponent, e.g. `123456789.000000001`,
+//! so no precision is lost like with [`f64`].
+
+use std::io::Write;
+
+use ::rust_decimal::Decimal;
+
+use crate::de::{de_from_str, DeserializeBuf};
+use crate::ser::{Serialize, XmlWriter};
+use crate::Error;
+
+impl DeserializeBuf for Decimal {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        de_from_str(buf, "Decimal")
+    }
+}
+
+impl<W: Write> Serialize<W> for Decimal {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        write!(writer, "{}", self)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+//! Implementations for [`std::time::Duration`] as ISO 8601 durations (`xs:duration`).
+//!
+//! Only days, hours, minutes and (fractional) seconds are supported, e.g. `P1DT2H30M15.5S`.
+//! Years and months are rejected because their length depends on the date they are applied to.
+//! Durations are serialized in the shortest form, the zero duration as `PT0S`.
+
+use std::io::Write;
+use std::time::Duration;
+
+use crate::de::DeserializeBuf;
+use crate::ser::{Serialize, XmlWriter};
+use crate::{Error, ErrorKind};
+
+const SECS_PER_MINUTE: u64 = 60;
+const SECS_PER_HOUR: u64 = 60 * SECS_PER_MINUTE;
+const SECS_PER_DAY: u64 = 24 * SECS_PER_HOUR;
+
+fn parse(s: &[u8]) -> Result<Duration, String> {
+    let rest = match s {
+        [b'P', rest @ ..] => rest,
+        _ => return Err(String::from("expected leading 'P'")),
+    };
+    if rest.is_empty() || rest == b"T" {
+        return Err(String::from("expected at least one component"));
+    }
+
+    let mut secs: u64 = 0;
+    let mut nanos: u32 = 0;
+    let mut in_time = false;
+    // index of the last parsed designator, used to enforce their order
+    let mut last = 0;
+    let mut rest = rest;
+    while !rest.is_empty() {
+        if rest[0] == b'T' {
+            if in_time {
+                return Err(String::from("duplicate 'T'"));
+            }
+            in_time = true;
+            rest = &rest[1..];
+            if rest.is_empty() {
+                return Err(String::from("expected a component after 'T'"));
+            }
+            continue;
+        }
+
+        let digits = rest.iter().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 {
+            return Err(format!(
+                "expected digits at '{}'",
+                String::from_utf8_lossy(rest)
+            ));
+        }
+        let value: u64 = atoi::atoi(&rest[..digits]).ok_or("number is too large")?;
+        rest = &rest[digits..];
+
+        let mut fraction = None;
+        if let [b'.' | b',', tail @ ..] = rest {
+            let frac_digits = tail.iter().take_while(|c| c.is_ascii_digit()).count();
+            if frac_digits == 0 {
+                return Err(String::from("expected digits after decimal separator"));
+            }
+            // digits beyond nanosecond precision are truncated
+            let frac = tail[..frac_digits]
+                .iter()
+                .chain(std::iter::repeat(&b'0'))
+                .take(9)
+                .fold(0, |acc, c| acc * 10 + u32::from(c - b'0'));
+            fraction = Some(frac);
+            rest = &tail[frac_digits..];
+        }
+
+        let (designator, tail) = rest
+            .split_first()
+            .ok_or("expected designator after number")?;
+        rest = tail;
+        let (index, unit) = match (in_time, designator) {
+            (false, b'Y' | b'M') => {
+                return Err(String::from(
+                    "years and months are not supported because their length is ambiguous",
+                ))
+            }
+            (false, b'W') => (1, 7 * SECS_PER_DAY),
+            (false, b'D') => (2, SECS_PER_DAY),
+            (true, b'H') => (3, SECS_PER_HOUR),
+            (true, b'M') => (4, SECS_PER_MINUTE),
+            (true, b'S') => (5, 1),
+            (_, d) => return Err(format!("unexpected designator '{}'", char::from(*d))),
+        };
+        if index <= last {
+            return Err(format!(
+                "unexpected designator '{}'",
+                char::from(*designator)
+            ));
+        }
+        last = index;
+        if fraction.is_some() && index != 5 {
+            return Err(String::from("only seconds may have a fraction"));
+        }
+        nanos = fraction.unwrap_or(0);
+        secs = value
+            .checked_mul(unit)
+            .and_then(|v| v.checked_add(secs))
+            .ok_or("duration is too large")?;
+    }
+    Ok(Duration::new(secs, nanos))
+}
+
+impl DeserializeBuf for Duration {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        parse(buf).map_err(|e| Error {
+            ty_name: String::from("Duration"),
+            kind: ErrorKind::InvalidType(format!("{}: {}", String::from_utf8_lossy(buf), e)),
+        })
+    }
+}
+
+impl<W: Write> Serialize<W> for Duration {
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        let mut secs = self.as_secs();
+        let nanos = self.subsec_nanos();
+        let days = secs / SECS_PER_DAY;
+        secs %= SECS_PER_DAY;
+        let hours = secs / SECS_PER_HOUR;
+        secs %= SECS_PER_HOUR;
+        let minutes = secs / SECS_PER_MINUTE;
+        secs %= SECS_PER_MINUTE;
+
+        writer.write_all(b"P")?;
+        if days > 0 {
+            write!(writer, "{}D", days)?;
+        }
+        if hours == 0 && minutes == 0 && secs == 0 && nanos == 0 {
+            if days == 0 {
+                writer.write_all(b"T0S")?;
+            }
+            return Ok(());
+        }
+        writer.write_all(b"T")?;
+        if hours > 0 {
+            write!(writer, "{}H", hours)?;
+        }
+        if minutes > 0 {
+            write!(writer, "{}M", minutes)?;
+        }
+        if nanos > 0 {
+            let frac = format!("{:09}", nanos);
+            write!(writer, "{}.{}S", secs, frac.trim_end_matches('0'))?;
+        } else if secs > 0 {
+            write!(writer, "{}S", secs)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+//! Generic tree of elements for documents whose structure isn't known in advance.
+
+use std::io::Write;
+
+use quick_xml::events::{BytesStart, Event};
+
+use crate::de::{DeserializeElement, XmlReader};
+use crate::ser::{Serialize, XmlWriter};
+use crate::{Error, ErrorKind};
+
+/// An element with its attributes and children, e.g. to read a document, change parts of it and
+/// write it back.
+///
+/// The names are qualified like in the document, e.g. `a:item`, and namespace declarations are
+/// kept as attributes. Text is unescaped, CDATA sections become text and comments and processing
+/// instructions are dropped. Whitespace between elements is kept as text, so indented documents
+/// are written back with the same indentation.
+///
+/// Elements match every name when deserializing, so a field `#[xmlib(value)] rest: Element`
+/// receives any child element as opaque subtree.
+///
+/// # Example
+/// ```
+/// use xmlib::Element;
+///
+/// let input = r#"<config><server port="80">main</server><server port="81"/></config>"#;
+/// let mut config: Element = xmlib::de::from_str(input).unwrap();
+///
+/// let ports: Vec<_> = config
+///     .children_named("server")
+///     .filter_map(|server| server.attr("port"))
+///     .collect();
+/// assert_eq!(ports, ["80", "81"]);
+/// assert_eq!(config.text(), "main");
+///
+/// config.set_attr("version", "2");
+/// assert_eq!(
+///     xmlib::ser::write_to_string(&config).unwrap(),
+///     r#"<config version="2"><server port="80">main</server><server port="81"/></config>"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Element {
+    /// The qualified name of the element.
+    pub name: String,
+    /// The attributes with their unescaped values in document order.
+    pub attributes: Vec<(String, String)>,
+    /// The child elements and text in document order.
+    pub children: Vec<Node>,
+}
+
+/// A child of an [`Element`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    /// A child element.
+    Element(Element),
+    /// Unescaped text, adjacent text and CDATA sections are merged.
+    Text(String),
+}
+
+impl Element {
+    /// Creates an element without attributes and children.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Returns the value of the attribute `name`.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the value of the attribute `name`, which is added after the others if it is missing.
+    pub fn set_attr(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+        let value = value.into();
+        match self.attributes.iter_mut().find(|(key, _)| *key == name) {
+            Some((_, old)) => *old = value,
+            None => self.attributes.push((name, value)),
+        }
+    }
+
+    /// Returns the child elements.
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// Returns the child elements with the qualified name `name`.
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.elements().filter(move |element| element.name == name)
+    }
+
+    /// Returns the text of the element and all its descendants in document order.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.collect_text(&mut text);
+        text
+    }
+
+    fn collect_text(&self, text: &mut String) {
+        for child in &self.children {
+            match child {
+                Node::Element(element) => element.collect_text(text),
+                Node::Text(t) => text.push_str(t),
+            }
+        }
+    }
+
+    // Appends text to the last child if it is text too
+    fn push_text(&mut self, text: &[u8]) -> Result<(), Error> {
+        // the reader returns empty text events between tags
+        if text.is_empty() {
+            return Ok(());
+        }
+        let text = std::str::from_utf8(text).map_err(|e| Error {
+            ty_name: self.name.clone(),
+            kind: ErrorKind::InvalidType(e.to_string()),
+        })?;
+        match self.children.last_mut() {
+            Some(Node::Text(last)) => last.push_str(text),
+            _ => self.children.push(Node::Text(String::from(text))),
+        }
+        Ok(())
+    }
+}
+
+impl<R: std::io::BufRead> DeserializeElement<R> for Element {
+    #[inline]
+    fn name() -> &'static [u8] {
+        b"element"
+    }
+
+    #[inline]
+    fn accepts_name(_local_name: &[u8]) -> bool {
+        true
+    }
+
+    fn de(reader: &mut XmlReader<R>, start: BytesStart) -> Result<Self, Error> {
+        reader.check_depth(start.name())?;
+        let name = String::from_utf8_lossy(start.name()).to_string();
+        let xml_error = |name: &str, e: quick_xml::Error| Error {
+            ty_name: String::from(name),
+            kind: ErrorKind::XmlError(e),
+        };
+
+        let mut attributes = Vec::new();
+        for attr in start.attributes() {
+            let attr = attr.map_err(|e| xml_error(&name, e.into()))?;
+            let value = attr.unescaped_value().map_err(|e| xml_error(&name, e))?;
+            attributes.push((
+                String::from_utf8_lossy(attr.key).to_string(),
+                String::from_utf8_lossy(&value).to_string(),
+            ));
+        }
+        let mut element = Self {
+            name,
+            attributes,
+            children: Vec::new(),
+        };
+
+        let mut buf = reader.take_buf();
+        loop {
+            match reader.read_event(&mut buf) {
+                Ok(Event::Start(e)) => {
+                    let child = <Self as DeserializeElement<R>>::de(reader, e.into_owned())
+                        .map_err(|e| e.in_parent(&element.name))?;
+                    element.children.push(Node::Element(child));
+                }
+                Ok(Event::End(_)) => break,
+                Ok(Event::Text(e)) => {
+                    let text = e.unescaped().map_err(|e| xml_error(&element.name, e))?;
+                    element.push_text(&text)?;
+                }
+                Ok(Event::CData(e)) => element.push_text(&e)?,
+                Ok(Event::Eof) => {
+                    return Err(xml_error(
+                        &element.name,
+                        quick_xml::Error::UnexpectedEof(element.name.clone()),
+                    ))
+                }
+                Ok(_) => {}
+                Err(e) => return Err(xml_error(&element.name, e)),
+            }
+            buf.clear();
+        }
+        reader.recycle_buf(buf);
+        Ok(element)
+    }
+}
+
+impl<W: Write> Serialize<W> for Element {
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        let quote = writer.quote();
+        writer.start_element()?;
+        writer.write_all(b"<")?;
+        writer.write_all(self.name.as_bytes())?;
+        for (name, value) in &self.attributes {
+            writer.write_all(b" ")?;
+            writer.write_all(name.as_bytes())?;
+            writer.write_all(b"=")?;
+            writer.write_all(quote)?;
+            writer.write_all(&quick_xml::escape::escape(value.as_bytes()))?;
+            writer.write_all(quote)?;
+        }
+        if self.children.is_empty() {
+            writer.write_all(b"/>")?;
+            writer.end_element(true)?;
+            return Ok(());
+        }
+        writer.write_all(b">")?;
+        // whitespace would change the text of the element
+        if self
+            .children
+            .iter()
+            .any(|child| matches!(child, Node::Text(_)))
+        {
+            writer.start_inline();
+        }
+        for child in &self.children {
+            child.ser(writer)?;
+        }
+        writer.end_element(false)?;
+        writer.write_all(b"</")?;
+        writer.write_all(self.name.as_bytes())?;
+        writer.write_all(b">")?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Serialize<W> for Node {
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        match self {
+            Self::Element(element) => element.ser(writer),
+            Self::Text(text) => {
+                writer.write_all(&quick_xml::escape::escape(text.as_bytes()))?;
+                Ok(())
+            }
+        }
+    }
+}
@@ -3,11 +3,24 @@ use std::fmt::{self, Debug, Display, Formatter};
 /// This type represents all possible errors that can occur.
 pub struct Error {
     /// Name of the element in which the error occurred.
+    ///
+    /// Errors of nested elements contain the names of their parents, e.g. `root > items > item`.
     pub ty_name: String,
     /// Errorkind which contains additional data.
     pub kind: ErrorKind,
 }
 
+impl Error {
+    /// Prepends the name of the parent element to [`Error::ty_name`].
+    ///
+    /// This is used when deserializing child elements, so that errors contain the path to the
+    /// element in which they occurred.
+    pub fn in_parent(mut self, parent: &str) -> Self {
+        self.ty_name = format!("{} > {}", parent, self.ty_name);
+        self
+    }
+}
+
 impl Debug for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         Display::fmt(self, f)
@@ -20,7 +33,11 @@ impl Display for Error {
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.kind.source()
+    }
+}
 
 /// A list specifying kinds of error.
 ///
@@ -35,12 +52,51 @@ pub enum ErrorKind {
     InvalidType(String),
     /// Required attribute of struct was missing
     MissingAttr(String),
+    /// Required child element of struct was missing
+    MissingChild(String),
     /// Invalid event occurred while deserialization
     UnexpectedEvent(String),
     /// Validation of attribute failed
     Validation(String),
+    /// Validation of attribute failed with a custom error.
+    ///
+    /// The error can be retrieved with [`std::error::Error::source`] or by downcasting.
+    CustomValidation(Box<dyn std::error::Error + Send + Sync>),
     /// Could not convert bytes to valid utf8 string
     FromUtf8Error(std::string::FromUtf8Error),
+    /// Number is out of the range of the type
+    Overflow {
+        /// Name of the type.
+        ty: String,
+        /// The value which couldn't be represented.
+        value: String,
+    },
+    /// Number of children is out of the allowed bounds
+    Cardinality {
+        /// Name of the children.
+        name: String,
+        /// Number of found children.
+        got: usize,
+        /// Minimal number of children.
+        min: Option<usize>,
+        /// Maximal number of children.
+        max: Option<usize>,
+    },
+    /// Two children of a keyed collection have the same key
+    DuplicateKey {
+        /// Name of the collection.
+        name: String,
+        /// Debug representation of the key.
+        key: String,
+        /// Byte position after the start tag of the first child.
+        first: usize,
+        /// Byte position after the start tag of the duplicate child.
+        duplicate: usize,
+    },
+    /// The namespace prefix of a name isn't declared
+    UnboundPrefix(String),
+    /// Elements are nested deeper than the maximal depth of the reader
+    DepthLimitExceeded(usize),
 }
 
 impl From<quick_xml::Error> for ErrorKind {
@@ -61,17 +117,125 @@ impl Display for ErrorKind {
             Self::XmlError(e) => write!(f, "xml error: {}", e),
             Self::InvalidType(e) => write!(f, "invalid type: {}", e),
             Self::MissingAttr(e) => write!(f, "missing attribute: {}", e),
+            Self::MissingChild(e) => write!(f, "missing child element: {}", e),
             Self::UnexpectedEvent(e) => write!(f, "unexpected event: {}", e),
             Self::Validation(e) => write!(f, "failed validation: {}", e),
+            Self::CustomValidation(e) => write!(f, "failed validation: {}", e),
             Self::FromUtf8Error(e) => write!(f, "{}", e),
+            Self::Overflow { ty, value } => write!(f, "{} is out of range for {}", value, ty),
+            Self::Cardinality {
+                name,
+                got,
+                min,
+                max,
+            } => {
+                write!(f, "got {} children {}, expected", got, name)?;
+                if let Some(min) = min {
+                    write!(f, " at least {}", min)?;
+                }
+                if let Some(max) = max {
+                    if min.is_some() {
+                        write!(f, " and")?;
+                    }
+                    write!(f, " at most {}", max)?;
+                }
+                Ok(())
+            }
+            Self::DuplicateKey {
+                name,
+                key,
+                first,
+                duplicate,
+            } => write!(
+                f,
+                "duplicate key {} in {} at position {}, first found at position {}",
+                key, name, duplicate, first
+            ),
+            Self::UnboundPrefix(prefix) => write!(f, "unbound namespace prefix: {}", prefix),
+            Self::DepthLimitExceeded(max) => {
+                write!(f, "elements are nested deeper than the limit of {}", max)
+            }
         }
     }
 }
 
-impl std::error::Error for ErrorKind {}
+impl std::error::Error for ErrorKind {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::XmlError(e) => Some(e),
+            Self::FromUtf8Error(e) => Some(e),
+            Self::CustomValidation(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 impl From<quick_xml::events::attributes::AttrError> for ErrorKind {
     fn from(e: quick_xml::events::attributes::AttrError) -> Self {
         Self::XmlError(quick_xml::Error::InvalidAttr(e))
     }
 }
+
+/// This type represents all possible errors that can occur while serializing.
+pub enum SerError {
+    /// Error from the underlying writer
+    Io(std::io::Error),
+    /// Validation of a value failed
+    Validation {
+        /// Name of the type whose validation failed.
+        ty_name: String,
+        /// Message describing why the validation failed.
+        message: String,
+    },
+    /// The value can't be represented as xml
+    Unrepresentable {
+        /// Name of the type which couldn't be serialized.
+        ty_name: String,
+        /// Reason why the value couldn't be serialized.
+        reason: String,
+    },
+}
+
+impl From<std::io::Error> for SerError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<SerError> for std::io::Error {
+    fn from(value: SerError) -> Self {
+        match value {
+            SerError::Io(e) => e,
+            e => Self::new(std::io::ErrorKind::InvalidData, e.to_string()),
+        }
+    }
+}
+
+impl Debug for SerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for SerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {}", e),
+            Self::Validation { ty_name, message } => {
+                write!(f, "failed validation in type {}: {}", ty_name, message)
+            }
+            Self::Unrepresentable { ty_name, reason } => {
+                write!(f, "can't serialize type {}: {}", ty_name, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
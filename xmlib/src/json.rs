@@ -0,0 +1,167 @@
+//! Conversion of [`Element`] trees from and into JSON.
+
+use serde_json::{Map, Value};
+
+use crate::element::{Element, Node};
+use crate::{Error, ErrorKind};
+
+const TEXT_KEY: &str = "$text";
+const ATTR_PREFIX: char = '@';
+
+impl Element {
+    /// Converts the element into JSON.
+    ///
+    /// The result is an object with the name of the element as only key. The content of an
+    /// element is
+    /// - a string with its text if it has neither attributes nor child elements
+    /// - otherwise an object with the attributes under `"@name"`, the text under `"$text"` and the
+    ///   child elements under their names, as array if there are several with the same name
+    ///
+    /// All values are strings, so numbers keep their exact spelling. Text between child elements
+    /// is concatenated and left out if it is only whitespace. The order of children with different
+    /// names is lost, so mixed content doesn't survive a round trip.
+    ///
+    /// # Example
+    /// ```
+    /// use xmlib::Element;
+    ///
+    /// let input = r#"<order id="7"><item>1.50</item><item>2</item><note>fragile</note></order>"#;
+    /// let order: Element = xmlib::de::from_str(input).unwrap();
+    ///
+    /// assert_eq!(
+    ///     order.to_json(),
+    ///     serde_json::json!({
+    ///         "order": {
+    ///             "@id": "7",
+    ///             "item": ["1.50", "2"],
+    ///             "note": "fragile",
+    ///         }
+    ///     })
+    /// );
+    /// assert_eq!(Element::from_json(&order.to_json()).unwrap(), order);
+    /// ```
+    pub fn to_json(&self) -> Value {
+        let mut root = Map::new();
+        root.insert(self.name.clone(), self.content_to_json());
+        Value::Object(root)
+    }
+
+    fn content_to_json(&self) -> Value {
+        let mut text = String::new();
+        let mut has_elements = false;
+        for child in &self.children {
+            match child {
+                Node::Element(_) => has_elements = true,
+                Node::Text(t) => text.push_str(t),
+            }
+        }
+        if self.attributes.is_empty() && !has_elements {
+            return Value::String(text);
+        }
+
+        let mut content = Map::new();
+        for (name, value) in &self.attributes {
+            content.insert(
+                format!("{}{}", ATTR_PREFIX, name),
+                Value::String(value.clone()),
+            );
+        }
+        if !(text.is_empty() || has_elements && text.trim().is_empty()) {
+            content.insert(String::from(TEXT_KEY), Value::String(text));
+        }
+        for element in self.elements() {
+            let value = element.content_to_json();
+            match content.get_mut(&element.name) {
+                Some(Value::Array(values)) => values.push(value),
+                Some(first) => {
+                    let first = first.take();
+                    content.insert(element.name.clone(), Value::Array(vec![first, value]));
+                }
+                None => {
+                    content.insert(element.name.clone(), value);
+                }
+            }
+        }
+        Value::Object(content)
+    }
+
+    /// Converts JSON in the format of [`Element::to_json`] into an element.
+    ///
+    /// Numbers and booleans are accepted as text and attribute values too. The text is placed
+    /// before the child elements, which follow in the order of their names.
+    pub fn from_json(value: &Value) -> Result<Self, Error> {
+        let error = |msg: String| Error {
+            ty_name: String::from("element"),
+            kind: ErrorKind::InvalidType(msg),
+        };
+        let root = value
+            .as_object()
+            .ok_or_else(|| error(format!("expected an object but got {}", value)))?;
+        let mut entries = root.iter();
+        match (entries.next(), entries.next()) {
+            (Some((name, content)), None) => Self::content_from_json(name, content),
+            _ => Err(error(format!(
+                "expected an object with exactly one key but got {} keys",
+                root.len()
+            ))),
+        }
+    }
+
+    fn content_from_json(name: &str, content: &Value) -> Result<Self, Error> {
+        let mut element = Self::new(name);
+        let error = |msg: String| Error {
+            ty_name: String::from(name),
+            kind: ErrorKind::InvalidType(msg),
+        };
+
+        let object = match content {
+            Value::Object(object) => object,
+            Value::Array(_) => return Err(error(String::from("unexpected nested array"))),
+            value => {
+                let text = scalar_to_string(value).ok_or_else(|| error(value.to_string()))?;
+                if !text.is_empty() {
+                    element.children.push(Node::Text(text));
+                }
+                return Ok(element);
+            }
+        };
+
+        if let Some(value) = object.get(TEXT_KEY) {
+            let text = scalar_to_string(value)
+                .ok_or_else(|| error(format!("expected text but got {}", value)))?;
+            if !text.is_empty() {
+                element.children.push(Node::Text(text));
+            }
+        }
+        for (key, value) in object {
+            if key == TEXT_KEY {
+                continue;
+            }
+            if let Some(attr) = key.strip_prefix(ATTR_PREFIX) {
+                let value = scalar_to_string(value).ok_or_else(|| {
+                    error(format!("expected a value for {} but got {}", key, value))
+                })?;
+                element.attributes.push((String::from(attr), value));
+                continue;
+            }
+            let values = match value {
+                Value::Array(values) => values.as_slice(),
+                value => std::slice::from_ref(value),
+            };
+            for value in values {
+                let child = Self::content_from_json(key, value).map_err(|e| e.in_parent(name))?;
+                element.children.push(Node::Element(child));
+            }
+        }
+        Ok(element)
+    }
+}
+
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
@@ -3,6 +3,23 @@
 //!
 //! This library uses [quick_xml](https://github.com/tafia/quick-xml/) under the hood.
 //!
+//! [`std::time::Duration`] is (de)serialized as ISO 8601 duration like `PT1H30M15S`.
+//! [`std::time::SystemTime`] uses whole seconds since the Unix epoch.
+//! The address types of [`std::net`] use their textual form, e.g. `[::1]:8080`.
+//!
+//! # Features
+//! - `uuid`: (de)serialize [`uuid::Uuid`](https://docs.rs/uuid) in the hyphenated form
+//! - `chrono`: (de)serialize dates and times of [`chrono`](https://docs.rs/chrono) as RFC 3339
+//! - `time`: (de)serialize dates and times of [`time`](https://docs.rs/time) as RFC 3339
+//! - `decimal`: (de)serialize [`rust_decimal::Decimal`](https://docs.rs/rust_decimal) without
+//!   exponent
+//! - `smallvec`: collect the children of `multiple` fields into
+//!   [`smallvec::SmallVec`](https://docs.rs/smallvec)
+//! - `json`: convert [`Element`] from and into [`serde_json::Value`](https://docs.rs/serde_json)
+//! - `schema`: generate XML Schema definitions of types annotated with `#[xmlib(reflect)]`, see
+//!   `schema`
+//! - `serde`: serialize types implementing the traits of [`serde`](https://docs.rs/serde), see
+//!   `compat`
 //!
 //! # Example
 //! ```rust
@@ -29,10 +46,33 @@
 #![warn(missing_docs)]
 #![warn(clippy::perf)]
 
+#[cfg(feature = "chrono")]
+mod chrono;
+#[cfg(feature = "serde")]
+pub mod compat;
 pub mod de;
+#[cfg(feature = "decimal")]
+mod decimal;
+mod duration;
+mod element;
 mod error;
+#[cfg(feature = "json")]
+mod json;
+pub mod meta;
+mod net;
+#[cfg(feature = "schema")]
+pub mod schema;
 pub mod ser;
+#[cfg(feature = "smallvec")]
+mod smallvec;
+mod system_time;
+#[cfg(feature = "time")]
+mod time;
+pub mod types;
+#[cfg(feature = "uuid")]
+mod uuid;
 
+pub use element::{Element, Node};
 pub use error::{Error, ErrorKind};
 
 /// Exports of [`memchr::memchr`] and [`quick_xml`]
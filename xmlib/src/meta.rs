@@ -0,0 +1,84 @@
+//! Descriptions of the xml structure of types, e.g. for editors or documentation.
+//!
+//! Named structs annotated with `#[xmlib(reflect)]` implement [`XmlSchema`] with the structure
+//! known to the derive, so it can't get out of sync with the (de)serialization.
+
+/// A type with a known xml structure.
+///
+/// # Example
+/// ```
+/// use xmlib::meta::XmlSchema;
+/// use xmlib_derive::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[xmlib(reflect)]
+/// struct Book {
+///     #[xmlib(default = 1)]
+///     edition: u32,
+///     #[xmlib(element, multiple, min = 1)]
+///     author: Vec<String>,
+/// }
+///
+/// let schema = Book::SCHEMA;
+/// assert_eq!(schema.name, b"book");
+/// assert_eq!(schema.attributes[0].ty, "u32");
+/// assert_eq!(schema.attributes[0].default, Some("1"));
+/// assert_eq!(schema.children[0].min_occurs, 1);
+/// assert_eq!(schema.children[0].max_occurs, None);
+/// ```
+pub trait XmlSchema {
+    /// The structure of the element.
+    const SCHEMA: ElementSchema;
+}
+
+/// The structure of an element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElementSchema {
+    /// The name of the element.
+    pub name: &'static [u8],
+    /// The namespace of the element if there is one.
+    pub namespace: Option<&'static [u8]>,
+    /// The attributes in declaration order.
+    pub attributes: &'static [AttributeSchema],
+    /// The child elements in declaration order.
+    pub children: &'static [ChildSchema],
+    /// The Rust type of the text content if there is one.
+    pub text: Option<&'static str>,
+    /// Whether text and child elements are mixed.
+    pub mixed: bool,
+}
+
+/// The structure of an attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttributeSchema {
+    /// The name of the attribute.
+    pub name: &'static [u8],
+    /// The Rust type of the value.
+    pub ty: &'static str,
+    /// Whether the attribute may be missing because the field has a default.
+    pub optional: bool,
+    /// The default if it is a literal, e.g. `42` or `"text"` for a `String`.
+    pub default: Option<&'static str>,
+}
+
+/// The structure of a child element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChildSchema {
+    /// The name of the child, which is the name of the type for `value` fields.
+    pub name: &'static [u8],
+    /// The namespace of `element` fields if there is one.
+    pub namespace: Option<&'static [u8]>,
+    /// The Rust type of a single child.
+    pub ty: &'static str,
+    /// Whether the child only contains text (`element`) instead of having its own structure
+    /// (`value`).
+    pub text_only: bool,
+    /// The minimal number of children.
+    pub min_occurs: usize,
+    /// The maximal number of children, `None` if there is no limit.
+    pub max_occurs: Option<usize>,
+    /// The name of the element wrapping `multiple` children.
+    pub wrapper: Option<&'static [u8]>,
+    /// The default of `element` fields if it is a literal.
+    pub default: Option<&'static str>,
+}
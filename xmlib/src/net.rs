@@ -0,0 +1,47 @@
+//! Implementations for the address types of [`std::net`] using their [`FromStr`](std::str::FromStr)
+//! and [`Display`](std::fmt::Display) implementations, e.g. `192.168.1.10` or `[::1]:8080`.
+
+use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use crate::de::{de_from_str, DeserializeBuf};
+use crate::ser::{Serialize, XmlWriter};
+use crate::Error;
+
+// long enough for the longest `SocketAddrV6`, e.g.
+// `[ffff:ffff:ffff:ffff:ffff:ffff:255.255.255.255%4294967295]:65535`
+const MAX_ADDR_LEN: usize = 64;
+
+macro_rules! impl_net {
+    ($($t:ident),+$(,)?) => {
+        $(
+            impl DeserializeBuf for $t {
+                #[inline]
+                fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+                    de_from_str(buf, stringify!($t))
+                }
+            }
+
+            impl<W: Write> Serialize<W> for $t {
+                #[inline]
+                fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+                    let mut buf = [0; MAX_ADDR_LEN];
+                    let mut cursor = std::io::Cursor::new(&mut buf[..]);
+                    write!(cursor, "{}", self)?;
+                    let len = cursor.position() as usize;
+                    writer.write_all(&buf[..len])?;
+                    Ok(())
+                }
+            }
+        )+
+    };
+}
+
+impl_net!(
+    IpAddr,
+    Ipv4Addr,
+    Ipv6Addr,
+    SocketAddr,
+    SocketAddrV4,
+    SocketAddrV6
+);
@@ -0,0 +1,185 @@
+//! Generation of XML Schema definitions from the structure of types, see [`xsd_for`].
+
+use std::fmt::Write;
+
+use crate::meta::{AttributeSchema, ChildSchema, ElementSchema, XmlSchema};
+
+/// Generates the `xs:element` definition of a type annotated with `#[xmlib(reflect)]`.
+///
+/// Attributes become `xs:attribute` with the simple type of their Rust type, e.g. `u32` is
+/// `xs:unsignedInt` and unknown types are `xs:string`. Children are an `xs:sequence` in
+/// declaration order, which is also the order they are written in. `multiple` children get
+/// `maxOccurs="unbounded"` or their `max` and literal defaults become `default="..."`.
+///
+/// `value` children are referenced with `ref="name"`, so their definitions must be generated
+/// separately and placed next to this one. Namespaces aren't included, the result is meant to be
+/// embedded into an `xs:schema` with the right `targetNamespace`.
+///
+/// # Example
+/// ```
+/// use xmlib_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// #[xmlib(reflect)]
+/// struct Point {
+///     x: i32,
+///     #[xmlib(default = 0)]
+///     y: i32,
+/// }
+///
+/// assert_eq!(
+///     xmlib::schema::xsd_for::<Point>(),
+///     r#"<xs:element name="point">
+///   <xs:complexType>
+///     <xs:attribute name="x" type="xs:int" use="required"/>
+///     <xs:attribute name="y" type="xs:int" default="0"/>
+///   </xs:complexType>
+/// </xs:element>
+/// "#
+/// );
+/// ```
+pub fn xsd_for<T: XmlSchema>() -> String {
+    let mut xsd = String::new();
+    write_element(&mut xsd, &T::SCHEMA);
+    xsd
+}
+
+// `fmt::Write` for `String` can't fail
+fn write_element(xsd: &mut String, schema: &ElementSchema) {
+    let _ = writeln!(xsd, r#"<xs:element name="{}">"#, escape_bytes(schema.name));
+    if schema.mixed {
+        xsd.push_str("  <xs:complexType mixed=\"true\">\n");
+    } else {
+        xsd.push_str("  <xs:complexType>\n");
+    }
+    match schema.text {
+        // text with attributes only extends the simple type of the text
+        Some(ty) if schema.children.is_empty() => {
+            let _ = writeln!(xsd, "    <xs:simpleContent>");
+            let _ = writeln!(xsd, r#"      <xs:extension base="{}">"#, simple_type(ty));
+            write_attributes(xsd, schema.attributes, 8);
+            let _ = writeln!(xsd, "      </xs:extension>");
+            let _ = writeln!(xsd, "    </xs:simpleContent>");
+        }
+        _ => {
+            if !schema.children.is_empty() {
+                xsd.push_str("    <xs:sequence>\n");
+                for child in schema.children {
+                    write_child(xsd, child);
+                }
+                xsd.push_str("    </xs:sequence>\n");
+            }
+            write_attributes(xsd, schema.attributes, 4);
+        }
+    }
+    xsd.push_str("  </xs:complexType>\n");
+    xsd.push_str("</xs:element>\n");
+}
+
+fn write_attributes(xsd: &mut String, attributes: &[AttributeSchema], indent: usize) {
+    for attr in attributes {
+        let _ = write!(
+            xsd,
+            r#"{:indent$}<xs:attribute name="{}" type="{}""#,
+            "",
+            escape_bytes(attr.name),
+            simple_type(attr.ty),
+            indent = indent
+        );
+        match attr.default {
+            Some(default) => {
+                let _ = write!(xsd, r#" default="{}""#, escape(default));
+            }
+            None if !attr.optional => xsd.push_str(r#" use="required""#),
+            None => {}
+        }
+        xsd.push_str("/>\n");
+    }
+}
+
+fn write_child(xsd: &mut String, child: &ChildSchema) {
+    let mut indent = 6;
+    if let Some(wrapper) = child.wrapper {
+        let _ = writeln!(
+            xsd,
+            r#"      <xs:element name="{}">"#,
+            escape_bytes(wrapper)
+        );
+        xsd.push_str("        <xs:complexType>\n");
+        xsd.push_str("          <xs:sequence>\n");
+        indent = 12;
+    }
+
+    let _ = write!(xsd, "{:indent$}<xs:element ", "", indent = indent);
+    if child.text_only {
+        let _ = write!(
+            xsd,
+            r#"name="{}" type="{}""#,
+            escape_bytes(child.name),
+            simple_type(child.ty)
+        );
+    } else {
+        let _ = write!(xsd, r#"ref="{}""#, escape_bytes(child.name));
+    }
+    if child.min_occurs != 1 {
+        let _ = write!(xsd, r#" minOccurs="{}""#, child.min_occurs);
+    }
+    match child.max_occurs {
+        Some(1) => {}
+        Some(max) => {
+            let _ = write!(xsd, r#" maxOccurs="{}""#, max);
+        }
+        None => xsd.push_str(r#" maxOccurs="unbounded""#),
+    }
+    if let (Some(default), true) = (child.default, child.text_only) {
+        let _ = write!(xsd, r#" default="{}""#, escape(default));
+    }
+    xsd.push_str("/>\n");
+
+    if child.wrapper.is_some() {
+        xsd.push_str("          </xs:sequence>\n");
+        xsd.push_str("        </xs:complexType>\n");
+        xsd.push_str("      </xs:element>\n");
+    }
+}
+
+// Maps the Rust type to the XML Schema type with the same lexical space
+fn simple_type(ty: &str) -> &'static str {
+    // only the name matters, e.g. `std::time::Duration` and `Cow<'a, str>`
+    let name = ty.split('<').next().unwrap_or(ty);
+    let name = name.rsplit("::").next().unwrap_or(name);
+    let name = name.rsplit(|c| c == '&' || c == ' ').next().unwrap_or(name);
+    match name {
+        "bool" => "xs:boolean",
+        "u8" => "xs:unsignedByte",
+        "u16" => "xs:unsignedShort",
+        "u32" => "xs:unsignedInt",
+        "u64" | "usize" => "xs:unsignedLong",
+        "u128" => "xs:nonNegativeInteger",
+        "i8" => "xs:byte",
+        "i16" => "xs:short",
+        "i32" => "xs:int",
+        "i64" | "isize" => "xs:long",
+        "i128" => "xs:integer",
+        "NonZeroU8" | "NonZeroU16" | "NonZeroU32" | "NonZeroU64" | "NonZeroU128"
+        | "NonZeroUsize" => "xs:positiveInteger",
+        "f32" => "xs:float",
+        "f64" => "xs:double",
+        "Decimal" => "xs:decimal",
+        "Duration" => "xs:duration",
+        "Base64" => "xs:base64Binary",
+        "HexBytes" | "HexArray" => "xs:hexBinary",
+        "DateTime" | "NaiveDateTime" | "OffsetDateTime" | "PrimitiveDateTime" => "xs:dateTime",
+        "NaiveDate" | "Date" => "xs:date",
+        "NaiveTime" | "Time" => "xs:time",
+        _ => "xs:string",
+    }
+}
+
+fn escape(value: &str) -> String {
+    String::from_utf8_lossy(&quick_xml::escape::escape(value.as_bytes())).into_owned()
+}
+
+fn escape_bytes(value: &[u8]) -> String {
+    escape(&String::from_utf8_lossy(value))
+}
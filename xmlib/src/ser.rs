@@ -2,6 +2,34 @@
 
 use std::io::{self, Write};
 
+pub use crate::error::SerError as Error;
+
+/// Serializes the value to bytes.
+///
+/// In contrast to [`write_to_string`] the output isn't checked for valid utf-8.
+/// The bytes are valid utf-8 by construction if all serialized values are.
+///
+/// # Example
+/// ```
+/// use xmlib_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Rectangle {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// let rect = Rectangle { width: 13, height: 42 };
+///
+/// let serialized = xmlib::ser::write_to_vec(rect).unwrap();
+/// assert_eq!(serialized, br#"<rectangle width="13" height="42"/>"#);
+/// ```
+pub fn write_to_vec<T: Serialize<Vec<u8>>>(value: T) -> Result<Vec<u8>, Error> {
+    let mut writer = XmlWriter::new(Vec::with_capacity(128))?;
+    value.ser(&mut writer)?;
+    Ok(writer.into_inner())
+}
+
 /// Serializes the value to a string.
 ///
 /// # Example
@@ -19,33 +47,756 @@ use std::io::{self, Write};
 /// let serialized = xmlib::ser::write_to_string(rect).unwrap();
 /// assert_eq!(serialized, r#"<rectangle width="13" height="42"/>"#);
 /// ```
-pub fn write_to_string<T: Serialize<Vec<u8>>>(value: T) -> io::Result<String> {
-    let mut writer = XmlWriter::new(Vec::with_capacity(128))?;
-    value.ser(&mut writer)?;
-    String::from_utf8(writer.into_inner())
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+pub fn write_to_string<T: Serialize<Vec<u8>>>(value: T) -> Result<String, Error> {
+    String::from_utf8(write_to_vec(value)?).map_err(|e| Error::Unrepresentable {
+        ty_name: String::from(std::any::type_name::<T>()),
+        reason: e.to_string(),
+    })
+}
+
+/// Writes the items of an iterator wrapped in the element `wrapper` without collecting them.
+///
+/// Returns the number of written items. The element is written as `<wrapper/>` if there are no
+/// items. Errors of the items are converted into [`io::Error`].
+///
+/// # Example
+/// ```
+/// use xmlib::ser::XmlWriter;
+/// use xmlib_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Row {
+///     id: u32,
+/// }
+///
+/// let mut writer = XmlWriter::new(Vec::new()).unwrap();
+/// let rows = (1..=3).map(|id| Row { id });
+/// let count = xmlib::ser::write_iter(&mut writer, b"rows", rows).unwrap();
+///
+/// assert_eq!(count, 3);
+/// assert_eq!(
+///     writer.into_inner(),
+///     br#"<rows><row id="1"/><row id="2"/><row id="3"/></rows>"#
+/// );
+/// ```
+pub fn write_iter<W, T, I>(writer: &mut XmlWriter<W>, wrapper: &[u8], items: I) -> io::Result<usize>
+where
+    W: Write,
+    T: Serialize<W>,
+    I: IntoIterator<Item = T>,
+{
+    write_wrapped(writer, wrapper, None, items.into_iter())
+}
+
+/// Writes the items like [`write_iter`] and their number into the attribute `count_attr` of the
+/// wrapper, e.g. `<rows count="3">`.
+///
+/// The start tag is written before the items, so their number must be known in advance from
+/// [`ExactSizeIterator::len`]. An iterator returning another number of items than announced
+/// causes an error of kind [`io::ErrorKind::InvalidData`] after writing them.
+///
+/// # Example
+/// ```
+/// use xmlib::ser::XmlWriter;
+///
+/// let mut writer = XmlWriter::new(Vec::new()).unwrap();
+/// let names = vec!["a", "b"];
+/// let count = xmlib::ser::write_iter_counted(&mut writer, b"names", b"count", names).unwrap();
+///
+/// assert_eq!(count, 2);
+/// assert_eq!(writer.into_inner(), br#"<names count="2">ab</names>"#);
+/// ```
+pub fn write_iter_counted<W, T, I>(
+    writer: &mut XmlWriter<W>,
+    wrapper: &[u8],
+    count_attr: &[u8],
+    items: I,
+) -> io::Result<usize>
+where
+    W: Write,
+    T: Serialize<W>,
+    I: IntoIterator<Item = T>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let items = items.into_iter();
+    let len = items.len();
+    let count = write_wrapped(writer, wrapper, Some((count_attr, len)), items)?;
+    if count != len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "the iterator announced {} items but returned {}",
+                len, count
+            ),
+        ));
+    }
+    Ok(count)
+}
+
+fn write_wrapped<W, T, I>(
+    writer: &mut XmlWriter<W>,
+    wrapper: &[u8],
+    count: Option<(&[u8], usize)>,
+    items: I,
+) -> io::Result<usize>
+where
+    W: Write,
+    T: Serialize<W>,
+    I: Iterator<Item = T>,
+{
+    let mut items = items.peekable();
+    writer.start_element()?;
+    writer.write_all(b"<")?;
+    writer.write_all(wrapper)?;
+    if let Some((attr, len)) = count {
+        let quote = writer.quote();
+        writer.write_all(b" ")?;
+        writer.write_all(attr)?;
+        writer.write_all(b"=")?;
+        writer.write_all(quote)?;
+        writer.write_all(itoa::Buffer::new().format(len).as_bytes())?;
+        writer.write_all(quote)?;
+    }
+    if items.peek().is_none() {
+        writer.write_all(b"/>")?;
+        writer.end_element(true)?;
+        return Ok(0);
+    }
+    writer.write_all(b">")?;
+
+    let mut written = 0;
+    for item in items {
+        item.ser(writer)?;
+        written += 1;
+    }
+
+    writer.end_element(false)?;
+    writer.write_all(b"</")?;
+    writer.write_all(wrapper)?;
+    writer.write_all(b">")?;
+    Ok(written)
+}
+
+/// Configuration of the output, which creates configured [`XmlWriter`]s.
+///
+/// # Example
+/// ```
+/// use xmlib::ser::{BoolStyle, QuoteStyle, Serializer};
+/// use xmlib_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Layer {
+///     visible: bool,
+///     #[xmlib(value, multiple)]
+///     rects: Vec<Rectangle>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Rectangle {
+///     width: u32,
+/// }
+///
+/// let layer = Layer { visible: true, rects: vec![Rectangle { width: 13 }] };
+///
+/// let serialized = Serializer::new()
+///     .declaration(true)
+///     .indent(' ', 2)
+///     .quotes(QuoteStyle::Single)
+///     .bools(BoolStyle::Words)
+///     .to_string(&layer)
+///     .unwrap();
+/// assert_eq!(
+///     serialized,
+///     "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+///      <layer visible='true'>\n  <rectangle width='13'/>\n</layer>"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Serializer {
+    declaration: bool,
+    indent: Option<(u8, usize)>,
+    quote_style: QuoteStyle,
+    bool_style: BoolStyle,
+}
+
+impl Serializer {
+    /// Creates a [`Serializer`] with the default options, which writes the same output as
+    /// [`write_to_vec`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the xml declaration is written before the value,
+    /// see [`XmlWriter::write_xml_start`].
+    pub fn declaration(mut self, declaration: bool) -> Self {
+        self.declaration = declaration;
+        self
+    }
+
+    /// Indents child elements, see [`XmlWriter::with_indent`].
+    ///
+    /// # Panics
+    /// Panics if `indent` isn't a space or a tab.
+    pub fn indent(mut self, indent: char, count: usize) -> Self {
+        self.indent = Some((check_indent(indent), count));
+        self
+    }
+
+    /// Sets the quote character of attribute values, see [`XmlWriter::with_quote_style`].
+    pub fn quotes(mut self, quote_style: QuoteStyle) -> Self {
+        self.quote_style = quote_style;
+        self
+    }
+
+    /// Sets how [`bool`] values are written, see [`XmlWriter::with_bool_style`].
+    pub fn bools(mut self, bool_style: BoolStyle) -> Self {
+        self.bool_style = bool_style;
+        self
+    }
+
+    /// Creates a configured [`XmlWriter`], writing the declaration if enabled.
+    pub fn writer<W: Write>(&self, writer: W) -> io::Result<XmlWriter<W>> {
+        let mut writer = XmlWriter::new(writer)?
+            .with_quote_style(self.quote_style)
+            .with_bool_style(self.bool_style);
+        writer.indent = self.indent;
+        if self.declaration {
+            writer.write_xml_start()?;
+        }
+        Ok(writer)
+    }
+
+    /// Serializes the value to the writer.
+    pub fn to_writer<W: Write, T: Serialize<W> + ?Sized>(
+        &self,
+        writer: W,
+        value: &T,
+    ) -> Result<(), Error> {
+        let mut writer = self.writer(writer)?;
+        value.ser(&mut writer)
+    }
+
+    /// Serializes the value to bytes, see [`write_to_vec`].
+    pub fn to_vec<T: Serialize<Vec<u8>> + ?Sized>(&self, value: &T) -> Result<Vec<u8>, Error> {
+        let mut writer = self.writer(Vec::with_capacity(128))?;
+        value.ser(&mut writer)?;
+        Ok(writer.into_inner())
+    }
+
+    /// Serializes the value to a string, see [`write_to_string`].
+    pub fn to_string<T: Serialize<Vec<u8>> + ?Sized>(&self, value: &T) -> Result<String, Error> {
+        String::from_utf8(self.to_vec(value)?).map_err(|e| Error::Unrepresentable {
+            ty_name: String::from(std::any::type_name::<T>()),
+            reason: e.to_string(),
+        })
+    }
+}
+
+// Returns the byte of the indentation character, panicking if it isn't a space or a tab
+fn check_indent(indent: char) -> u8 {
+    assert!(
+        indent == ' ' || indent == '\t',
+        "indent must be a space or a tab, got {:?}",
+        indent
+    );
+    indent as u8
+}
+
+/// Wrapper which implements [`Display`](std::fmt::Display) by serializing the value.
+///
+/// This avoids the intermediate [`String`] of [`write_to_string`], e.g. for logging.
+///
+/// The serialized data is written through [`FmtWriter`], so every write of the [`Serialize`]
+/// implementation must be valid utf-8. Otherwise formatting fails with [`std::fmt::Error`].
+///
+/// # Example
+/// ```
+/// use xmlib::ser::AsXml;
+/// use xmlib_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Rectangle {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// let rect = Rectangle { width: 13, height: 42 };
+///
+/// let formatted = format!("rect: {}", AsXml(&rect));
+/// assert_eq!(formatted, r#"rect: <rectangle width="13" height="42"/>"#);
+/// ```
+pub struct AsXml<'a, T>(pub &'a T);
+
+impl<'a, T> std::fmt::Display for AsXml<'a, T>
+where
+    T: for<'w> Serialize<FmtWriter<'w>>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut writer = XmlWriter::new(FmtWriter::new(f)).map_err(|_| std::fmt::Error)?;
+        self.0.ser(&mut writer).map_err(|_| std::fmt::Error)
+    }
+}
+
+/// Adapter to use a [`std::fmt::Write`] as [`std::io::Write`].
+///
+/// Every write must be valid utf-8, otherwise an error of kind [`io::ErrorKind::InvalidData`] is
+/// returned.
+pub struct FmtWriter<'a> {
+    inner: &'a mut dyn std::fmt::Write,
+}
+
+impl<'a> FmtWriter<'a> {
+    /// Creates a new [`FmtWriter`]
+    pub fn new(inner: &'a mut dyn std::fmt::Write) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a> Write for FmtWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let s =
+            std::str::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.inner
+            .write_str(s)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Quote character used for attribute values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// `name="value"`
+    Double,
+    /// `name='value'`
+    Single,
+}
+
+// `#[default]` on enum variants requires Rust 1.62
+impl Default for QuoteStyle {
+    fn default() -> Self {
+        Self::Double
+    }
+}
+
+/// Representation of [`bool`] values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoolStyle {
+    /// `1` and `0`
+    Digits,
+    /// `true` and `false`
+    Words,
+}
+
+impl Default for BoolStyle {
+    fn default() -> Self {
+        Self::Digits
+    }
 }
 
 /// Interface for writing XML values
 pub struct XmlWriter<W: Write> {
     writer: W,
+    quote_style: QuoteStyle,
+    bool_style: BoolStyle,
+    /// Character and its count per level, `None` writes everything on one line.
+    indent: Option<(u8, usize)>,
+    /// Number of currently open elements.
+    depth: usize,
+    /// The depth from which on the content isn't indented, because it contains text.
+    inline_depth: Option<usize>,
+    /// Whether the last written tag was the end of an element.
+    after_end: bool,
+    /// Whether anything was written before the root element, e.g. the declaration.
+    after_declaration: bool,
+    /// Buffer of [`XmlWriter::text_buffer`], which is given back after writing its content.
+    scratch: Vec<u8>,
 }
 
 impl<W: Write> XmlWriter<W> {
     /// Creates a new [`XmlWriter`]
     pub fn new(writer: W) -> io::Result<Self> {
-        let s = Self { writer };
+        let s = Self {
+            writer,
+            quote_style: QuoteStyle::default(),
+            bool_style: BoolStyle::default(),
+            indent: None,
+            depth: 0,
+            inline_depth: None,
+            after_end: false,
+            after_declaration: false,
+            scratch: Vec::new(),
+        };
         // TODO
         //s.write_xml_start()?;
         Ok(s)
     }
 
+    /// Sets the quote character used for attribute values.
+    ///
+    /// # Example
+    /// ```
+    /// use xmlib::ser::{QuoteStyle, Serialize, XmlWriter};
+    /// use xmlib_derive::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Point {
+    ///     x: u32,
+    /// }
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new()).unwrap().with_quote_style(QuoteStyle::Single);
+    /// Point { x: 3 }.ser(&mut writer).unwrap();
+    /// assert_eq!(writer.into_inner(), b"<point x='3'/>");
+    /// ```
+    #[inline]
+    pub fn with_quote_style(mut self, quote_style: QuoteStyle) -> Self {
+        self.quote_style = quote_style;
+        self
+    }
+
+    /// Returns the quote character used for attribute values.
+    #[inline]
+    pub fn quote(&self) -> &'static [u8] {
+        match self.quote_style {
+            QuoteStyle::Double => b"\"",
+            QuoteStyle::Single => b"'",
+        }
+    }
+
+    /// Sets how [`bool`] values are written.
+    ///
+    /// Fields annotated with `#[xmlib(bool_format = "...")]` keep their format.
+    ///
+    /// # Example
+    /// ```
+    /// use xmlib::ser::{BoolStyle, Serialize, XmlWriter};
+    /// use xmlib_derive::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Switch {
+    ///     on: bool,
+    /// }
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new()).unwrap().with_bool_style(BoolStyle::Words);
+    /// Switch { on: true }.ser(&mut writer).unwrap();
+    /// assert_eq!(writer.into_inner(), br#"<switch on="true"/>"#);
+    /// ```
+    #[inline]
+    pub fn with_bool_style(mut self, bool_style: BoolStyle) -> Self {
+        self.bool_style = bool_style;
+        self
+    }
+
+    /// Returns how [`bool`] values are written.
+    #[inline]
+    pub fn bool_style(&self) -> BoolStyle {
+        self.bool_style
+    }
+
+    /// Indents every child element on a new line by `count` times `indent` per level.
+    ///
+    /// The content of elements with text, e.g. `value_buf` or `mixed` fields, isn't indented
+    /// because the whitespace would change the text. The indentation of other elements is
+    /// whitespace text too, so enable `trim_text` of the [`XmlReader`](crate::de::XmlReader)
+    /// to read the output back.
+    ///
+    /// # Panics
+    /// Panics if `indent` isn't a space or a tab.
+    ///
+    /// # Example
+    /// ```
+    /// use xmlib::ser::{Serialize, XmlWriter};
+    /// use xmlib_derive::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Point {
+    ///     x: u32,
+    ///     #[xmlib(element)]
+    ///     y: u32,
+    /// }
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new()).unwrap().with_indent(' ', 2);
+    /// Point { x: 1, y: 2 }.ser(&mut writer).unwrap();
+    /// assert_eq!(writer.into_inner(), b"<point x=\"1\">\n  <y>2</y>\n</point>");
+    /// ```
+    #[inline]
+    pub fn with_indent(mut self, indent: char, count: usize) -> Self {
+        self.indent = Some((check_indent(indent), count));
+        self
+    }
+
+    /// Called by [`Serialize`] implementations before the start tag of an element.
+    ///
+    /// This writes the line break and indentation if enabled.
+    #[inline]
+    pub fn start_element(&mut self) -> io::Result<()> {
+        if (self.depth > 0 || self.after_declaration) && !self.is_inline() {
+            self.write_indent()?;
+        }
+        self.depth += 1;
+        self.after_end = false;
+        Ok(())
+    }
+
+    /// Called by [`Serialize`] implementations before the end tag of an element or after an
+    /// empty element like `<name/>`.
+    #[inline]
+    pub fn end_element(&mut self, empty: bool) -> io::Result<()> {
+        let inline = self.is_inline();
+        if self.inline_depth == Some(self.depth) {
+            self.inline_depth = None;
+        }
+        self.depth = self.depth.saturating_sub(1);
+        if !empty && self.after_end && !inline {
+            self.write_indent()?;
+        }
+        self.after_end = true;
+        Ok(())
+    }
+
+    /// Called by [`Serialize`] implementations after the start tag of an element whose content
+    /// contains text, so the content isn't indented.
+    #[inline]
+    pub fn start_inline(&mut self) {
+        if self.inline_depth.is_none() {
+            self.inline_depth = Some(self.depth);
+        }
+    }
+
+    // Whether the current element is inside of an element containing text
+    #[inline]
+    fn is_inline(&self) -> bool {
+        matches!(self.inline_depth, Some(depth) if self.depth >= depth)
+    }
+
+    // Writes a line break and the indentation of the current depth
+    #[inline]
+    fn write_indent(&mut self) -> io::Result<()> {
+        let (indent, count) = match self.indent {
+            Some(indent) => indent,
+            None => return Ok(()),
+        };
+        self.writer.write_all(b"\n")?;
+        let buf = [indent; 64];
+        let mut len = self.depth * count;
+        while len > 0 {
+            let n = len.min(buf.len());
+            self.writer.write_all(&buf[..n])?;
+            len -= n;
+        }
+        Ok(())
+    }
+
     /// Writes the start of a xml file
     ///
     /// `<?xml version="1.0" encoding="UTF-8" standalone="yes"?>`
+    ///
+    /// Use [`XmlWriter::write_declaration`] for other values.
     pub fn write_xml_start(&mut self) -> io::Result<()> {
-        self.writer
-            .write_all(br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#)
+        self.write_declaration("1.0", Some("UTF-8"), Some(true))
+    }
+
+    /// Writes the xml declaration with the given values.
+    ///
+    /// `encoding` and `standalone` are omitted if they are `None`. The values are written as
+    /// given, the output itself is always UTF-8.
+    ///
+    /// # Example
+    /// ```
+    /// use xmlib::ser::XmlWriter;
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new()).unwrap();
+    /// writer.write_declaration("1.0", Some("ISO-8859-1"), None).unwrap();
+    /// assert_eq!(
+    ///     writer.into_inner(),
+    ///     br#"<?xml version="1.0" encoding="ISO-8859-1"?>"#
+    /// );
+    /// ```
+    pub fn write_declaration(
+        &mut self,
+        version: &str,
+        encoding: Option<&str>,
+        standalone: Option<bool>,
+    ) -> io::Result<()> {
+        self.after_declaration = true;
+        self.writer.write_all(b"<?xml version=\"")?;
+        self.writer.write_all(version.as_bytes())?;
+        self.writer.write_all(b"\"")?;
+        if let Some(encoding) = encoding {
+            self.writer.write_all(b" encoding=\"")?;
+            self.writer.write_all(encoding.as_bytes())?;
+            self.writer.write_all(b"\"")?;
+        }
+        match standalone {
+            Some(true) => self.writer.write_all(b" standalone=\"yes\"")?,
+            Some(false) => self.writer.write_all(b" standalone=\"no\"")?,
+            None => {}
+        }
+        self.writer.write_all(b"?>")
+    }
+
+    /// Writes the text as content of an element.
+    ///
+    /// `&` and `<` are escaped and `>` only after `]]`, where it would end a CDATA section.
+    /// Quotes are written as they are, since they only need to be escaped in attribute values.
+    ///
+    /// # Example
+    /// ```
+    /// use xmlib::ser::XmlWriter;
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new()).unwrap();
+    /// writer.write_text(br#"a < b && "c" ]]> d > e"#).unwrap();
+    /// assert_eq!(writer.into_inner(), br#"a &lt; b &amp;&amp; "c" ]]&gt; d > e"#);
+    /// ```
+    pub fn write_text(&mut self, text: &[u8]) -> io::Result<()> {
+        let mut start = 0;
+        for (i, c) in text.iter().enumerate() {
+            let escaped: &[u8] = match c {
+                b'&' => b"&amp;",
+                b'<' => b"&lt;",
+                b'>' if text[..i].ends_with(b"]]") => b"&gt;",
+                _ => continue,
+            };
+            self.writer.write_all(&text[start..i])?;
+            self.writer.write_all(escaped)?;
+            start = i + 1;
+        }
+        self.writer.write_all(&text[start..])
+    }
+
+    /// Creates a writer with the same configuration, whose output is written as text, CDATA
+    /// section or attribute value by [`XmlWriter::write_text_buffer`],
+    /// [`XmlWriter::write_cdata_buffer`] or [`XmlWriter::write_attr_buffer`].
+    ///
+    /// The buffer is reused after it was written, so serializing text doesn't allocate for every
+    /// value.
+    ///
+    /// # Example
+    /// ```
+    /// use xmlib::ser::{Serialize, XmlWriter};
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new()).unwrap();
+    /// let mut text = writer.text_buffer();
+    /// "a < b".ser(&mut text).unwrap();
+    /// writer.write_text_buffer(text).unwrap();
+    /// assert_eq!(writer.into_inner(), b"a &lt; b");
+    /// ```
+    pub fn text_buffer(&mut self) -> XmlWriter<Vec<u8>> {
+        XmlWriter {
+            writer: std::mem::take(&mut self.scratch),
+            quote_style: self.quote_style,
+            bool_style: self.bool_style,
+            indent: self.indent,
+            depth: 0,
+            inline_depth: None,
+            after_end: false,
+            after_declaration: false,
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Writes the output of a writer created by [`XmlWriter::text_buffer`] as text, see
+    /// [`XmlWriter::write_text`].
+    pub fn write_text_buffer(&mut self, buffer: XmlWriter<Vec<u8>>) -> io::Result<()> {
+        let buffer = buffer.into_inner();
+        let result = self.write_text(&buffer);
+        self.recycle_scratch(buffer);
+        result
+    }
+
+    /// Writes the output of a writer created by [`XmlWriter::text_buffer`] as CDATA section, see
+    /// [`XmlWriter::write_cdata`].
+    pub fn write_cdata_buffer(&mut self, buffer: XmlWriter<Vec<u8>>) -> io::Result<()> {
+        let buffer = buffer.into_inner();
+        let result = self.write_cdata(&buffer);
+        self.recycle_scratch(buffer);
+        result
+    }
+
+    /// Writes the output of a writer created by [`XmlWriter::text_buffer`] as attribute value,
+    /// see [`XmlWriter::write_attr_value`].
+    pub fn write_attr_buffer(&mut self, buffer: XmlWriter<Vec<u8>>) -> io::Result<()> {
+        let buffer = buffer.into_inner();
+        let result = self.write_attr_value(&buffer);
+        self.recycle_scratch(buffer);
+        result
+    }
+
+    // Keeps the allocation of a written buffer for the next `text_buffer`
+    #[inline]
+    fn recycle_scratch(&mut self, mut buffer: Vec<u8>) {
+        buffer.clear();
+        self.scratch = buffer;
+    }
+
+    /// Writes the value of an attribute without the surrounding quotes.
+    ///
+    /// `&`, `<` and the quote character of the [`QuoteStyle`] are escaped, the other quote
+    /// character is written as it is.
+    ///
+    /// # Example
+    /// ```
+    /// use xmlib::ser::{QuoteStyle, XmlWriter};
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new()).unwrap().with_quote_style(QuoteStyle::Single);
+    /// writer.write_attr_value(br#"it's "a" & <b>"#).unwrap();
+    /// assert_eq!(writer.into_inner(), br#"it&apos;s "a" &amp; &lt;b>"#);
+    /// ```
+    pub fn write_attr_value(&mut self, value: &[u8]) -> io::Result<()> {
+        let (quote, escaped_quote): (u8, &[u8]) = match self.quote_style {
+            QuoteStyle::Double => (b'"', b"&quot;"),
+            QuoteStyle::Single => (b'\'', b"&apos;"),
+        };
+        let mut start = 0;
+        for (i, &c) in value.iter().enumerate() {
+            let escaped: &[u8] = match c {
+                b'&' => b"&amp;",
+                b'<' => b"&lt;",
+                c if c == quote => escaped_quote,
+                _ => continue,
+            };
+            self.writer.write_all(&value[start..i])?;
+            self.writer.write_all(escaped)?;
+            start = i + 1;
+        }
+        self.writer.write_all(&value[start..])
+    }
+
+    /// Writes the data as CDATA section.
+    ///
+    /// If the data contains `]]>` it is split into multiple sections.
+    pub fn write_cdata(&mut self, mut data: &[u8]) -> io::Result<()> {
+        self.writer.write_all(b"<![CDATA[")?;
+        while let Some(i) = data.windows(3).position(|w| w == b"]]>") {
+            // end the section between `]]` and `>`
+            self.writer.write_all(&data[..i + 2])?;
+            self.writer.write_all(b"]]><![CDATA[")?;
+            data = &data[i + 2..];
+        }
+        self.writer.write_all(data)?;
+        self.writer.write_all(b"]]>")
+    }
+
+    /// Writes the text as comment `<!--text-->`.
+    ///
+    /// Returns an error of the kind [`io::ErrorKind::InvalidInput`] if the text contains `--` or
+    /// ends with `-`, which isn't allowed in comments.
+    pub fn write_comment(&mut self, text: &[u8]) -> io::Result<()> {
+        if text.windows(2).any(|w| w == b"--") || text.ends_with(b"-") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid comment: {}", String::from_utf8_lossy(text)),
+            ));
+        }
+        self.start_element()?;
+        self.writer.write_all(b"<!--")?;
+        self.writer.write_all(text)?;
+        self.writer.write_all(b"-->")?;
+        self.end_element(true)
     }
 
     /// Consumes the `XmlWriter`, returning the wrapped writer.
@@ -74,17 +825,55 @@ pub trait Serialize<W: Write> {
     /// Serialization function
     ///
     /// Mark this as `#[inline]`
-    fn ser(&self, writer: &mut XmlWriter<W>) -> io::Result<()>;
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error>;
+
+    /// Gets the name of the element written by this type.
+    ///
+    /// This allows containers to write the right start tag generically. Types which aren't
+    /// serialized as a single element with a fixed name return `None`.
+    #[inline]
+    fn element_name() -> Option<&'static [u8]>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Gets the namespace uri of the element written by this type, if any.
+    #[inline]
+    fn element_namespace() -> Option<&'static [u8]>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+/// Serialize an element with an additional attribute in its start tag.
+///
+/// This is implemented by the derive for named structs and used by enums with
+/// `#[xmlib(tag_attr = "...")]` to write the discriminant into the element of the variant.
+pub trait SerializeWithAttr<W: Write>: Serialize<W> {
+    /// Serializes the element with `attr` as first attribute.
+    ///
+    /// The value of the attribute is escaped, see [`XmlWriter::write_attr_value`]. `None` is the
+    /// same as [`Serialize::ser`].
+    fn ser_with_attr(
+        &self,
+        writer: &mut XmlWriter<W>,
+        attr: Option<(&[u8], &[u8])>,
+    ) -> Result<(), Error>;
 }
 
 macro_rules! impl_ser_num {
     ($t:ty) => {
         impl<W: Write> Serialize<W> for $t {
             #[inline]
-            fn ser(&self, writer: &mut XmlWriter<W>) -> io::Result<()> {
+            fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
                 let mut buffer = itoa::Buffer::new();
                 let s = buffer.format(*self);
-                writer.write_all(s.as_bytes())
+                writer.write_all(s.as_bytes())?;
+                Ok(())
             }
         }
     };
@@ -97,10 +886,11 @@ macro_rules! impl_ser_float {
     ($t:ty) => {
         impl<W: Write> Serialize<W> for $t {
             #[inline]
-            fn ser(&self, writer: &mut XmlWriter<W>) -> io::Result<()> {
+            fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
                 let mut buffer = ryu::Buffer::new();
                 let s = buffer.format(*self);
-                writer.write_all(s.as_bytes())
+                writer.write_all(s.as_bytes())?;
+                Ok(())
             }
         }
     };
@@ -112,14 +902,120 @@ macro_rules! impl_ser_float {
 impl_ser_num!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
 impl_ser_float!(f32, f64);
 
+/// Floats which can be serialized without exponent or with a limited precision.
+///
+/// This is used by fields annotated with `#[xmlib(float_format(precision = 6))]` or
+/// `#[xmlib(float_format(no_exponent))]`. The output never uses an exponent and trailing zeros
+/// of the fraction are removed, e.g. `0.30000000000000004` with precision 2 is written as `0.3`
+/// and `2.0` is still written as `2.0`.
+///
+/// # Example
+/// ```
+/// use xmlib_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Sample {
+///     #[xmlib(float_format(precision = 2))]
+///     value: f64,
+///     #[xmlib(float_format(no_exponent))]
+///     tiny: f64,
+/// }
+///
+/// let sample = Sample { value: 0.1 + 0.2, tiny: 1e-7 };
+/// let serialized = xmlib::ser::write_to_string(sample).unwrap();
+/// assert_eq!(serialized, r#"<sample value="0.3" tiny="0.0000001"/>"#);
+/// ```
+pub trait FormatFloat {
+    /// Writes the float without exponent, rounded to `precision` decimal places if given.
+    fn ser_formatted<W: Write>(
+        &self,
+        precision: Option<usize>,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), Error>;
+}
+
+macro_rules! impl_format_float {
+    ($($t:ty),+$(,)?) => {
+        $(
+            impl FormatFloat for $t {
+                fn ser_formatted<W: Write>(
+                    &self,
+                    precision: Option<usize>,
+                    writer: &mut XmlWriter<W>,
+                ) -> Result<(), Error> {
+                    if !self.is_finite() {
+                        return self.ser(writer);
+                    }
+                    // `Display` never uses an exponent and prints the shortest representation
+                    let s = match precision {
+                        Some(precision) => format!("{:.*}", precision, self),
+                        None => self.to_string(),
+                    };
+                    let mut s = match s.find('.') {
+                        // keep one digit after the point like the default output does
+                        Some(point) => s[..(point + 2).max(s.trim_end_matches('0').len())].to_owned(),
+                        None => s + ".0",
+                    };
+                    // rounding a small negative number must not result in `-0.0`
+                    if s == "-0.0" && *self != 0.0 {
+                        s.remove(0);
+                    }
+                    writer.write_all(s.as_bytes())?;
+                    Ok(())
+                }
+            }
+        )+
+    };
+}
+
+impl_format_float!(f32, f64);
+
+macro_rules! impl_ser_non_zero {
+    ($($t:ident),+$(,)?) => {
+        $(
+            impl<W: Write> Serialize<W> for std::num::$t {
+                #[inline]
+                fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+                    self.get().ser(writer)
+                }
+            }
+        )+
+    };
+}
+
+impl_ser_non_zero!(
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize,
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize,
+);
+
 impl<W: Write, T> Serialize<W> for &T
 where
     T: Serialize<W>,
 {
     #[inline]
-    fn ser(&self, writer: &mut XmlWriter<W>) -> io::Result<()> {
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
         T::ser(self, writer)
     }
+
+    #[inline]
+    fn element_name() -> Option<&'static [u8]> {
+        T::element_name()
+    }
+
+    #[inline]
+    fn element_namespace() -> Option<&'static [u8]> {
+        T::element_namespace()
+    }
 }
 
 impl<W: Write, T> Serialize<W> for &mut T
@@ -127,32 +1023,204 @@ where
     T: Serialize<W>,
 {
     #[inline]
-    fn ser(&self, writer: &mut XmlWriter<W>) -> io::Result<()> {
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        T::ser(self, writer)
+    }
+
+    #[inline]
+    fn element_name() -> Option<&'static [u8]> {
+        T::element_name()
+    }
+
+    #[inline]
+    fn element_namespace() -> Option<&'static [u8]> {
+        T::element_namespace()
+    }
+}
+
+impl<W: Write, T> Serialize<W> for Box<T>
+where
+    T: Serialize<W>,
+{
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        T::ser(self, writer)
+    }
+
+    #[inline]
+    fn element_name() -> Option<&'static [u8]> {
+        T::element_name()
+    }
+
+    #[inline]
+    fn element_namespace() -> Option<&'static [u8]> {
+        T::element_namespace()
+    }
+}
+
+/// Allows heterogeneous collections like `Vec<Box<dyn Serialize<W>>>`.
+impl<'a, W: Write> Serialize<W> for Box<dyn Serialize<W> + 'a> {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        (**self).ser(writer)
+    }
+}
+
+impl<'a, W: Write> Serialize<W> for &(dyn Serialize<W> + 'a) {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        (**self).ser(writer)
+    }
+}
+
+impl<W: Write, T> Serialize<W> for std::rc::Rc<T>
+where
+    T: Serialize<W>,
+{
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        T::ser(self, writer)
+    }
+
+    #[inline]
+    fn element_name() -> Option<&'static [u8]> {
+        T::element_name()
+    }
+
+    #[inline]
+    fn element_namespace() -> Option<&'static [u8]> {
+        T::element_namespace()
+    }
+}
+
+impl<W: Write, T> Serialize<W> for std::sync::Arc<T>
+where
+    T: Serialize<W>,
+{
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
         T::ser(self, writer)
     }
+
+    #[inline]
+    fn element_name() -> Option<&'static [u8]> {
+        T::element_name()
+    }
+
+    #[inline]
+    fn element_namespace() -> Option<&'static [u8]> {
+        T::element_namespace()
+    }
 }
 
 impl<W: Write> Serialize<W> for &str {
     #[inline]
-    fn ser(&self, writer: &mut XmlWriter<W>) -> io::Result<()> {
-        writer.write_all(self.as_bytes())
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        writer.write_all(self.as_bytes())?;
+        Ok(())
     }
 }
 
 impl<W: Write> Serialize<W> for String {
     #[inline]
-    fn ser(&self, writer: &mut XmlWriter<W>) -> io::Result<()> {
-        writer.write_all(self.as_bytes())
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        writer.write_all(self.as_bytes())?;
+        Ok(())
+    }
+}
+
+macro_rules! impl_ser_str_ptr {
+    ($($t:ty),+$(,)?) => {
+        $(
+            impl<W: Write> Serialize<W> for $t {
+                #[inline]
+                fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+                    writer.write_all(self.as_bytes())?;
+                    Ok(())
+                }
+            }
+        )+
+    };
+}
+
+impl_ser_str_ptr!(Box<str>, std::rc::Rc<str>, std::sync::Arc<str>);
+
+impl<'a, W: Write> Serialize<W> for std::borrow::Cow<'a, str> {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        writer.write_all(self.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Serialize<W> for std::path::PathBuf {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        self.as_path().ser(writer)
+    }
+}
+
+/// Xml can't represent arbitrary bytes, so paths which aren't valid utf-8 return
+/// [`Error::Unrepresentable`] instead of being written lossily.
+impl<W: Write> Serialize<W> for std::path::Path {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        match self.to_str() {
+            Some(s) => {
+                writer.write_all(s.as_bytes())?;
+                Ok(())
+            }
+            None => Err(Error::Unrepresentable {
+                ty_name: String::from("Path"),
+                reason: format!("path \"{}\" is not valid utf-8", self.display()),
+            }),
+        }
+    }
+}
+
+impl<W: Write> Serialize<W> for std::ffi::OsString {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        self.as_os_str().ser(writer)
+    }
+}
+
+impl<W: Write> Serialize<W> for std::ffi::OsStr {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        match self.to_str() {
+            Some(s) => {
+                writer.write_all(s.as_bytes())?;
+                Ok(())
+            }
+            None => Err(Error::Unrepresentable {
+                ty_name: String::from("OsStr"),
+                reason: format!("{:?} is not valid utf-8", self),
+            }),
+        }
     }
 }
 
+/// Writes nothing, see [`DeserializeBuf for ()`](crate::de::DeserializeBuf#impl-DeserializeBuf-for-()).
+impl<W: Write> Serialize<W> for () {
+    #[inline]
+    fn ser(&self, _writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Writes `1`/`0` or `true`/`false` depending on [`XmlWriter::with_bool_style`].
 impl<W: Write> Serialize<W> for bool {
     #[inline]
-    fn ser(&self, writer: &mut XmlWriter<W>) -> io::Result<()> {
-        writer.write_all(match self {
-            true => b"1",
-            false => b"0",
-        })
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        let value: &[u8] = match (writer.bool_style, self) {
+            (BoolStyle::Digits, true) => b"1",
+            (BoolStyle::Digits, false) => b"0",
+            (BoolStyle::Words, true) => b"true",
+            (BoolStyle::Words, false) => b"false",
+        };
+        writer.write_all(value)?;
+        Ok(())
     }
 }
 
@@ -161,15 +1229,25 @@ where
     T: Serialize<W>,
 {
     #[inline]
-    fn ser(&self, writer: &mut XmlWriter<W>) -> io::Result<()> {
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
         match self {
             Some(val) => val.ser(writer),
-            None => Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "cannot serialize None",
-            )),
+            None => Err(Error::Unrepresentable {
+                ty_name: String::from(std::any::type_name::<T>()),
+                reason: String::from("cannot serialize None"),
+            }),
         }
     }
+
+    #[inline]
+    fn element_name() -> Option<&'static [u8]> {
+        T::element_name()
+    }
+
+    #[inline]
+    fn element_namespace() -> Option<&'static [u8]> {
+        T::element_namespace()
+    }
 }
 
 impl<W: Write, T> Serialize<W> for &[T]
@@ -177,7 +1255,7 @@ where
     T: Serialize<W>,
 {
     #[inline]
-    fn ser(&self, writer: &mut XmlWriter<W>) -> io::Result<()> {
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
         for val in *self {
             match val.ser(writer) {
                 Ok(()) => {}
@@ -186,6 +1264,16 @@ where
         }
         Ok(())
     }
+
+    #[inline]
+    fn element_name() -> Option<&'static [u8]> {
+        T::element_name()
+    }
+
+    #[inline]
+    fn element_namespace() -> Option<&'static [u8]> {
+        T::element_namespace()
+    }
 }
 
 impl<W: Write, T> Serialize<W> for Vec<T>
@@ -193,7 +1281,7 @@ where
     T: Serialize<W>,
 {
     #[inline]
-    fn ser(&self, writer: &mut XmlWriter<W>) -> io::Result<()> {
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
         for val in self {
             match val.ser(writer) {
                 Ok(()) => {}
@@ -202,4 +1290,40 @@ where
         }
         Ok(())
     }
+
+    #[inline]
+    fn element_name() -> Option<&'static [u8]> {
+        T::element_name()
+    }
+
+    #[inline]
+    fn element_namespace() -> Option<&'static [u8]> {
+        T::element_namespace()
+    }
+}
+
+impl<W: Write, T, const N: usize> Serialize<W> for [T; N]
+where
+    T: Serialize<W>,
+{
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), Error> {
+        for val in self {
+            match val.ser(writer) {
+                Ok(()) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn element_name() -> Option<&'static [u8]> {
+        T::element_name()
+    }
+
+    #[inline]
+    fn element_namespace() -> Option<&'static [u8]> {
+        T::element_namespace()
+    }
 }
@@ -0,0 +1,32 @@
+//! Implementations for [`smallvec::SmallVec`], enabled with the `smallvec` feature.
+//!
+//! The items are serialized one after another like the items of a [`Vec`].
+
+use std::io::Write;
+
+use ::smallvec::{Array, SmallVec};
+
+use crate::de::{sealed, CollectChild};
+use crate::ser::{Serialize, XmlWriter};
+
+impl<A: Array> sealed::Sealed for SmallVec<A> {}
+
+impl<A: Array> CollectChild<A::Item> for SmallVec<A> {
+    #[inline]
+    fn push_child(&mut self, child: A::Item) {
+        self.push(child);
+    }
+}
+
+impl<W: Write, A: Array> Serialize<W> for SmallVec<A>
+where
+    A::Item: Serialize<W>,
+{
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        for val in self {
+            val.ser(writer)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+//! Implementations for [`std::time::SystemTime`] as whole seconds since the Unix epoch.
+//!
+//! The sub-second part is truncated when serializing. Times before [`UNIX_EPOCH`] can't be
+//! serialized and negative values are rejected when deserializing, as are values which overflow
+//! [`SystemTime`] on the current platform.
+
+use std::io::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::de::DeserializeBuf;
+use crate::ser::{Serialize, XmlWriter};
+use crate::{Error, ErrorKind};
+
+impl DeserializeBuf for SystemTime {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error {
+            ty_name: String::from("SystemTime"),
+            kind: ErrorKind::InvalidType(format!("{}: {}", String::from_utf8_lossy(buf), reason)),
+        };
+        let secs =
+            u64::de_buf(buf).map_err(|_| invalid("expected seconds since the unix epoch"))?;
+        UNIX_EPOCH
+            .checked_add(Duration::from_secs(secs))
+            .ok_or_else(|| invalid("out of range"))
+    }
+}
+
+impl<W: Write> Serialize<W> for SystemTime {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs().ser(writer),
+            Err(e) => Err(crate::ser::Error::Unrepresentable {
+                ty_name: String::from("SystemTime"),
+                reason: format!("{:?} before the unix epoch", e.duration()),
+            }),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Implementations for the dates and times of [`time`], enabled with the `time` feature.
+//!
+//! All values are serialized and deserialized as RFC 3339, e.g. `2024-01-15T10:30:00+05:30` for
+//! [`OffsetDateTime`], `2024-01-15T10:30:00` for [`PrimitiveDateTime`] and `2024-01-15` for
+//! [`Date`]. Fractional seconds are only written if they aren't zero.
+
+use std::io::Write;
+
+use ::time::format_description::well_known::Rfc3339;
+use ::time::format_description::FormatItem;
+use ::time::macros::{format_description, offset};
+use ::time::{Date, OffsetDateTime, PrimitiveDateTime};
+
+use crate::de::{de_with_parser, DeserializeBuf};
+use crate::ser::{Serialize, XmlWriter};
+use crate::Error;
+
+const DATE_FORMAT: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+// Maps formatting errors to `Unrepresentable`, e.g. for years with more than four digits
+fn format_error(ty_name: &str, e: ::time::error::Format) -> crate::ser::Error {
+    match e {
+        ::time::error::Format::StdIo(e) => crate::ser::Error::Io(e),
+        e => crate::ser::Error::Unrepresentable {
+            ty_name: String::from(ty_name),
+            reason: e.to_string(),
+        },
+    }
+}
+
+impl DeserializeBuf for Date {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        de_with_parser(buf, "Date", |s| Date::parse(s, DATE_FORMAT))
+    }
+}
+
+impl DeserializeBuf for OffsetDateTime {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        de_with_parser(buf, "OffsetDateTime", |s| {
+            OffsetDateTime::parse(s, &Rfc3339)
+        })
+    }
+}
+
+impl DeserializeBuf for PrimitiveDateTime {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        // RFC 3339 requires an offset, so parse it as utc
+        de_with_parser(buf, "PrimitiveDateTime", |s| {
+            OffsetDateTime::parse(&format!("{}Z", s), &Rfc3339)
+                .map(|date| PrimitiveDateTime::new(date.date(), date.time()))
+        })
+    }
+}
+
+impl<W: Write> Serialize<W> for Date {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        self.format_into(&mut **writer, DATE_FORMAT)
+            .map_err(|e| format_error("Date", e))?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Serialize<W> for OffsetDateTime {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        self.format_into(&mut **writer, &Rfc3339)
+            .map_err(|e| format_error("OffsetDateTime", e))?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Serialize<W> for PrimitiveDateTime {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        let formatted = self
+            .assume_offset(offset!(UTC))
+            .format(&Rfc3339)
+            .map_err(|e| format_error("PrimitiveDateTime", e))?;
+        // strip the offset `Z`
+        writer.write_all(&formatted.as_bytes()[..formatted.len() - 1])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,540 @@
+//! Wrapper types for values which need a special representation in xml.
+
+use std::io::Write;
+
+use crate::de::DeserializeBuf;
+use crate::ser::{Serialize, XmlWriter};
+use crate::{Error, ErrorKind};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Binary data encoded as base64 text (`xs:base64Binary`).
+///
+/// Whitespace and line breaks inside the text are ignored while deserializing.
+/// The data is serialized with padding and without line breaks.
+///
+/// # Example
+/// ```
+/// use xmlib::types::Base64;
+/// use xmlib_derive::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Thumbnail {
+///     #[xmlib(value_buf)]
+///     data: Base64,
+/// }
+///
+/// let thumbnail: Thumbnail = xmlib::de::from_str("<thumbnail>aGVs\nbG8=</thumbnail>").unwrap();
+/// assert_eq!(thumbnail.data.0, b"hello");
+///
+/// let serialized = xmlib::ser::write_to_string(thumbnail).unwrap();
+/// assert_eq!(serialized, "<thumbnail>aGVsbG8=</thumbnail>");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Base64(pub Vec<u8>);
+
+fn base64_value(c: u8) -> Option<u32> {
+    let value = match c {
+        b'A'..=b'Z' => c - b'A',
+        b'a'..=b'z' => c - b'a' + 26,
+        b'0'..=b'9' => c - b'0' + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => return None,
+    };
+    Some(u32::from(value))
+}
+
+impl DeserializeBuf for Base64 {
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        let error = |message: String| Error {
+            ty_name: String::from("Base64"),
+            kind: ErrorKind::InvalidType(message),
+        };
+        let invalid = |pos: usize| {
+            error(format!(
+                "invalid base64 character '{}' at byte {}",
+                char::from(buf[pos]).escape_default(),
+                pos
+            ))
+        };
+
+        let mut data = Vec::with_capacity(buf.len() / 4 * 3);
+        // bits of the current group of four characters
+        let mut group = 0;
+        let mut len = 0;
+        let mut padding = 0;
+        for (pos, &c) in buf.iter().enumerate() {
+            if c.is_ascii_whitespace() {
+                continue;
+            }
+            if c == b'=' {
+                // at least two characters are needed for one byte
+                if len < 2 {
+                    return Err(invalid(pos));
+                }
+                padding += 1;
+                group <<= 6;
+            } else {
+                if padding > 0 {
+                    return Err(invalid(pos));
+                }
+                group = group << 6 | base64_value(c).ok_or_else(|| invalid(pos))?;
+            }
+            len += 1;
+
+            if len == 4 {
+                let bytes = [(group >> 16) as u8, (group >> 8) as u8, group as u8];
+                data.extend_from_slice(&bytes[..3 - padding]);
+                group = 0;
+                len = 0;
+                if padding > 0 {
+                    // mark the data as finished, so following characters are rejected
+                    padding = 3;
+                }
+            }
+        }
+        if len != 0 {
+            return Err(error(format!(
+                "unexpected end of base64 data at byte {}",
+                buf.len()
+            )));
+        }
+        Ok(Self(data))
+    }
+}
+
+impl<W: Write> Serialize<W> for Base64 {
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        let mut out = [0; 256];
+        // 192 bytes of data are encoded to exactly 256 characters
+        for chunk in self.0.chunks(192) {
+            let mut len = 0;
+            for bytes in chunk.chunks(3) {
+                let group = bytes
+                    .iter()
+                    .enumerate()
+                    .fold(0, |group, (i, &b)| group | u32::from(b) << (16 - 8 * i));
+                for i in 0..4 {
+                    out[len + i] = if i <= bytes.len() {
+                        BASE64_ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize]
+                    } else {
+                        b'='
+                    };
+                }
+                len += 4;
+            }
+            writer.write_all(&out[..len])?;
+        }
+        Ok(())
+    }
+}
+
+/// Binary data encoded as hex text (`xs:hexBinary`), e.g. `FF00AA00`.
+///
+/// Both upper and lower case digits are accepted while deserializing, the data is serialized in
+/// upper case. Use [`HexArray`] if the data has a fixed length.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct HexBytes(pub Vec<u8>);
+
+/// Binary data of fixed length encoded as hex text, e.g. `rgb="FF00AA"` for `HexArray<3>`.
+///
+/// Text with the wrong length is rejected, see [`HexBytes`] for the format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HexArray<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Default for HexArray<N> {
+    fn default() -> Self {
+        Self([0; N])
+    }
+}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+// Decodes the hex text into `out`, which must be half as long as the text
+fn decode_hex(buf: &[u8], out: &mut [u8], ty_name: &str) -> Result<(), Error> {
+    let error = |message: String| Error {
+        ty_name: String::from(ty_name),
+        kind: ErrorKind::InvalidType(message),
+    };
+    if buf.len() != out.len() * 2 {
+        return Err(error(if buf.len() % 2 == 1 {
+            format!("odd number of hex digits: {}", buf.len())
+        } else {
+            format!("expected {} hex digits, got {}", out.len() * 2, buf.len())
+        }));
+    }
+    for (i, (pair, out)) in buf.chunks_exact(2).zip(out).enumerate() {
+        let digit = |pos: usize| {
+            hex_value(pair[pos % 2]).ok_or_else(|| {
+                error(format!(
+                    "invalid hex digit '{}' at byte {}",
+                    char::from(pair[pos % 2]).escape_default(),
+                    pos
+                ))
+            })
+        };
+        *out = digit(2 * i)? << 4 | digit(2 * i + 1)?;
+    }
+    Ok(())
+}
+
+fn encode_hex<W: Write>(data: &[u8], writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+    let mut out = [0; 256];
+    for chunk in data.chunks(out.len() / 2) {
+        for (b, out) in chunk.iter().zip(out.chunks_exact_mut(2)) {
+            out[0] = HEX_DIGITS[usize::from(b >> 4)];
+            out[1] = HEX_DIGITS[usize::from(b & 0xf)];
+        }
+        writer.write_all(&out[..chunk.len() * 2])?;
+    }
+    Ok(())
+}
+
+impl DeserializeBuf for HexBytes {
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        // odd lengths are rejected by `decode_hex`
+        let mut data = vec![0; buf.len() / 2];
+        decode_hex(buf, &mut data, "HexBytes")?;
+        Ok(Self(data))
+    }
+}
+
+impl<W: Write> Serialize<W> for HexBytes {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        encode_hex(&self.0, writer)
+    }
+}
+
+impl<const N: usize> DeserializeBuf for HexArray<N> {
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        let mut data = [0; N];
+        decode_hex(buf, &mut data, "HexArray")?;
+        Ok(Self(data))
+    }
+}
+
+impl<W: Write, const N: usize> Serialize<W> for HexArray<N> {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        encode_hex(&self.0, writer)
+    }
+}
+
+/// List of values separated by whitespace (`xs:list`), e.g. `ids="1 5 9 12"`.
+///
+/// The items are split at any ASCII whitespace while deserializing, so an empty text is an
+/// empty list. They are serialized separated by single spaces.
+///
+/// # Example
+/// ```
+/// use xmlib::types::SpaceList;
+/// use xmlib_derive::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Selection {
+///     ids: SpaceList<u32>,
+/// }
+///
+/// let selection: Selection = xmlib::de::from_str(r#"<selection ids=" 1  5 9 "/>"#).unwrap();
+/// assert_eq!(selection.ids.0, [1, 5, 9]);
+///
+/// let serialized = xmlib::ser::write_to_string(selection).unwrap();
+/// assert_eq!(serialized, r#"<selection ids="1 5 9"/>"#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpaceList<T>(pub Vec<T>);
+
+impl<T> Default for SpaceList<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T: DeserializeBuf> DeserializeBuf for SpaceList<T> {
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        buf.split(|c| c.is_ascii_whitespace())
+            .filter(|item| !item.is_empty())
+            .map(T::de_buf)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl<W: Write, T: Serialize<W>> Serialize<W> for SpaceList<T> {
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                writer.write_all(b" ")?;
+            }
+            item.ser(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Integers which are (de)serialized in another radix than 10, e.g. `mask="1F"`.
+///
+/// This is used by fields annotated with `#[xmlib(radix = 16)]`, the radix may be 2, 8 or 16.
+/// A prefix `0b`, `0o` or `0x` matching the radix is accepted after an optional sign but never
+/// written. Digits are serialized in upper case and accepted in both cases.
+///
+/// # Example
+/// ```
+/// use xmlib_derive::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Register {
+///     #[xmlib(radix = 16)]
+///     mask: u32,
+/// }
+///
+/// let register: Register = xmlib::de::from_str(r#"<register mask="0x1f"/>"#).unwrap();
+/// assert_eq!(register.mask, 31);
+///
+/// let serialized = xmlib::ser::write_to_string(register).unwrap();
+/// assert_eq!(serialized, r#"<register mask="1F"/>"#);
+/// ```
+pub trait RadixInteger: Sized {
+    /// Parses the integer from digits in the radix.
+    fn de_radix(buf: &[u8], radix: u32) -> Result<Self, Error>;
+
+    /// Writes the integer as digits in the radix.
+    fn ser_radix<W: Write>(
+        &self,
+        radix: u32,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), crate::ser::Error>;
+}
+
+// Parses the sign and the magnitude of an integer, `None` if the digits are invalid.
+// Overflows of the magnitude are returned as `Some(Err(()))`.
+fn parse_radix(buf: &[u8], radix: u32) -> Option<(bool, Result<u128, ()>)> {
+    let (negative, digits) = match buf {
+        [b'-', rest @ ..] => (true, rest),
+        [b'+', rest @ ..] => (false, rest),
+        _ => (false, buf),
+    };
+    let prefix = match radix {
+        2 => b'b',
+        8 => b'o',
+        16 => b'x',
+        _ => 0,
+    };
+    let digits = match digits {
+        [b'0', p, rest @ ..] if p.to_ascii_lowercase() == prefix => rest,
+        _ => digits,
+    };
+    if digits.is_empty() {
+        return None;
+    }
+
+    let mut magnitude: Option<u128> = Some(0);
+    for &c in digits {
+        let digit = (c as char).to_digit(radix)?;
+        magnitude = magnitude
+            .and_then(|m| m.checked_mul(u128::from(radix)))
+            .and_then(|m| m.checked_add(u128::from(digit)));
+    }
+    Some((negative, magnitude.ok_or(())))
+}
+
+fn ser_radix_digits<W: Write>(
+    negative: bool,
+    mut magnitude: u128,
+    radix: u32,
+    writer: &mut XmlWriter<W>,
+) -> Result<(), crate::ser::Error> {
+    // enough for `u128::MAX` in radix 2 and a sign
+    let mut buf = [0; 129];
+    let mut start = buf.len();
+    loop {
+        start -= 1;
+        let digit = (magnitude % u128::from(radix)) as u32;
+        buf[start] = char::from_digit(digit, radix).unwrap().to_ascii_uppercase() as u8;
+        magnitude /= u128::from(radix);
+        if magnitude == 0 {
+            break;
+        }
+    }
+    if negative {
+        start -= 1;
+        buf[start] = b'-';
+    }
+    writer.write_all(&buf[start..])?;
+    Ok(())
+}
+
+macro_rules! impl_radix_integer {
+    ($($t:ty),+$(,)?) => {
+        $(
+            impl RadixInteger for $t {
+                fn de_radix(buf: &[u8], radix: u32) -> Result<Self, Error> {
+                    let buf = crate::de::trim_whitespace(buf);
+                    let overflow = || Error {
+                        ty_name: String::from(stringify!($t)),
+                        kind: ErrorKind::Overflow {
+                            ty: String::from(stringify!($t)),
+                            value: String::from_utf8_lossy(buf).to_string(),
+                        },
+                    };
+                    let (negative, magnitude) = parse_radix(buf, radix).ok_or_else(|| Error {
+                        ty_name: String::from(stringify!($t)),
+                        kind: ErrorKind::InvalidType(format!(
+                            "{} is not an integer in radix {}",
+                            String::from_utf8_lossy(buf),
+                            radix
+                        )),
+                    })?;
+                    let magnitude = magnitude.map_err(|_| overflow())?;
+                    let value = if negative {
+                        match magnitude.cmp(&i128::MIN.unsigned_abs()) {
+                            std::cmp::Ordering::Less => -(magnitude as i128),
+                            std::cmp::Ordering::Equal => i128::MIN,
+                            std::cmp::Ordering::Greater => return Err(overflow()),
+                        }
+                    } else {
+                        i128::try_from(magnitude).map_err(|_| overflow())?
+                    };
+                    <$t>::try_from(value).map_err(|_| overflow())
+                }
+
+                fn ser_radix<W: Write>(
+                    &self,
+                    radix: u32,
+                    writer: &mut XmlWriter<W>,
+                ) -> Result<(), crate::ser::Error> {
+                    let value = *self as i128;
+                    ser_radix_digits(value < 0, value.unsigned_abs(), radix, writer)
+                }
+            }
+        )+
+    };
+}
+
+impl_radix_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl RadixInteger for u128 {
+    fn de_radix(buf: &[u8], radix: u32) -> Result<Self, Error> {
+        let buf = crate::de::trim_whitespace(buf);
+        let (negative, magnitude) = parse_radix(buf, radix).ok_or_else(|| Error {
+            ty_name: String::from("u128"),
+            kind: ErrorKind::InvalidType(format!(
+                "{} is not an integer in radix {}",
+                String::from_utf8_lossy(buf),
+                radix
+            )),
+        })?;
+        match magnitude {
+            Ok(magnitude) if !negative || magnitude == 0 => Ok(magnitude),
+            _ => Err(Error {
+                ty_name: String::from("u128"),
+                kind: ErrorKind::Overflow {
+                    ty: String::from("u128"),
+                    value: String::from_utf8_lossy(buf).to_string(),
+                },
+            }),
+        }
+    }
+
+    fn ser_radix<W: Write>(
+        &self,
+        radix: u32,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), crate::ser::Error> {
+        ser_radix_digits(false, *self, radix, writer)
+    }
+}
+
+/// Module for `#[xmlib(display)]` fields, which are (de)serialized with their
+/// [`FromStr`](std::str::FromStr) and [`Display`](std::fmt::Display) implementations.
+///
+/// # Example
+/// ```
+/// use xmlib_derive::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Version(u32, u32);
+///
+/// impl std::fmt::Display for Version {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "{}.{}", self.0, self.1)
+///     }
+/// }
+///
+/// impl std::str::FromStr for Version {
+///     type Err = std::num::ParseIntError;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         let (major, minor) = s.split_once('.').unwrap_or((s, "0"));
+///         Ok(Version(major.parse()?, minor.parse()?))
+///     }
+/// }
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Package {
+///     #[xmlib(display)]
+///     version: Version,
+/// }
+///
+/// let package: Package = xmlib::de::from_str(r#"<package version="1.2"/>"#).unwrap();
+/// assert_eq!(package.version, Version(1, 2));
+///
+/// let serialized = xmlib::ser::write_to_string(package).unwrap();
+/// assert_eq!(serialized, r#"<package version="1.2"/>"#);
+/// ```
+pub mod display {
+    use std::fmt::{Debug, Display};
+    use std::io::Write;
+    use std::str::FromStr;
+
+    use crate::ser::XmlWriter;
+    use crate::{Error, ErrorKind};
+
+    /// Decodes the buffer as utf-8 and parses it with [`FromStr`].
+    ///
+    /// Returns [`ErrorKind::InvalidType`] containing the raw value and the parse error.
+    pub fn de_buf<T>(buf: &[u8]) -> Result<T, Error>
+    where
+        T: FromStr,
+        T::Err: Debug,
+    {
+        de_buf_named(buf, std::any::type_name::<T>())
+    }
+
+    // Used by `impl_buf_via_str!` to name the type like it was written
+    #[doc(hidden)]
+    pub fn de_buf_named<T>(buf: &[u8], ty_name: &str) -> Result<T, Error>
+    where
+        T: FromStr,
+        T::Err: Debug,
+    {
+        let invalid = |reason: String| Error {
+            ty_name: String::from(ty_name),
+            kind: ErrorKind::InvalidType(format!("{}: {}", String::from_utf8_lossy(buf), reason)),
+        };
+        let s = std::str::from_utf8(buf).map_err(|e| invalid(e.to_string()))?;
+        s.parse().map_err(|e| invalid(format!("{:?}", e)))
+    }
+
+    /// Writes the [`Display`] output of the value escaped.
+    pub fn ser<T: Display, W: Write>(
+        value: &T,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), crate::ser::Error> {
+        let text = value.to_string();
+        writer.write_all(&quick_xml::escape::escape(text.as_bytes()))?;
+        Ok(())
+    }
+}
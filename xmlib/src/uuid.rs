@@ -0,0 +1,28 @@
+//! Implementations for [`uuid::Uuid`], enabled with the `uuid` feature.
+//!
+//! Uuids are serialized in the hyphenated lowercase form, e.g.
+//! `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+
+use std::io::Write;
+
+use ::uuid::Uuid;
+
+use crate::de::{de_from_str, DeserializeBuf};
+use crate::ser::{Serialize, XmlWriter};
+use crate::Error;
+
+impl DeserializeBuf for Uuid {
+    #[inline]
+    fn de_buf(buf: &[u8]) -> Result<Self, Error> {
+        de_from_str(buf, "Uuid")
+    }
+}
+
+impl<W: Write> Serialize<W> for Uuid {
+    #[inline]
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), crate::ser::Error> {
+        let mut buf = Uuid::encode_buffer();
+        writer.write_all(self.hyphenated().encode_lower(&mut buf).as_bytes())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use xmlib::de::DeserializeBuf;
+use xmlib::types::Base64;
+use xmlib::ErrorKind;
+
+fn encode(data: &[u8]) -> Vec<u8> {
+    xmlib::ser::write_to_vec(Base64(data.to_vec())).unwrap()
+}
+
+#[test]
+fn roundtrip() {
+    for (data, encoded) in [
+        (&b""[..], &b""[..]),
+        (b"f", b"Zg=="),
+        (b"fo", b"Zm8="),
+        (b"foo", b"Zm9v"),
+        (b"foob", b"Zm9vYg=="),
+        (b"\xff\xfe\x00", b"//4A"),
+    ] {
+        assert_eq!(encode(data), encoded);
+        assert_eq!(Base64::de_buf(encoded).unwrap().0, data);
+    }
+}
+
+#[test]
+fn large() {
+    let data: Vec<u8> = (0..10_000).map(|i| (i * 7 % 256) as u8).collect();
+    let encoded = encode(&data);
+    assert_eq!(encoded.len(), (data.len() + 2) / 3 * 4);
+    assert!(!encoded.contains(&b'\n'));
+    assert_eq!(Base64::de_buf(&encoded).unwrap().0, data);
+
+    // line breaks every 76 characters like MIME
+    let wrapped: Vec<u8> = encoded
+        .chunks(76)
+        .flat_map(|line| line.iter().copied().chain(*b"\r\n"))
+        .collect();
+    assert_eq!(Base64::de_buf(&wrapped).unwrap().0, data);
+}
+
+#[test]
+fn whitespace() {
+    assert_eq!(Base64::de_buf(b"  Zm9v\n\tYg = = ").unwrap().0, b"foob");
+}
+
+#[test]
+fn invalid() {
+    let err = Base64::de_buf(b"Zm9v\nY*==").unwrap_err();
+    assert_eq!(err.ty_name, "Base64");
+    assert!(
+        matches!(err.kind, ErrorKind::InvalidType(ref m) if m.contains("at byte 6")),
+        "{}",
+        err
+    );
+
+    for input in [&b"Zm9"[..], b"Z===", b"Zg==Zg==", b"Zg=a", b"Zm9v="] {
+        assert!(
+            Base64::de_buf(input).is_err(),
+            "{}",
+            String::from_utf8_lossy(input)
+        );
+    }
+}
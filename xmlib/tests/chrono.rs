@@ -0,0 +1,55 @@
+#![cfg(feature = "chrono")]
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use xmlib::de::DeserializeBuf;
+use xmlib_derive::{Deserialize, Serialize};
+
+#[test]
+fn chrono_values() {
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Entry {
+        created: DateTime<Utc>,
+        #[xmlib(value_buf)]
+        day: NaiveDate,
+    }
+
+    let entry = Entry {
+        created: Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap(),
+        day: NaiveDate::from_ymd_opt(2024, 1, 16).unwrap(),
+    };
+    let serialized = xmlib::ser::write_to_string(&entry).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<entry created="2024-01-15T10:30:00Z">2024-01-16</entry>"#
+    );
+    let deserialized: Entry = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, entry);
+
+    // other offsets are converted to utc
+    let deserialized: Entry =
+        xmlib::de::from_str(r#"<entry created="2024-01-15T12:30:00+02:00">2024-01-16</entry>"#)
+            .unwrap();
+    assert_eq!(deserialized, entry);
+}
+
+#[test]
+fn chrono_formats() {
+    let date = DateTime::<FixedOffset>::de_buf(b"2024-01-15T10:30:00.5+02:00").unwrap();
+    assert_eq!(
+        xmlib::ser::write_to_string(date).unwrap(),
+        "2024-01-15T10:30:00.500+02:00"
+    );
+
+    let date = NaiveDateTime::de_buf(b"2024-01-15T10:30:00").unwrap();
+    assert_eq!(
+        xmlib::ser::write_to_string(date).unwrap(),
+        "2024-01-15T10:30:00"
+    );
+
+    let err = NaiveDate::de_buf(b"2024-13-01").unwrap_err();
+    match err.kind {
+        xmlib::ErrorKind::InvalidType(message) => assert!(message.contains("2024-13-01")),
+        kind => panic!("unexpected error {:?}", kind),
+    }
+    assert!(DateTime::<Utc>::de_buf(b"2024-01-15").is_err());
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "decimal")]
+
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use xmlib::de::DeserializeBuf;
+use xmlib_derive::{Deserialize, Serialize};
+
+#[test]
+fn decimal_attribute() {
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Payment {
+        amount: Decimal,
+        #[xmlib(element)]
+        fee: Decimal,
+    }
+
+    let input = r#"<payment amount="123456789.000000001"><fee>-0.10</fee></payment>"#;
+    let payment: Payment = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        payment.amount,
+        Decimal::from_str("123456789.000000001").unwrap()
+    );
+    // f64 can't represent the value
+    assert_ne!(123456789.000000001f64.to_string(), "123456789.000000001");
+    assert_eq!(xmlib::ser::write_to_string(&payment).unwrap(), input);
+}
+
+#[test]
+fn no_exponent() {
+    let small = Decimal::from_str("0.0000000000000000000000000001").unwrap();
+    assert_eq!(
+        xmlib::ser::write_to_vec(small).unwrap(),
+        b"0.0000000000000000000000000001"
+    );
+    let large = Decimal::MAX;
+    assert_eq!(
+        xmlib::ser::write_to_vec(large).unwrap(),
+        b"79228162514264337593543950335"
+    );
+}
+
+#[test]
+fn invalid() {
+    let err = Decimal::de_buf(b"12.3.4").unwrap_err();
+    assert_eq!(err.ty_name, "Decimal");
+    assert!(matches!(err.kind, xmlib::ErrorKind::InvalidType(ref v) if v == "12.3.4"));
+    assert!(Decimal::de_buf(b"").is_err());
+    assert!(Decimal::de_buf(b"abc").is_err());
+}
@@ -0,0 +1,56 @@
+use xmlib::ser::{Serialize, XmlWriter};
+use xmlib_derive::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Note {
+    id: u32,
+}
+
+fn declaration(encoding: Option<&str>, standalone: Option<bool>) -> String {
+    let mut writer = XmlWriter::new(Vec::new()).unwrap();
+    writer
+        .write_declaration("1.0", encoding, standalone)
+        .unwrap();
+    String::from_utf8(writer.into_inner()).unwrap()
+}
+
+#[test]
+fn declaration_values() {
+    assert_eq!(
+        declaration(Some("UTF-8"), Some(true)),
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#
+    );
+    assert_eq!(
+        declaration(Some("UTF-8"), Some(false)),
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#
+    );
+    assert_eq!(
+        declaration(Some("UTF-8"), None),
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#
+    );
+    assert_eq!(declaration(None, None), r#"<?xml version="1.0"?>"#);
+
+    let mut writer = XmlWriter::new(Vec::new()).unwrap();
+    writer.write_xml_start().unwrap();
+    assert_eq!(
+        writer.into_inner(),
+        declaration(Some("UTF-8"), Some(true)).into_bytes()
+    );
+}
+
+#[test]
+fn declaration_without_standalone() {
+    let mut writer = XmlWriter::new(Vec::new()).unwrap().with_indent(' ', 2);
+    writer
+        .write_declaration("1.0", Some("UTF-8"), None)
+        .unwrap();
+    Note { id: 1 }.ser(&mut writer).unwrap();
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+        output,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<note id=\"1\"/>"
+    );
+
+    let note: Note = xmlib::de::from_str(&output).unwrap();
+    assert_eq!(note, Note { id: 1 });
+}
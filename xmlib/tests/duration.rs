@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use xmlib::de::DeserializeBuf;
+
+fn ser(d: Duration) -> Vec<u8> {
+    xmlib::ser::write_to_vec(d).unwrap()
+}
+
+#[test]
+fn roundtrip() {
+    for (d, s) in [
+        (Duration::ZERO, &b"PT0S"[..]),
+        (Duration::from_secs(5415), b"PT1H30M15S"),
+        (Duration::from_millis(1500), b"PT1.5S"),
+        (Duration::from_nanos(1), b"PT0.000000001S"),
+        (Duration::from_secs(86400), b"P1D"),
+        (Duration::new(90000, 250_000_000), b"P1DT1H0.25S"),
+        (Duration::from_secs(120), b"PT2M"),
+    ] {
+        assert_eq!(ser(d), s);
+        assert_eq!(Duration::de_buf(s).unwrap(), d);
+    }
+}
+
+#[test]
+fn non_canonical() {
+    assert_eq!(
+        Duration::de_buf(b"PT90M").unwrap(),
+        Duration::from_secs(5400)
+    );
+    assert_eq!(
+        Duration::de_buf(b"PT0,5S").unwrap(),
+        Duration::from_millis(500)
+    );
+    assert_eq!(
+        Duration::de_buf(b"P1W").unwrap(),
+        Duration::from_secs(604800)
+    );
+    assert_eq!(Duration::de_buf(b"P0D").unwrap(), Duration::ZERO);
+    assert_eq!(
+        Duration::de_buf(b"PT1.1234567891S").unwrap(),
+        Duration::new(1, 123_456_789)
+    );
+}
+
+#[test]
+fn invalid() {
+    for s in [
+        &b""[..],
+        b"P",
+        b"PT",
+        b"T1S",
+        b"-PT1S",
+        b"PT1",
+        b"PT1.S",
+        b"PT1.5M",
+        b"PT1S1M",
+        b"P1H",
+        b"PT1D",
+    ] {
+        assert!(
+            Duration::de_buf(s).is_err(),
+            "{}",
+            String::from_utf8_lossy(s)
+        );
+    }
+    let err = Duration::de_buf(b"P1Y").unwrap_err();
+    assert!(err.to_string().contains("years and months"), "{}", err);
+    assert!(Duration::de_buf(b"P1M").is_err());
+}
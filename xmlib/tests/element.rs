@@ -0,0 +1,72 @@
+use xmlib::{Element, Node};
+use xmlib_derive::{Deserialize, Serialize};
+
+#[test]
+fn element_roundtrip() {
+    let input = concat!(
+        r#"<doc xmlns="urn:doc" xmlns:x="urn:x" x:id="1&amp;2">"#,
+        r#"<p>Hello <b>world</b>! <![CDATA[a<b]]></p>"#,
+        r#"<x:meta x:key="k"/>"#,
+        "</doc>"
+    );
+    let mut doc: Element = xmlib::de::from_str(input).unwrap();
+
+    assert_eq!(doc.name, "doc");
+    assert_eq!(doc.attr("xmlns:x"), Some("urn:x"));
+    assert_eq!(doc.attr("x:id"), Some("1&2"));
+    let p = doc.children_named("p").next().unwrap();
+    assert_eq!(
+        p.children,
+        [
+            Node::Text(String::from("Hello ")),
+            Node::Element(Element {
+                name: String::from("b"),
+                attributes: Vec::new(),
+                children: vec![Node::Text(String::from("world"))],
+            }),
+            Node::Text(String::from("! a<b")),
+        ]
+    );
+    assert_eq!(p.text(), "Hello world! a<b");
+    assert_eq!(doc.children_named("x:meta").count(), 1);
+
+    let expected = concat!(
+        r#"<doc xmlns="urn:doc" xmlns:x="urn:x" x:id="1&amp;2">"#,
+        r#"<p>Hello <b>world</b>! a&lt;b</p>"#,
+        r#"<x:meta x:key="k"/>"#,
+        "</doc>"
+    );
+    assert_eq!(xmlib::ser::write_to_string(&doc).unwrap(), expected);
+
+    doc.set_attr("x:id", "3");
+    let reread: Element = xmlib::de::from_str(&xmlib::ser::write_to_string(&doc).unwrap()).unwrap();
+    assert_eq!(reread, doc);
+    assert_eq!(reread.attr("x:id"), Some("3"));
+}
+
+#[test]
+fn element_pretty() {
+    let doc: Element = xmlib::de::from_str("<a><b>text <c/></b><d/></a>").unwrap();
+    let serialized = xmlib::ser::Serializer::new()
+        .indent(' ', 2)
+        .to_string(&doc)
+        .unwrap();
+    assert_eq!(serialized, "<a>\n  <b>text <c/></b>\n  <d/>\n</a>");
+}
+
+#[test]
+fn element_as_opaque_field() {
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Envelope {
+        version: u32,
+        #[xmlib(value)]
+        body: Element,
+    }
+
+    let input = r#"<envelope version="2"><order id="7"><item>a</item></order></envelope>"#;
+    let envelope: Envelope = xmlib::de::from_str(input).unwrap();
+    assert_eq!(envelope.body.name, "order");
+    assert_eq!(envelope.body.attr("id"), Some("7"));
+    assert_eq!(envelope.body.text(), "a");
+    assert_eq!(xmlib::ser::write_to_string(envelope).unwrap(), input);
+}
@@ -0,0 +1,60 @@
+use xmlib::de::DeserializeBuf;
+use xmlib::types::{HexArray, HexBytes};
+use xmlib::ErrorKind;
+
+fn error_message(err: xmlib::Error) -> String {
+    match err.kind {
+        ErrorKind::InvalidType(message) => message,
+        kind => panic!("unexpected error: {}", kind),
+    }
+}
+
+#[test]
+fn bytes() {
+    for (data, encoded) in [
+        (&b""[..], &b""[..]),
+        (b"\x00", b"00"),
+        (b"\xff\x00\xaa\x00", b"FF00AA00"),
+    ] {
+        assert_eq!(
+            xmlib::ser::write_to_vec(HexBytes(data.to_vec())).unwrap(),
+            encoded
+        );
+        assert_eq!(HexBytes::de_buf(encoded).unwrap().0, data);
+    }
+    assert_eq!(HexBytes::de_buf(b"fF0a").unwrap().0, b"\xff\x0a");
+
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    let encoded = xmlib::ser::write_to_vec(HexBytes(data.clone())).unwrap();
+    assert_eq!(encoded.len(), 2000);
+    assert_eq!(HexBytes::de_buf(&encoded).unwrap().0, data);
+}
+
+#[test]
+fn array() {
+    let color = HexArray::<3>::de_buf(b"ff00Aa").unwrap();
+    assert_eq!(color, HexArray([0xff, 0x00, 0xaa]));
+    assert_eq!(xmlib::ser::write_to_vec(color).unwrap(), b"FF00AA");
+    assert_eq!(HexArray::<0>::de_buf(b"").unwrap(), HexArray([]));
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(
+        error_message(HexBytes::de_buf(b"FFF").unwrap_err()),
+        "odd number of hex digits: 3"
+    );
+    assert_eq!(
+        error_message(HexBytes::de_buf(b"FFxF").unwrap_err()),
+        "invalid hex digit 'x' at byte 2"
+    );
+    assert_eq!(
+        error_message(HexArray::<4>::de_buf(b"FF00AA").unwrap_err()),
+        "expected 8 hex digits, got 6"
+    );
+    assert_eq!(
+        error_message(HexArray::<2>::de_buf(b"FF0").unwrap_err()),
+        "odd number of hex digits: 3"
+    );
+    assert!(HexArray::<1>::de_buf(b" F").is_err());
+}
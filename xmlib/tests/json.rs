@@ -0,0 +1,67 @@
+#![cfg(feature = "json")]
+
+use serde_json::json;
+use xmlib::{Element, Node};
+
+fn parse(input: &str) -> Element {
+    xmlib::de::from_str(input).unwrap()
+}
+
+#[test]
+fn attributes_and_text() {
+    let price = parse(r#"<price currency="EUR" net="true">12.50</price>"#);
+    let expected = json!({
+        "price": {
+            "@currency": "EUR",
+            "@net": "true",
+            "$text": "12.50",
+        }
+    });
+    assert_eq!(price.to_json(), expected);
+    assert_eq!(Element::from_json(&expected).unwrap(), price);
+}
+
+#[test]
+fn repeated_children() {
+    let input = r#"<list>
+    <item>1</item>
+    <sep/>
+    <item>2</item>
+    <item><id>3</id><id>03</id></item>
+</list>"#;
+    let json = parse(input).to_json();
+    assert_eq!(
+        json,
+        json!({
+            "list": {
+                "item": ["1", "2", { "id": ["3", "03"] }],
+                "sep": "",
+            }
+        })
+    );
+
+    let list = Element::from_json(&json).unwrap();
+    let names: Vec<_> = list.elements().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["item", "item", "item", "sep"]);
+    assert_eq!(list.text(), "12303");
+    assert_eq!(list.to_json(), json);
+}
+
+#[test]
+fn numbers_stay_strings() {
+    let json = parse("<n value=\"1.0\">007</n>").to_json();
+    assert_eq!(json, json!({ "n": { "@value": "1.0", "$text": "007" } }));
+
+    let n = Element::from_json(&json!({ "n": { "@value": 1.5, "$text": 7, "ok": true } })).unwrap();
+    assert_eq!(n.attr("value"), Some("1.5"));
+    assert_eq!(n.children[0], Node::Text(String::from("7")));
+    assert_eq!(n.children_named("ok").next().unwrap().text(), "true");
+}
+
+#[test]
+fn invalid_json() {
+    assert!(Element::from_json(&json!("text")).is_err());
+    assert!(Element::from_json(&json!({ "a": "", "b": "" })).is_err());
+    let err = Element::from_json(&json!({ "a": { "b": { "@c": null } } })).unwrap_err();
+    assert_eq!(err.ty_name, "a > b");
+}
@@ -0,0 +1,56 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use xmlib::de::DeserializeBuf;
+use xmlib::ser::Serialize;
+
+fn roundtrip<T>(s: &str, expected: T)
+where
+    T: DeserializeBuf + Serialize<Vec<u8>> + PartialEq + std::fmt::Debug,
+{
+    let value = T::de_buf(s.as_bytes()).unwrap();
+    assert_eq!(value, expected);
+    assert_eq!(xmlib::ser::write_to_vec(value).unwrap(), s.as_bytes());
+}
+
+#[test]
+fn v4() {
+    roundtrip("192.168.1.10", Ipv4Addr::new(192, 168, 1, 10));
+    roundtrip("192.168.1.10", IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)));
+    roundtrip(
+        "127.0.0.1:8080",
+        SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8080),
+    );
+    roundtrip(
+        "127.0.0.1:8080",
+        SocketAddr::from((Ipv4Addr::LOCALHOST, 8080)),
+    );
+}
+
+#[test]
+fn v6() {
+    roundtrip("::1", Ipv6Addr::LOCALHOST);
+    roundtrip("fe80::1", IpAddr::V6("fe80::1".parse().unwrap()));
+    roundtrip("[::1]:8080", SocketAddr::from((Ipv6Addr::LOCALHOST, 8080)));
+    roundtrip(
+        "[fe80::1%2]:443",
+        SocketAddrV6::new("fe80::1".parse().unwrap(), 443, 0, 2),
+    );
+    let longest = SocketAddrV6::new(
+        "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff".parse().unwrap(),
+        u16::MAX,
+        0,
+        u32::MAX,
+    );
+    roundtrip(&longest.to_string(), longest);
+}
+
+#[test]
+fn invalid() {
+    let err = Ipv4Addr::de_buf(b"256.0.0.1").unwrap_err();
+    assert_eq!(err.ty_name, "Ipv4Addr");
+    assert!(matches!(err.kind, xmlib::ErrorKind::InvalidType(ref v) if v == "256.0.0.1"));
+    assert!(IpAddr::de_buf(b"localhost").is_err());
+    assert!(SocketAddr::de_buf(b"::1:8080").is_err());
+    assert!(SocketAddrV4::de_buf(b"[::1]:8080").is_err());
+    assert!(Ipv6Addr::de_buf(b"\xff").is_err());
+}
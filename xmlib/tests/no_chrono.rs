@@ -0,0 +1,44 @@
+#![cfg(not(feature = "chrono"))]
+
+// Without the `chrono` feature timestamps are plain text and the rest of the crate is unchanged.
+
+use xmlib::de::DeserializeBuf;
+use xmlib::ser::{Serialize, XmlWriter};
+use xmlib_derive::{Deserialize, Serialize};
+
+// a date type of the user, which must not conflict with the implementations of the feature
+#[derive(Debug, PartialEq)]
+struct NaiveDate(String);
+
+impl DeserializeBuf for NaiveDate {
+    fn de_buf(buf: &[u8]) -> Result<Self, xmlib::Error> {
+        String::de_buf(buf).map(NaiveDate)
+    }
+}
+
+impl<W: std::io::Write> Serialize<W> for NaiveDate {
+    fn ser(&self, writer: &mut XmlWriter<W>) -> Result<(), xmlib::ser::Error> {
+        self.0.ser(writer)
+    }
+}
+
+#[test]
+fn dates_as_text() {
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Entry {
+        created: String,
+        #[xmlib(value_buf)]
+        day: NaiveDate,
+    }
+
+    let input = r#"<entry created="2024-01-15T12:30:00+02:00">2024-01-16</entry>"#;
+    let entry: Entry = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        entry,
+        Entry {
+            created: String::from("2024-01-15T12:30:00+02:00"),
+            day: NaiveDate(String::from("2024-01-16")),
+        }
+    );
+    assert_eq!(xmlib::ser::write_to_string(&entry).unwrap(), input);
+}
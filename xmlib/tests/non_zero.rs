@@ -0,0 +1,41 @@
+use std::num::{NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU32, NonZeroU8, NonZeroUsize};
+
+use xmlib::de::DeserializeBuf;
+use xmlib::ErrorKind;
+
+#[test]
+fn roundtrip() {
+    let value = NonZeroU32::de_buf(b"42").unwrap();
+    assert_eq!(value.get(), 42);
+    assert_eq!(xmlib::ser::write_to_vec(value).unwrap(), b"42");
+
+    let value = NonZeroI8::de_buf(b"-128").unwrap();
+    assert_eq!(value.get(), i8::MIN);
+    assert_eq!(xmlib::ser::write_to_vec(value).unwrap(), b"-128");
+
+    let max = u128::MAX.to_string();
+    let value = NonZeroU128::de_buf(max.as_bytes()).unwrap();
+    assert_eq!(value.get(), u128::MAX);
+    assert_eq!(xmlib::ser::write_to_vec(value).unwrap(), max.as_bytes());
+
+    let max = i64::MAX.to_string();
+    assert_eq!(NonZeroI64::de_buf(max.as_bytes()).unwrap().get(), i64::MAX);
+}
+
+#[test]
+fn zero() {
+    let err = NonZeroUsize::de_buf(b"0").unwrap_err();
+    assert_eq!(err.ty_name, "NonZeroUsize");
+    assert!(matches!(err.kind, ErrorKind::InvalidType(ref m) if m == "value must be non-zero"));
+    assert!(NonZeroI8::de_buf(b"-0").is_err());
+}
+
+#[test]
+fn invalid() {
+    assert!(matches!(
+        NonZeroU8::de_buf(b"256").unwrap_err().kind,
+        ErrorKind::Overflow { .. }
+    ));
+    assert!(NonZeroU8::de_buf(b"-1").is_err());
+    assert!(NonZeroU8::de_buf(b"").is_err());
+}
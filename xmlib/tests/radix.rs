@@ -0,0 +1,73 @@
+use xmlib::types::RadixInteger;
+
+fn ser<T: RadixInteger>(value: T, radix: u32) -> String {
+    let mut writer = xmlib::ser::XmlWriter::new(Vec::new()).unwrap();
+    value.ser_radix(radix, &mut writer).unwrap();
+    String::from_utf8(writer.into_inner()).unwrap()
+}
+
+#[test]
+fn roundtrip() {
+    assert_eq!(ser(u64::MAX, 16), "FFFFFFFFFFFFFFFF");
+    assert_eq!(u64::de_radix(b"FFFFFFFFFFFFFFFF", 16).unwrap(), u64::MAX);
+    assert_eq!(ser(u128::MAX, 2), "1".repeat(128));
+    assert_eq!(
+        u128::de_radix("1".repeat(128).as_bytes(), 2).unwrap(),
+        u128::MAX
+    );
+    assert_eq!(ser(i128::MIN, 16), "-80000000000000000000000000000000");
+    assert_eq!(
+        i128::de_radix(b"-80000000000000000000000000000000", 16).unwrap(),
+        i128::MIN
+    );
+    assert_eq!(ser(0u8, 8), "0");
+    assert_eq!(ser(-31i32, 16), "-1F");
+    assert_eq!(ser(8u8, 8), "10");
+}
+
+#[test]
+fn prefix_and_case() {
+    assert_eq!(u32::de_radix(b"0x1F", 16).unwrap(), 31);
+    assert_eq!(u32::de_radix(b"0X1f", 16).unwrap(), 31);
+    assert_eq!(i32::de_radix(b"-0x1f", 16).unwrap(), -31);
+    assert_eq!(u8::de_radix(b"0b101", 2).unwrap(), 5);
+    assert_eq!(u8::de_radix(b"0o17", 8).unwrap(), 15);
+    assert_eq!(u8::de_radix(b" +17 ", 8).unwrap(), 15);
+    // `0b` is a valid hex number
+    assert_eq!(u8::de_radix(b"0b", 16).unwrap(), 11);
+}
+
+#[test]
+fn invalid() {
+    for (input, radix) in [
+        (&b"1G"[..], 16),
+        (b"2", 2),
+        (b"8", 8),
+        (b"0x", 16),
+        (b"", 16),
+    ] {
+        let err = u32::de_radix(input, radix).unwrap_err();
+        assert!(
+            matches!(err.kind, xmlib::ErrorKind::InvalidType(ref message) if message.ends_with(&format!("radix {}", radix))),
+            "{:?}",
+            err
+        );
+    }
+    for (input, radix) in [
+        (&b"100"[..], 16),
+        (b"-1", 16),
+        (b"1".repeat(129).as_slice(), 2),
+    ] {
+        let err = u8::de_radix(input, radix).unwrap_err();
+        assert!(matches!(err.kind, xmlib::ErrorKind::Overflow { .. }));
+    }
+    assert!(matches!(
+        u128::de_radix(b"-1", 16).unwrap_err().kind,
+        xmlib::ErrorKind::Overflow { .. }
+    ));
+    assert!(matches!(
+        i8::de_radix(b"-81", 16).unwrap_err().kind,
+        xmlib::ErrorKind::Overflow { .. }
+    ));
+    assert_eq!(i8::de_radix(b"-80", 16).unwrap(), i8::MIN);
+}
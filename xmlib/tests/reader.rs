@@ -0,0 +1,95 @@
+use xmlib::de::XmlReader;
+use xmlib::exports::events::Event;
+
+// Reads all events and returns the first error
+fn read_all<R: std::io::BufRead>(mut reader: XmlReader<R>) -> Result<(), xmlib::exports::Error> {
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        if let Event::Eof = reader.read_event(&mut buf)? {
+            return Ok(());
+        }
+    }
+}
+
+#[test]
+fn mismatched_end_tags() {
+    let input = &b"<a><b></a>"[..];
+    assert!(read_all(XmlReader::new(input)).is_ok());
+    assert!(matches!(
+        read_all(XmlReader::new_checked(input)),
+        Err(xmlib::exports::Error::EndEventMismatch { .. })
+    ));
+
+    let input = &b"<a><b/><c></c></a>"[..];
+    assert!(read_all(XmlReader::new_checked(input)).is_ok());
+}
+
+// Reads until the next start tag
+fn read_start<R: std::io::BufRead>(reader: &mut XmlReader<R>, buf: &mut Vec<u8>) {
+    loop {
+        buf.clear();
+        match reader.read_event(buf).unwrap() {
+            Event::Start(_) => return,
+            Event::Eof => panic!("no start tag found"),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn read_text_with_cdata() {
+    let mut reader = XmlReader::from_str("<a>abc<![CDATA[&<b>]]>def &amp; <!-- c -->ghi</a><e/>");
+    let mut buf = Vec::new();
+    let mut other_buf = Vec::new();
+
+    read_start(&mut reader, &mut buf);
+    let text = reader.read_text(b"a", &mut buf, &mut other_buf).unwrap();
+    assert_eq!(text, "abc&<b>def & ghi");
+
+    read_start(&mut reader, &mut buf);
+    let text = reader.read_text(b"e", &mut buf, &mut other_buf).unwrap();
+    assert_eq!(text, "");
+
+    let mut reader = XmlReader::from_str("<a>abc<b/></a>");
+    read_start(&mut reader, &mut buf);
+    assert!(reader.read_text(b"a", &mut buf, &mut other_buf).is_err());
+}
+
+#[test]
+fn resolve_prefix() {
+    let input = r#"<root xmlns:r="urn:root" xmlns:s="urn:s"><a xmlns:r="urn:inner"/><b/></root>"#;
+    let mut reader = XmlReader::new(input.as_bytes());
+    let mut buf = Vec::new();
+
+    read_start(&mut reader, &mut buf);
+    assert_eq!(
+        reader.resolve_prefix(b"r:foo").unwrap(),
+        Some(&b"urn:root"[..])
+    );
+    assert_eq!(reader.resolve_prefix(b"foo").unwrap(), None);
+    assert_eq!(
+        reader.resolve_prefix(b"xmlns:r").unwrap(),
+        Some(&b"http://www.w3.org/2000/xmlns/"[..])
+    );
+
+    // the inner declaration shadows the outer one until the element ends
+    read_start(&mut reader, &mut buf);
+    assert_eq!(
+        reader.resolve_prefix(b"r:foo").unwrap(),
+        Some(&b"urn:inner"[..])
+    );
+    read_start(&mut reader, &mut buf);
+    assert_eq!(
+        reader.resolve_prefix(b"r:foo").unwrap(),
+        Some(&b"urn:root"[..])
+    );
+    assert_eq!(
+        reader.resolve_prefix(b"s:foo").unwrap(),
+        Some(&b"urn:s"[..])
+    );
+
+    let err = reader.resolve_prefix(b"t:foo").unwrap_err();
+    assert_eq!(err.ty_name, "t:foo");
+    assert!(matches!(err.kind, xmlib::ErrorKind::UnboundPrefix(ref prefix) if prefix == "t"));
+}
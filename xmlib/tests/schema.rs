@@ -0,0 +1,63 @@
+#![cfg(feature = "schema")]
+
+use std::time::Duration;
+
+use xmlib_derive::Xml;
+
+#[derive(Xml)]
+#[xmlib(reflect)]
+struct Track {
+    #[xmlib(value_buf)]
+    title: String,
+    length: Duration,
+}
+
+#[derive(Xml)]
+#[xmlib(reflect, rename = "album")]
+struct Album {
+    #[xmlib(rename = "catalog-id")]
+    id: u32,
+    #[xmlib(default = "unknown")]
+    label: String,
+    #[xmlib(default = 1.0)]
+    rating: f64,
+    #[xmlib(default)]
+    explicit: bool,
+    #[xmlib(element)]
+    artist: String,
+    #[xmlib(element, default = 2000)]
+    year: i16,
+    #[xmlib(element)]
+    note: Option<String>,
+    #[xmlib(value, multiple, min = 1, wrapper = "tracks")]
+    tracks: Vec<Track>,
+    #[xmlib(element, multiple, max = 5)]
+    genre: Vec<String>,
+    #[xmlib(value)]
+    bonus: Option<Track>,
+}
+
+#[test]
+fn golden_album() {
+    assert_eq!(
+        xmlib::schema::xsd_for::<Album>(),
+        include_str!("golden/album.xsd")
+    );
+}
+
+#[test]
+fn simple_content() {
+    assert_eq!(
+        xmlib::schema::xsd_for::<Track>(),
+        r#"<xs:element name="track">
+  <xs:complexType>
+    <xs:simpleContent>
+      <xs:extension base="xs:string">
+        <xs:attribute name="length" type="xs:duration" use="required"/>
+      </xs:extension>
+    </xs:simpleContent>
+  </xs:complexType>
+</xs:element>
+"#
+    );
+}
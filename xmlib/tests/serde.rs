@@ -0,0 +1,139 @@
+#![cfg(feature = "serde")]
+
+use xmlib::compat::{SerdeBuf, SerdeElement};
+use xmlib_derive::{Deserialize, Serialize};
+
+// like a type of another crate, which only implements the traits of serde
+mod remote {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    pub enum Status {
+        #[serde(rename = "active")]
+        Active,
+        #[serde(rename = "on-hold")]
+        OnHold,
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    pub struct Score(pub f32);
+}
+
+#[test]
+fn serde_flat_struct() {
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Kind {
+        Small,
+        Large,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Id(u32);
+
+    #[derive(serde::Serialize)]
+    #[serde(rename = "item", rename_all = "camelCase")]
+    struct Item {
+        item_id: Id,
+        name: String,
+        visible: bool,
+        ratio: f64,
+        kind: Kind,
+        note: Option<String>,
+        initial: char,
+    }
+
+    let item = Item {
+        item_id: Id(7),
+        name: String::from("a<b"),
+        visible: true,
+        ratio: 0.5,
+        kind: Kind::Large,
+        note: None,
+        initial: '"',
+    };
+    assert_eq!(
+        xmlib::ser::write_to_string(SerdeElement(&item)).unwrap(),
+        r#"<item itemId="7" name="a&lt;b" visible="1" ratio="0.5" kind="large" initial="&quot;"/>"#
+    );
+
+    let small = Item {
+        kind: Kind::Small,
+        note: Some(String::from("n")),
+        ..item
+    };
+    let serialized = xmlib::ser::Serializer::new()
+        .bools(xmlib::ser::BoolStyle::Words)
+        .to_string(&SerdeElement(small))
+        .unwrap();
+    assert_eq!(
+        serialized,
+        r#"<item itemId="7" name="a&lt;b" visible="true" ratio="0.5" kind="small" note="n" initial="&quot;"/>"#
+    );
+}
+
+#[test]
+fn serde_unsupported_shapes() {
+    #[derive(serde::Serialize)]
+    struct Inner {
+        x: u32,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Nested {
+        inner: Inner,
+    }
+
+    #[derive(serde::Serialize)]
+    struct List {
+        items: Vec<u32>,
+    }
+
+    let err = xmlib::ser::write_to_string(SerdeElement(Nested {
+        inner: Inner { x: 1 },
+    }))
+    .unwrap_err();
+    assert!(
+        matches!(err, xmlib::ser::Error::Unrepresentable { ref ty_name, .. } if ty_name == "Nested.inner")
+    );
+    assert!(xmlib::ser::write_to_string(SerdeElement(List { items: vec![1] })).is_err());
+    assert!(xmlib::ser::write_to_string(SerdeElement(42)).is_err());
+}
+
+#[test]
+fn serde_scalars() {
+    use remote::{Score, Status};
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Task {
+        #[xmlib(serde)]
+        status: Status,
+        #[xmlib(serde, default)]
+        previous: Option<Status>,
+        #[xmlib(serde, element, multiple)]
+        scores: Vec<Score>,
+        #[xmlib(serde, value_buf)]
+        title: String,
+    }
+
+    let input =
+        r#"<task status="on-hold"><scores>1.5</scores><scores>2.0</scores>a &amp; b</task>"#;
+    let task: Task = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        task,
+        Task {
+            status: Status::OnHold,
+            previous: None,
+            scores: vec![Score(1.5), Score(2.0)],
+            title: String::from("a & b"),
+        }
+    );
+    assert_eq!(xmlib::ser::write_to_string(task).unwrap(), input);
+
+    assert_eq!(
+        <SerdeBuf<Option<Status>> as xmlib::de::DeserializeBuf>::de_buf(b"active").unwrap(),
+        SerdeBuf(Some(Status::Active))
+    );
+    let err = xmlib::de::from_str::<Task>(r#"<task status="done">a</task>"#).unwrap_err();
+    assert!(matches!(err.kind, xmlib::ErrorKind::InvalidType(ref s) if s.contains("done")));
+    assert!(<SerdeBuf<Score> as xmlib::de::DeserializeBuf>::de_buf(b"high").is_err());
+    assert!(<SerdeBuf<Vec<u32>> as xmlib::de::DeserializeBuf>::de_buf(b"1").is_err());
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "smallvec")]
+
+use smallvec::{smallvec, SmallVec};
+use xmlib_derive::{Deserialize, Serialize};
+
+#[test]
+fn smallvec_children() {
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Point {
+        x: i32,
+    }
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Path {
+        #[xmlib(value, multiple)]
+        points: SmallVec<[Point; 2]>,
+        #[xmlib(element, multiple, rename = "tag")]
+        tags: SmallVec<[String; 4]>,
+    }
+
+    let path = Path {
+        points: smallvec![Point { x: 1 }, Point { x: 2 }, Point { x: 3 }],
+        tags: smallvec![String::from("a")],
+    };
+    let serialized = xmlib::ser::write_to_string(&path).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<path><point x="1"/><point x="2"/><point x="3"/><tag>a</tag></path>"#
+    );
+    let deserialized: Path = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, path);
+    assert!(deserialized.points.spilled());
+    assert!(!deserialized.tags.spilled());
+
+    let points: SmallVec<[Point; 2]> = smallvec![Point { x: 4 }];
+    assert_eq!(
+        xmlib::ser::write_to_string(&points).unwrap(),
+        r#"<point x="4"/>"#
+    );
+}
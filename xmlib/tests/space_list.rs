@@ -0,0 +1,56 @@
+use xmlib::de::DeserializeBuf;
+use xmlib::types::SpaceList;
+use xmlib_derive::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+enum Class {
+    Primary,
+    Large,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Button {
+    ids: SpaceList<u32>,
+    #[xmlib(default)]
+    class: SpaceList<Class>,
+}
+
+#[test]
+fn integers_and_enums() {
+    let input = r#"<button ids="1 5 9 12" class="primary large"/>"#;
+    let button: Button = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        button,
+        Button {
+            ids: SpaceList(vec![1, 5, 9, 12]),
+            class: SpaceList(vec![Class::Primary, Class::Large]),
+        }
+    );
+    assert_eq!(xmlib::ser::write_to_string(&button).unwrap(), input);
+}
+
+#[test]
+fn whitespace() {
+    assert_eq!(
+        SpaceList::<u8>::de_buf(b"\n 1\t 2  3 \r\n").unwrap(),
+        SpaceList(vec![1, 2, 3])
+    );
+}
+
+#[test]
+fn empty() {
+    let button: Button = xmlib::de::from_str(r#"<button ids=""/>"#).unwrap();
+    assert_eq!(button.ids, SpaceList(vec![]));
+    assert_eq!(SpaceList::<u8>::de_buf(b"   ").unwrap(), SpaceList(vec![]));
+    assert_eq!(
+        xmlib::ser::write_to_string(&button).unwrap(),
+        r#"<button ids=""/>"#
+    );
+}
+
+#[test]
+fn invalid_item() {
+    let err = xmlib::de::from_str::<Button>(r#"<button ids="1 x 3"/>"#).unwrap_err();
+    assert_eq!(err.ty_name, "u32");
+    assert!(xmlib::de::from_str::<Button>(r#"<button ids="1" class="small"/>"#).is_err());
+}
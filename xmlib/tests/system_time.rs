@@ -0,0 +1,44 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use xmlib::de::DeserializeBuf;
+
+#[test]
+fn roundtrip() {
+    let now = SystemTime::now();
+    let secs = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let serialized = xmlib::ser::write_to_string(now).unwrap();
+    assert_eq!(serialized, secs.to_string());
+    assert_eq!(
+        SystemTime::de_buf(serialized.as_bytes()).unwrap(),
+        UNIX_EPOCH + Duration::from_secs(secs)
+    );
+
+    assert_eq!(xmlib::ser::write_to_string(UNIX_EPOCH).unwrap(), "0");
+    assert_eq!(SystemTime::de_buf(b"0").unwrap(), UNIX_EPOCH);
+}
+
+#[test]
+fn truncates_subseconds() {
+    let time = UNIX_EPOCH + Duration::from_millis(1_999);
+    assert_eq!(xmlib::ser::write_to_string(time).unwrap(), "1");
+}
+
+#[test]
+fn before_epoch() {
+    let time = UNIX_EPOCH - Duration::from_secs(1);
+    assert!(matches!(
+        xmlib::ser::write_to_string(time),
+        Err(xmlib::ser::Error::Unrepresentable { .. })
+    ));
+}
+
+#[test]
+fn invalid() {
+    for input in [&b"-1"[..], b"1.5", b"now"] {
+        let err = SystemTime::de_buf(input).unwrap_err();
+        assert_eq!(err.ty_name, "SystemTime");
+        assert!(matches!(err.kind, xmlib::ErrorKind::InvalidType(_)));
+    }
+    let err = SystemTime::de_buf(u64::MAX.to_string().as_bytes()).unwrap_err();
+    assert!(matches!(err.kind, xmlib::ErrorKind::InvalidType(ref m) if m.contains("out of range")));
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "time")]
+
+use time::macros::{date, datetime};
+use time::{Date, OffsetDateTime, PrimitiveDateTime};
+use xmlib::de::DeserializeBuf;
+use xmlib_derive::{Deserialize, Serialize};
+
+#[test]
+fn time_values() {
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Entry {
+        created: OffsetDateTime,
+        #[xmlib(value_buf)]
+        day: Date,
+    }
+
+    let entry = Entry {
+        created: datetime!(2024-01-15 10:30:00.25 +05:30),
+        day: date!(2024 - 01 - 16),
+    };
+    let serialized = xmlib::ser::write_to_string(&entry).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<entry created="2024-01-15T10:30:00.25+05:30">2024-01-16</entry>"#
+    );
+    let deserialized: Entry = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, entry);
+}
+
+#[test]
+fn time_formats() {
+    let date = OffsetDateTime::de_buf(b"2024-01-15T10:30:00Z").unwrap();
+    assert_eq!(
+        xmlib::ser::write_to_string(date).unwrap(),
+        "2024-01-15T10:30:00Z"
+    );
+
+    let date = PrimitiveDateTime::de_buf(b"2024-01-15T10:30:00.123456789").unwrap();
+    assert_eq!(date, datetime!(2024-01-15 10:30:00.123456789));
+    assert_eq!(
+        xmlib::ser::write_to_string(date).unwrap(),
+        "2024-01-15T10:30:00.123456789"
+    );
+    assert!(PrimitiveDateTime::de_buf(b"2024-01-15T10:30:00+01:00").is_err());
+
+    let err = Date::de_buf(b"2024-13-01").unwrap_err();
+    match err.kind {
+        xmlib::ErrorKind::InvalidType(message) => assert!(message.contains("2024-13-01")),
+        kind => panic!("unexpected error {:?}", kind),
+    }
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "uuid")]
+
+use uuid::Uuid;
+use xmlib::de::DeserializeBuf;
+use xmlib_derive::{Deserialize, Serialize};
+
+#[test]
+fn uuid_attribute() {
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Item {
+        id: Uuid,
+        #[xmlib(default)]
+        parent: Uuid,
+    }
+
+    let id = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    let item = Item {
+        id,
+        parent: Uuid::nil(),
+    };
+    let serialized = xmlib::ser::write_to_string(&item).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<item id="67e55044-10b1-426f-9247-bb680e5fe0c8"/>"#
+    );
+    let deserialized: Item = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, item);
+
+    let deserialized: Item =
+        xmlib::de::from_str(r#"<item id="67E55044-10B1-426F-9247-BB680E5FE0C8"/>"#).unwrap();
+    assert_eq!(deserialized.id, id);
+
+    assert!(Uuid::de_buf(b"67e55044-10b1").is_err());
+    assert!(Uuid::de_buf(b"\xff").is_err());
+}
@@ -0,0 +1,96 @@
+use std::io;
+
+use xmlib::ser::XmlWriter;
+use xmlib_derive::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Item {
+    id: u32,
+    #[xmlib(value_buf)]
+    name: String,
+}
+
+#[derive(Debug, PartialEq)]
+struct Items(Vec<Item>);
+xmlib::ser_deser_vec!(Items, b"items", inner = Item, count = b"count");
+
+fn item(id: u32) -> Item {
+    Item {
+        id,
+        name: format!("item {}", id),
+    }
+}
+
+#[test]
+fn stream_items() {
+    let mut writer = XmlWriter::new(Vec::new()).unwrap();
+    let count = xmlib::ser::write_iter(&mut writer, b"items", (0..100_000).map(item)).unwrap();
+    assert_eq!(count, 100_000);
+
+    let output = writer.into_inner();
+    assert!(output.starts_with(br#"<items><item id="0">item 0</item><item id="1">"#));
+    let items: Items = xmlib::de::from_bytes(&output).unwrap();
+    assert_eq!(items.0.len(), 100_000);
+    for id in [0, 4_711, 99_999] {
+        assert_eq!(items.0[id as usize], item(id));
+    }
+}
+
+#[test]
+fn stream_items_counted() {
+    let mut writer = XmlWriter::new(Vec::new()).unwrap();
+    let count =
+        xmlib::ser::write_iter_counted(&mut writer, b"items", b"count", (0..100_000).map(item))
+            .unwrap();
+    assert_eq!(count, 100_000);
+
+    let output = writer.into_inner();
+    assert!(output.starts_with(br#"<items count="100000"><item id="0">"#));
+    // the count is checked while deserializing
+    let items: Items = xmlib::de::from_bytes(&output).unwrap();
+    assert_eq!(items.0[99_999], item(99_999));
+}
+
+#[test]
+fn stream_empty() {
+    let mut writer = XmlWriter::new(Vec::new()).unwrap();
+    let count = xmlib::ser::write_iter_counted(&mut writer, b"items", b"count", Vec::<Item>::new())
+        .unwrap();
+    assert_eq!(count, 0);
+    assert_eq!(writer.into_inner(), br#"<items count="0"/>"#);
+}
+
+#[test]
+fn stream_indented() {
+    let mut writer = XmlWriter::new(Vec::new()).unwrap().with_indent(' ', 2);
+    xmlib::ser::write_iter(&mut writer, b"items", (1..=2).map(item)).unwrap();
+    assert_eq!(
+        writer.into_inner(),
+        b"<items>\n  <item id=\"1\">item 1</item>\n  <item id=\"2\">item 2</item>\n</items>"
+    );
+}
+
+// announces more items than it returns
+struct Lying(std::ops::Range<u32>);
+
+impl Iterator for Lying {
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Item> {
+        self.0.next().map(item)
+    }
+}
+
+impl ExactSizeIterator for Lying {
+    fn len(&self) -> usize {
+        self.0.len() + 1
+    }
+}
+
+#[test]
+fn stream_wrong_len() {
+    let mut writer = XmlWriter::new(Vec::new()).unwrap();
+    let err =
+        xmlib::ser::write_iter_counted(&mut writer, b"items", b"count", Lying(0..2)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
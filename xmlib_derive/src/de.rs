@@ -1,18 +1,60 @@
+use heck::ToLowerCamelCase;
 use proc_macro::TokenStream;
-use quote::{quote, ToTokens};
-use syn::Ident;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{ext::IdentExt, spanned::Spanned, Ident};
 
-use crate::parse::{Enum, Field, Input, InputData, NamedStruct, UnnamedStruct};
+use crate::parse::{
+    array_item_ty, map_with_positions, vec_item_ty, AttrCollection, Enum, Field, Input, InputData,
+    NamedStruct, UnnamedStruct, Validation,
+};
 
-pub(crate) fn expand(Input { data, ident }: Input) -> TokenStream {
+pub(crate) fn expand(
+    Input {
+        data,
+        ident,
+        lifetime,
+        erased,
+        ser_only: _,
+        de_only: _,
+    }: Input,
+) -> TokenStream {
     match data {
         InputData::Enum(v) => expand_enum(v, ident),
-        InputData::NamedStruct(v) => expand_named_struct(v, ident),
-        InputData::UnnamedStruct(v) => expand_unnamed_struct(v, ident),
+        InputData::NamedStruct(v) => expand_named_struct(v, ident, lifetime, erased),
+        InputData::UnnamedStruct(v) => expand_unnamed_struct(v, ident, erased),
+    }
+}
+
+// Returns the generics of the impl and the reader type.
+// Erased types are only implemented for `ErasedReader`, so they are compiled only once.
+fn reader_generics(erased: bool) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    if erased {
+        (quote! {<'r__>}, quote! {::xmlib::de::ErasedReader<'r__>})
+    } else {
+        (quote! {<R: ::std::io::BufRead>}, quote! {R})
     }
 }
 
-fn expand_enum(Enum { has_data, variants }: Enum, enum_ident: Ident) -> TokenStream {
+fn expand_enum(
+    Enum {
+        has_data,
+        variants,
+        text_variant,
+        tagged,
+        value_variants,
+        tag_attr,
+    }: Enum,
+    enum_ident: Ident,
+) -> TokenStream {
+    if let Some(text_variant) = text_variant {
+        return expand_mixed_enum(variants, text_variant, enum_ident);
+    }
+    if tagged {
+        return expand_tagged_enum(variants, value_variants, enum_ident);
+    }
+    if let Some(tag_attr) = tag_attr {
+        return expand_tag_attr_enum(variants, tag_attr, enum_ident);
+    }
     let ident_str = enum_ident.to_string();
     let inner_code = if has_data {
         let mut variants: Vec<_> = variants.into_iter().map(|(ident, _name, ty)| {
@@ -34,6 +76,7 @@ fn expand_enum(Enum { has_data, variants }: Enum, enum_ident: Ident) -> TokenStr
         }
     } else {
         let variants = variants.into_iter().map(|(ident, name, _ty)| {
+            let name = proc_macro2::Literal::byte_string(name.as_bytes());
             quote! { #name => ::std::result::Result::Ok(Self::#ident), }
         });
 
@@ -65,15 +108,283 @@ fn expand_enum(Enum { has_data, variants }: Enum, enum_ident: Ident) -> TokenStr
     .into()
 }
 
+// Mixed enums are either the text or one of the child elements
+fn expand_mixed_enum(
+    variants: Vec<(Ident, String, Option<syn::Type>)>,
+    text_variant: Ident,
+    enum_ident: Ident,
+) -> TokenStream {
+    let ident_str = enum_ident.to_string();
+    let children: Vec<_> = variants
+        .into_iter()
+        .filter(|(ident, _, _)| *ident != text_variant)
+        .map(|(ident, _name, ty)| (ident, ty.unwrap()))
+        .collect();
+    let accepts = children.iter().map(|(_, ty)| {
+        quote! {
+            (<#ty as ::xmlib::de::DeserializeElement<R>>::accepts_name(start.local_name())
+                && reader.is_in_namespace(start.name(), <#ty as ::xmlib::de::DeserializeElement<R>>::namespace()))
+        }
+    });
+    let de_children = children.iter().map(|(ident, ty)| {
+        quote! {
+            if <#ty as ::xmlib::de::DeserializeElement<R>>::accepts_name(start.local_name())
+                && reader.is_in_namespace(start.name(), <#ty as ::xmlib::de::DeserializeElement<R>>::namespace()) {
+                return <#ty as ::xmlib::de::DeserializeElement<R>>::de(reader, start).map(Self::#ident);
+            }
+        }
+    });
+
+    quote! {
+        #[automatically_derived]
+        impl<R: ::std::io::BufRead> ::xmlib::de::DeserializeMixed<R> for #enum_ident {
+            #[inline]
+            fn de_text(text: &[u8]) -> ::std::result::Result<Self, ::xmlib::de::Error> {
+                ::xmlib::de::DeserializeBuf::de_buf(text).map(Self::#text_variant)
+            }
+
+            #[inline]
+            fn accepts(
+                reader: &::xmlib::de::XmlReader<R>,
+                start: &::xmlib::exports::events::BytesStart,
+            ) -> bool {
+                false #(|| #accepts)*
+            }
+
+            #[inline]
+            fn de_child(
+                reader: &mut ::xmlib::de::XmlReader<R>,
+                start: ::xmlib::exports::events::BytesStart,
+            ) -> ::std::result::Result<Self, ::xmlib::de::Error> {
+                #(#de_children)*
+                ::std::result::Result::Err(::xmlib::de::Error {
+                    ty_name: ::std::string::String::from(#ident_str),
+                    kind: ::xmlib::de::ErrorKind::UnexpectedEvent(format!("start of {}",
+                        ::std::string::String::from_utf8_lossy(start.name()),
+                    )),
+                })
+            }
+        }
+    }
+    .into()
+}
+
+// Tagged enums are elements with the name of the variant,
+// either empty for unit variants or containing the value as text.
+// The data of `value` variants is the element itself, which is chosen by its own name.
+fn expand_tagged_enum(
+    variants: Vec<(Ident, String, Option<syn::Type>)>,
+    value_variants: Vec<Ident>,
+    enum_ident: Ident,
+) -> TokenStream {
+    let ident_str = enum_ident.to_string();
+    let raw_ser_name = proc_macro2::Literal::byte_string(
+        enum_ident
+            .unraw()
+            .to_string()
+            .to_lower_camel_case()
+            .as_bytes(),
+    );
+    let (value_variants, variants): (Vec<_>, Vec<_>) = variants
+        .into_iter()
+        .partition(|(ident, _, _)| value_variants.contains(ident));
+    let names: Vec<_> = variants
+        .iter()
+        .map(|(_, name, _)| proc_macro2::Literal::byte_string(name.as_bytes()))
+        .collect();
+    let value_tys: Vec<_> = value_variants
+        .iter()
+        .map(|(_, _, ty)| ty.as_ref().unwrap())
+        .collect();
+    let value_variants = value_variants.iter().map(|(ident, _, ty)| {
+        quote! {
+            if <#ty as ::xmlib::de::DeserializeElement<R>>::accepts_name(start__.local_name())
+                && reader__.is_in_namespace(
+                    start__.name(),
+                    <#ty as ::xmlib::de::DeserializeElement<R>>::namespace(),
+                )
+            {
+                return <#ty as ::xmlib::de::DeserializeElement<R>>::de(reader__, start__)
+                    .map(Self::#ident);
+            }
+        }
+    });
+    let variants = variants.iter().zip(&names).map(|((ident, _, ty), name)| match ty {
+        None => quote! {
+            #name => {
+                let mut buf = ::std::vec::Vec::new();
+                if let ::std::result::Result::Err(e) = reader__.read_to_end(start__.name(), &mut buf) {
+                    return ::std::result::Result::Err(::xmlib::de::Error {
+                        ty_name: ::std::string::String::from(#ident_str),
+                        kind: ::xmlib::de::ErrorKind::XmlError(e),
+                    });
+                }
+                ::std::result::Result::Ok(Self::#ident)
+            }
+        },
+        Some(ty) => {
+            // points at the type of the variant if it can't be read from text
+            let check = quote_spanned! {ty.span()=>
+                fn assert_impl__<T: ::xmlib::de::DeserializeBuf>() {}
+                assert_impl__::<#ty>();
+            };
+            quote! {
+                #name => {
+                    #check
+                    let mut buf = ::std::vec::Vec::new();
+                    let mut other_buf = ::std::vec::Vec::new();
+                    let text = match reader__.read_text(start__.name(), &mut buf, &mut other_buf) {
+                        ::std::result::Result::Ok(text) => text,
+                        ::std::result::Result::Err(kind) => return ::std::result::Result::Err(::xmlib::de::Error {
+                            ty_name: ::std::string::String::from(#ident_str),
+                            kind,
+                        }),
+                    };
+                    <#ty as ::xmlib::de::DeserializeBuf>::de_buf(text.as_bytes()).map(Self::#ident)
+                }
+            }
+        }
+    });
+
+    quote! {
+        #[automatically_derived]
+        impl ::xmlib::de::XmlName for #enum_ident {
+            const NAME: &'static [u8] = #raw_ser_name;
+        }
+
+        #[automatically_derived]
+        impl<R: ::std::io::BufRead> ::xmlib::de::DeserializeElement<R> for #enum_ident {
+            #[inline]
+            fn name() -> &'static [u8] {
+                #raw_ser_name
+            }
+
+            #[inline]
+            fn accepts_name(local_name: &[u8]) -> bool {
+                false #(|| local_name == #names)*
+                    #(|| <#value_tys as ::xmlib::de::DeserializeElement<R>>::accepts_name(local_name))*
+            }
+
+            #[inline]
+            fn de(
+                reader__: &mut ::xmlib::de::XmlReader<R>,
+                start__: ::xmlib::exports::events::BytesStart,
+            ) -> ::std::result::Result<Self, ::xmlib::de::Error> {
+                #(#value_variants)*
+                match start__.local_name() {
+                    #(#variants)*
+                    name => ::std::result::Result::Err(::xmlib::de::Error {
+                        ty_name: ::std::string::String::from(#ident_str),
+                        kind: ::xmlib::de::ErrorKind::UnexpectedEvent(format!("start of {}",
+                            ::std::string::String::from_utf8_lossy(name),
+                        )),
+                    }),
+                }
+            }
+        }
+    }
+    .into()
+}
+
+// Enums with `tag_attr` are one of the elements of their variants,
+// which is chosen by the name of the variant in the attribute `tag_attr`
+fn expand_tag_attr_enum(
+    variants: Vec<(Ident, String, Option<syn::Type>)>,
+    tag_attr: String,
+    enum_ident: Ident,
+) -> TokenStream {
+    let ident_str = enum_ident.to_string();
+    let variants: Vec<_> = variants
+        .into_iter()
+        .map(|(ident, name, ty)| (ident, name, ty.unwrap()))
+        .collect();
+    let first_ty = &variants[0].2;
+    let tys = variants.iter().map(|(_, _, ty)| ty);
+    let de_variants = variants.iter().map(|(ident, name, ty)| {
+        let name = proc_macro2::Literal::byte_string(name.as_bytes());
+        quote! {
+            ::std::option::Option::Some(#name) => {
+                <#ty as ::xmlib::de::DeserializeElement<R>>::de(reader__, start__).map(Self::#ident)
+            }
+        }
+    });
+    let key = proc_macro2::Literal::byte_string(tag_attr.as_bytes());
+
+    quote! {
+        #[automatically_derived]
+        impl<R: ::std::io::BufRead> ::xmlib::de::DeserializeElement<R> for #enum_ident {
+            #[inline]
+            fn name() -> &'static [u8] {
+                <#first_ty as ::xmlib::de::DeserializeElement<R>>::name()
+            }
+
+            #[inline]
+            fn accepts_name(local_name: &[u8]) -> bool {
+                false #(|| <#tys as ::xmlib::de::DeserializeElement<R>>::accepts_name(local_name))*
+            }
+
+            #[inline]
+            fn namespace() -> ::std::option::Option<&'static [u8]> {
+                <#first_ty as ::xmlib::de::DeserializeElement<R>>::namespace()
+            }
+
+            #[inline]
+            fn de(
+                reader__: &mut ::xmlib::de::XmlReader<R>,
+                start__: ::xmlib::exports::events::BytesStart,
+            ) -> ::std::result::Result<Self, ::xmlib::de::Error> {
+                let (discriminant__, start__) = match ::xmlib::de::split_attribute(&start__, #key) {
+                    ::std::result::Result::Ok(split) => split,
+                    ::std::result::Result::Err(e) => return ::std::result::Result::Err(::xmlib::de::Error {
+                        ty_name: ::std::string::String::from(#ident_str),
+                        kind: ::xmlib::de::ErrorKind::XmlError(e),
+                    }),
+                };
+                match discriminant__.as_deref() {
+                    #(#de_variants)*
+                    ::std::option::Option::Some(v) => ::std::result::Result::Err(::xmlib::de::Error {
+                        ty_name: ::std::string::String::from(#ident_str),
+                        kind: ::xmlib::de::ErrorKind::InvalidType(
+                            format!("invalid {} {}", #tag_attr,
+                                ::std::string::String::from_utf8_lossy(v))
+                        ),
+                    }),
+                    ::std::option::Option::None => ::std::result::Result::Err(::xmlib::de::Error {
+                        ty_name: ::std::string::String::from(#ident_str),
+                        kind: ::xmlib::de::ErrorKind::MissingAttr(::std::string::String::from(#tag_attr)),
+                    }),
+                }
+            }
+        }
+    }
+    .into()
+}
+
 // Unnamed structs are just new-types and deserialized as them
 fn expand_unnamed_struct(
-    UnnamedStruct { validation, ty }: UnnamedStruct,
+    UnnamedStruct {
+        list,
+        validation,
+        finalize,
+        ty,
+        member,
+    }: UnnamedStruct,
     struct_ident: Ident,
+    erased: bool,
 ) -> TokenStream {
+    if let Some(raw_ser_name) = list {
+        return expand_list(raw_ser_name, validation, finalize, ty, struct_ident, erased);
+    }
+
     let ident_str = struct_ident.to_string();
     let inner_ident = Ident::new("inner", proc_macro2::Span::call_site());
     let validation =
         validation.map(|validation| create_validation(&validation, &inner_ident, &ident_str));
+    let finish = create_finish(
+        finalize.as_ref(),
+        quote! {Self { #member: inner }},
+        &ident_str,
+    );
 
     quote! {
         #[automatically_derived]
@@ -82,14 +393,14 @@ fn expand_unnamed_struct(
             fn de_buf(
                 buf: &[u8],
             ) -> ::std::result::Result<Self, ::xmlib::de::Error> {
-                let inner = match #ty::de_buf(buf) {
+                let inner = match <#ty as ::xmlib::de::DeserializeBuf>::de_buf(buf) {
                     ::std::result::Result::Ok(inner) => inner,
                     ::std::result::Result::Err(e) => return ::std::result::Result::Err(e),
                 };
 
                 #validation
 
-                ::std::result::Result::Ok(Self(inner))
+                #finish
             }
         }
 
@@ -97,52 +408,176 @@ fn expand_unnamed_struct(
     .into()
 }
 
-fn expand_named_struct(s: NamedStruct, struct_ident: Ident) -> TokenStream {
+// Lists are new-types around a Vec and deserialized as element containing all items
+fn expand_list(
+    raw_ser_name: String,
+    validation: Option<Validation>,
+    finalize: Option<syn::Expr>,
+    ty: syn::Type,
+    struct_ident: Ident,
+    erased: bool,
+) -> TokenStream {
+    let (impl_generics, reader_ty) = reader_generics(erased);
+    let inner_ident = Ident::new("inner", proc_macro2::Span::call_site());
+    let validation =
+        validation.map(|validation| create_validation(&validation, &inner_ident, &raw_ser_name));
+    let finish = create_finish(finalize.as_ref(), quote! {Self(inner)}, &raw_ser_name);
+    let ty_name = raw_ser_name.clone();
+    let raw_ser_name = proc_macro2::Literal::byte_string(raw_ser_name.as_bytes());
+
+    quote! {
+        #[automatically_derived]
+        impl ::xmlib::de::XmlName for #struct_ident {
+            const NAME: &'static [u8] = #raw_ser_name;
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::xmlib::de::DeserializeElement<#reader_ty> for #struct_ident {
+            #[inline]
+            fn name() -> &'static [u8] {
+                #raw_ser_name
+            }
+
+            #[inline]
+            fn de(
+                reader__: &mut ::xmlib::de::XmlReader<#reader_ty>,
+                start__: ::xmlib::exports::events::BytesStart,
+            ) -> ::std::result::Result<Self, ::xmlib::de::Error> {
+                use ::xmlib::exports::events::Event;
+
+                reader__.check_depth(start__.name())?;
+                let mut buf = reader__.take_buf();
+                let mut inner: #ty = ::std::default::Default::default();
+
+                loop {
+                    let event = match reader__.read_event(&mut buf) {
+                        ::std::result::Result::Ok(event) => event,
+                        ::std::result::Result::Err(e) => return ::std::result::Result::Err(::xmlib::de::Error {
+                            ty_name: ::std::string::String::from(#ty_name),
+                            kind: ::xmlib::de::ErrorKind::XmlError(e),
+                        }),
+                    };
+                    match event {
+                        Event::Start(e)
+                            if <#ty as ::xmlib::de::DeserializeElement<#reader_ty>>::accepts_name(e.local_name())
+                                && reader__.is_in_namespace(e.name(), <#ty as ::xmlib::de::DeserializeElement<#reader_ty>>::namespace()) => {
+                            inner.push(::xmlib::de::DeserializeElement::de(reader__, e).map_err(|e| e.in_parent(#ty_name))?);
+                        }
+                        Event::End(e) if e.local_name() == start__.local_name() => {
+                            break;
+                        }
+                        Event::Text(e) if e.is_empty() => {}
+                        e => {
+                            return ::std::result::Result::Err(::xmlib::de::Error {
+                                ty_name: ::std::string::String::from(#ty_name),
+                                kind: ::xmlib::de::ErrorKind::UnexpectedEvent(format!("{:?}", e)),
+                            })
+                        }
+                    }
+                }
+                reader__.recycle_buf(buf);
+
+                #validation
+
+                #finish
+            }
+        }
+    }
+    .into()
+}
+
+fn expand_named_struct(
+    s: NamedStruct,
+    struct_ident: Ident,
+    lifetime: Option<syn::Lifetime>,
+    erased: bool,
+) -> TokenStream {
     let NamedStruct {
         no_constructor: _,
+        derive_default: _,
+        reflect: _,
+        finalize,
         raw_ser_name,
+        namespace,
+        ignore_unknown_children,
         ty_attribute,
         ty_value,
         ty_value_buf,
+        ty_mixed,
         ty_collect_namespaces,
+        ty_collect_attrs,
+        ty_collect_comments,
+        field_order: _,
     } = s;
 
+    // structs with a lifetime borrow from the input, so they can only be deserialized from slices
+    // and read the events unbuffered
+    let (impl_header, reader_ty, buf_code, read_event_code, recycle_code) = if let Some(lifetime) =
+        &lifetime
+    {
+        (
+            quote! {
+                impl<#lifetime> ::xmlib::de::DeserializeElement<&#lifetime [u8]> for #struct_ident<#lifetime>
+            },
+            quote! {&#lifetime [u8]},
+            quote! {},
+            quote! {reader__.read_event_unbuffered()},
+            quote! {},
+        )
+    } else {
+        let (impl_generics, reader_ty) = reader_generics(erased);
+        (
+            quote! {
+                impl #impl_generics ::xmlib::de::DeserializeElement<#reader_ty> for #struct_ident
+            },
+            reader_ty,
+            quote! {let mut buf = reader__.take_buf();},
+            quote! {reader__.read_event(&mut buf)},
+            quote! {reader__.recycle_buf(buf);},
+        )
+    };
+
+    let impl_lifetime = lifetime.as_ref().map(|lifetime| quote! {<#lifetime>});
+
     let mut init_code = Vec::new();
     let mut attr_ser_code = Vec::new();
     let mut value_ser_code = Vec::new();
     let mut pre_finish_code = Vec::new();
     let mut validation_code = Vec::new();
     let mut finish_code = Vec::new();
+    // assertions pointing at the field if its type doesn't implement the needed trait
+    let mut trait_checks = Vec::new();
 
-    let mut process_field = |field: &Field| {
+    // `child` is true for fields deserialized from child elements
+    let mut process_field = |field: &Field, child: bool| {
         let ident = &field.ident;
-        let default = field.default.as_ref().map(|default| {
-            if let syn::Lit::Str(lit) = default {
-                match &field.ty {
-                    syn::Type::Path(syn::TypePath { qself: None, path })
-                        if path.is_ident("String") =>
-                    {
-                        quote! {::std::string::String::from(#lit)}
-                    }
-                    _ => syn::parse2(syn::parse_str(&lit.value()).unwrap()).unwrap(),
-                }
-            } else {
-                quote! {#default}
-            }
-        });
+        let default = field.default.clone();
 
         let init_val = if let Some(default) = default.as_ref() {
             default.clone()
         } else {
-            if !field.has_multiple {
-                let name = &field.name;
+            if !field.has_multiple && !field.optional {
+                let name = &field.de_name;
+                let kind = if !child {
+                    quote! {::xmlib::de::ErrorKind::MissingAttr(::std::string::String::from(#name))}
+                } else if field.element {
+                    quote! {::xmlib::de::ErrorKind::MissingChild(::std::string::String::from(#name))}
+                } else {
+                    // the name of `value` fields is only known from the type
+                    let ty = &field.ty;
+                    quote! {
+                        ::xmlib::de::ErrorKind::MissingChild(
+                            ::std::string::String::from_utf8_lossy(<#ty as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string()
+                        )
+                    }
+                };
                 pre_finish_code.push(quote! {
                     let #ident = match #ident {
                         ::std::option::Option::Some(val) => val,
                         ::std::option::Option::None => return ::std::result::Result::Err(
                             ::xmlib::de::Error {
                                 ty_name: ::std::string::String::from(#raw_ser_name),
-                                kind: ::xmlib::de::ErrorKind::MissingAttr(::std::string::String::from(#name)),
+                                kind: #kind,
                             }
                         )
                     };
@@ -153,26 +588,110 @@ fn expand_named_struct(s: NamedStruct, struct_ident: Ident) -> TokenStream {
 
         finish_code.push(quote! {#ident, });
 
+        if field.min.is_some() || field.max.is_some() {
+            let name = &field.de_name;
+            let min = option_tokens(field.min);
+            let max = option_tokens(field.max);
+            let mut checks = Vec::new();
+            if let Some(min) = field.min {
+                checks.push(quote! {#ident.len() < #min});
+            }
+            if let Some(max) = field.max {
+                checks.push(quote! {#ident.len() > #max});
+            }
+            pre_finish_code.push(quote! {
+                if #(#checks)||* {
+                    return ::std::result::Result::Err(::xmlib::de::Error {
+                        ty_name: ::std::string::String::from(#raw_ser_name),
+                        kind: ::xmlib::de::ErrorKind::Cardinality {
+                            name: ::std::string::String::from(#name),
+                            got: #ident.len(),
+                            min: #min,
+                            max: #max,
+                        },
+                    });
+                }
+            });
+        }
+
         if let Some(validation) = &field.validation {
-            validation_code.push(create_validation(validation, ident, &field.name));
+            validation_code.push(create_validation(validation, ident, &field.de_name));
         }
 
         let ty = &field.ty;
-        init_code.push(if default.is_some() || field.has_multiple {
+        init_code.push(if let Some(len) = &field.array_len {
+            // the children are collected before converting them into the array
+            let item_ty = array_item_ty(ty).unwrap();
+            let name = &field.de_name;
+            pre_finish_code.push(quote! {
+                let #ident: #ty = match ::std::convert::TryFrom::try_from(#ident) {
+                    ::std::result::Result::Ok(array) => array,
+                    ::std::result::Result::Err(items) => {
+                        let items: ::std::vec::Vec<#item_ty> = items;
+                        return ::std::result::Result::Err(::xmlib::de::Error {
+                            ty_name: ::std::string::String::from(#raw_ser_name),
+                            kind: ::xmlib::de::ErrorKind::Cardinality {
+                                name: ::std::string::String::from(#name),
+                                got: items.len(),
+                                min: ::std::option::Option::Some(#len),
+                                max: ::std::option::Option::Some(#len),
+                            },
+                        });
+                    }
+                };
+            });
+            quote! {let mut #ident: ::std::vec::Vec<#item_ty> = ::std::vec::Vec::new();}
+        } else if default.is_some() || field.has_multiple || field.optional {
             quote! {let mut #ident: #ty = #init_val;}
         } else {
             quote! {let mut #ident: ::std::option::Option<#ty> = #init_val;}
         });
+        if field.key.is_some() {
+            // positions of the children to report duplicate keys
+            let positions = format_ident!("{}_positions__", ident.unraw());
+            let positions_ty = map_with_positions(ty);
+            init_code.push(quote! {
+                let mut #positions: #positions_ty = ::std::default::Default::default();
+            });
+        }
 
         default
     };
 
     for field in ty_attribute {
-        let default = process_field(&field);
-        let name_str = proc_macro2::Literal::byte_string(field.name.as_bytes());
+        let default = process_field(&field, false);
+        if field.borrow {
+            trait_checks.push(assert_impl(
+                &field.ty,
+                &reader_ty,
+                quote! {::xmlib::de::DeserializeBufBorrowed<'de>},
+            ));
+        } else if field.with.is_none() {
+            trait_checks.push(assert_impl(
+                &field.ty,
+                &reader_ty,
+                quote! {::xmlib::de::DeserializeBuf},
+            ));
+        }
+        let name_str = proc_macro2::Literal::byte_string(field.de_name.as_bytes());
 
         // TODO remove ?
-        let mut code = quote! { ::xmlib::de::DeserializeBuf::de_buf(&attr.value)? };
+        let mut code = if field.borrow {
+            quote! {{
+                let value = match attr.unescaped_value() {
+                    ::std::result::Result::Ok(value) => value,
+                    ::std::result::Result::Err(e) => return ::std::result::Result::Err(::xmlib::de::Error {
+                        ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
+                        kind: ::xmlib::de::ErrorKind::XmlError(e),
+                    }),
+                };
+                ::xmlib::de::DeserializeBufBorrowed::de_buf_borrowed(reader__.borrow_input(value))?
+            }}
+        } else if field.trim {
+            de_buf_call(&field, quote! {::xmlib::de::trim_whitespace(&attr.value)})
+        } else {
+            de_buf_call(&field, quote! {&attr.value})
+        };
         if default.is_none() {
             code = quote! {::std::option::Option::Some(#code)};
         }
@@ -182,36 +701,240 @@ fn expand_named_struct(s: NamedStruct, struct_ident: Ident) -> TokenStream {
         });
     }
 
+    let element_buf_code = if ty_value.iter().any(|field| field.element) {
+        quote! {
+            let mut text_buf__ = reader__.take_buf();
+            let mut text_other_buf__ = reader__.take_buf();
+        }
+    } else {
+        quote! {}
+    };
+    let element_recycle_code = if ty_value.iter().any(|field| field.element) {
+        quote! {
+            reader__.recycle_buf(text_buf__);
+            reader__.recycle_buf(text_other_buf__);
+        }
+    } else {
+        quote! {}
+    };
+
     for field in ty_value {
-        let default = process_field(&field);
-        let ty = field.ty;
+        let default = process_field(&field, true);
+        // children of `multiple` fields are deserialized one by one
+        let ty = field.item_ty();
+        if !field.element {
+            trait_checks.push(assert_impl(
+                ty,
+                &reader_ty,
+                quote! {::xmlib::de::DeserializeElement<R__>},
+            ));
+        } else if field.with.is_none() {
+            trait_checks.push(assert_impl(
+                ty,
+                &reader_ty,
+                quote! {::xmlib::de::DeserializeBuf},
+            ));
+        }
+        let element_de_code = de_buf_call(&field, quote! {text.as_bytes()});
         let ident = &field.ident;
-        // TODO remove ?
-        let mut code = quote! { ::xmlib::de::DeserializeElement::de(&mut reader__, e)? };
-        let code = if field.has_multiple {
+        let child_namespace = if field.element {
+            field.namespace.as_ref().map(|namespace| {
+                let namespace = proc_macro2::Literal::byte_string(namespace.as_bytes());
+                quote! { && reader__.is_in_namespace(e.name(), ::std::option::Option::Some(#namespace)) }
+            })
+        } else {
+            Some(quote! {
+                && reader__.is_in_namespace(e.name(), <#ty as ::xmlib::de::DeserializeElement<#reader_ty>>::namespace())
+            })
+        };
+        let (child_match, mut code) = if field.element {
+            (
+                {
+                    let name = proc_macro2::Literal::byte_string(field.de_name.as_bytes());
+                    quote! { e.local_name() == #name }
+                },
+                // TODO remove ?
+                quote! {{
+                    text_buf__.clear();
+                    let text = match reader__.read_text(e.name(), &mut text_buf__, &mut text_other_buf__) {
+                        ::std::result::Result::Ok(text) => text,
+                        ::std::result::Result::Err(kind) => return ::std::result::Result::Err(::xmlib::de::Error {
+                            ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
+                            kind,
+                        }),
+                    };
+                    let value__ = #element_de_code;
+                    // reuse the allocation of the text for the next element
+                    text_other_buf__ = text.into_bytes();
+                    value__
+                }},
+            )
+        } else {
+            (
+                quote! { <#ty as ::xmlib::de::DeserializeElement<#reader_ty>>::accepts_name(e.local_name()) },
+                // TODO remove ?
+                quote! { ::xmlib::de::DeserializeElement::de(&mut reader__, e).map_err(|e| e.in_parent(#raw_ser_name))? },
+            )
+        };
+        if field.nillable {
+            code = quote! {
+                if reader__.is_nil(&e) {
+                    let mut nil_buf__ = reader__.take_buf();
+                    if let ::std::result::Result::Err(e) = reader__.read_to_end(e.name(), &mut nil_buf__) {
+                        return ::std::result::Result::Err(::xmlib::de::Error {
+                            ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
+                            kind: ::xmlib::de::ErrorKind::XmlError(e),
+                        });
+                    }
+                    reader__.recycle_buf(nil_buf__);
+                    ::std::option::Option::None
+                } else {
+                    #code
+                }
+            };
+        }
+        let code = if let Some(key) = &field.key {
+            let positions = format_ident!("{}_positions__", ident.unraw());
+            let name = &field.de_name;
+            quote! {{
+                let position__ = reader__.buffer_position();
+                let item__: #ty = #code;
+                let key__ = ::std::clone::Clone::clone(&item__.#key);
+                if let ::std::option::Option::Some(first) = #positions.get(&key__) {
+                    return ::std::result::Result::Err(::xmlib::de::Error {
+                        ty_name: ::std::string::String::from(#raw_ser_name),
+                        kind: ::xmlib::de::ErrorKind::DuplicateKey {
+                            name: ::std::string::String::from(#name),
+                            key: format!("{:?}", key__),
+                            first: *first,
+                            duplicate: position__,
+                        },
+                    });
+                }
+                #positions.insert(::std::clone::Clone::clone(&key__), position__);
+                #ident.insert(key__, item__);
+            }}
+        } else if field.array_len.is_some() {
             quote! { #ident.push(#code) }
+        } else if field.has_multiple {
+            let collection_ty = &field.ty;
+            quote! { <#collection_ty as ::xmlib::de::CollectChild<#ty>>::push_child(&mut #ident, #code) }
         } else {
-            if default.is_none() {
+            if default.is_none() && !field.optional {
                 code = quote! {::std::option::Option::Some(#code)};
             }
             quote! { #ident = #code }
         };
+        if let Some(wrapper) = &field.wrapper {
+            let wrapper = proc_macro2::Literal::byte_string(wrapper.as_bytes());
+            value_ser_code.push(quote! {
+                Event::Start(e) if e.local_name() == #wrapper => {
+                    #buf_code
+                    loop {
+                        let event = match #read_event_code {
+                            ::std::result::Result::Ok(event) => event,
+                            ::std::result::Result::Err(e) => return ::std::result::Result::Err(::xmlib::de::Error {
+                                ty_name: ::std::string::String::from_utf8_lossy(#wrapper).to_string(),
+                                kind: ::xmlib::de::ErrorKind::XmlError(e),
+                            }),
+                        };
+                        match event {
+                            Event::Start(e) if #child_match #child_namespace => {
+                                #code;
+                            }
+                            Event::End(e) if e.local_name() == #wrapper => {
+                                break;
+                            }
+                            Event::Text(e) if e.iter().all(|c| c.is_ascii_whitespace()) => {}
+                            Event::Comment(_) => {}
+                            e => {
+                                return ::std::result::Result::Err(::xmlib::de::Error {
+                                    ty_name: ::std::string::String::from_utf8_lossy(#wrapper).to_string(),
+                                    kind: ::xmlib::de::ErrorKind::UnexpectedEvent(format!("{:?}", e)),
+                                })
+                            }
+                        }
+                    }
+                    #recycle_code
+                }
+            });
+        } else {
+            value_ser_code.push(quote! {
+                Event::Start(e) if #child_match #child_namespace => {
+                    #code;
+                }
+            });
+        }
+    }
+
+    let mut text_finish_code = None;
+
+    if let Some(field) = ty_value_buf.as_ref().filter(|field| field.has_multiple) {
+        process_field(field, false);
+        let ty = field.item_ty();
+        if field.with.is_none() {
+            trait_checks.push(assert_impl(
+                ty,
+                &reader_ty,
+                quote! {::xmlib::de::DeserializeBuf},
+            ));
+        }
+        let ident = &field.ident;
+        let push = |buf: proc_macro2::TokenStream| {
+            let code = de_buf_call(field, buf);
+            if field.array_len.is_some() {
+                quote! { #ident.push(#code) }
+            } else {
+                let collection_ty = &field.ty;
+                quote! { <#collection_ty as ::xmlib::de::CollectChild<#ty>>::push_child(&mut #ident, #code) }
+            }
+        };
+        let text_code = push(quote! {&unescaped});
+        let cdata_code = push(quote! {&e});
+
+        // every text event is one item, e.g. text separated by comments
         value_ser_code.push(quote! {
-            Event::Start(e) if e.local_name() == <#ty as ::xmlib::de::DeserializeElement<R>>::name() => {
-                #code;
+            Event::Text(e) if e.iter().all(|c| c.is_ascii_whitespace()) => {}
+            Event::Text(e) => {
+                let unescaped = match e.unescaped() {
+                    ::std::result::Result::Ok(unescaped) => unescaped,
+                    ::std::result::Result::Err(e) => return ::std::result::Result::Err(::xmlib::de::Error {
+                        ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
+                        kind: ::xmlib::de::ErrorKind::XmlError(e),
+                    }),
+                };
+                #text_code;
+            }
+            Event::CData(e) => {
+                #cdata_code;
             }
         });
-    }
-
-    if let Some(field) = ty_value_buf {
-        let default = process_field(&field);
+    } else if let Some(field) = ty_value_buf {
+        let default = process_field(&field, false);
+        if field.with.is_none() {
+            trait_checks.push(assert_impl(
+                &field.ty,
+                &reader_ty,
+                quote! {::xmlib::de::DeserializeBuf},
+            ));
+        }
         // TODO remove ?
-        let mut code = quote! { ::xmlib::de::DeserializeBuf::de_buf(e.into_inner().as_ref())? };
+        let mut code = de_buf_call(&field, quote! {&text__});
         if default.is_none() {
             code = quote! {::std::option::Option::Some(#code)};
         }
         let ident = &field.ident;
-        let code = quote! { #ident = #code };
+
+        // the text may be split into multiple events, e.g. by comments or CDATA sections,
+        // so collect all of them before deserializing
+        init_code.push(quote! {
+            let mut text__: ::std::option::Option<::std::vec::Vec<u8>> = ::std::option::Option::None;
+        });
+        text_finish_code = Some(quote! {
+            if let ::std::option::Option::Some(text__) = text__ {
+                #ident = #code;
+            }
+        });
 
         // TODO find a better solution
         value_ser_code.push(quote! {
@@ -219,7 +942,19 @@ fn expand_named_struct(s: NamedStruct, struct_ident: Ident) -> TokenStream {
         });
 
         value_ser_code.push(quote! {
-            Event::Text(e) => #code,
+            Event::Text(e) => {
+                let unescaped = match e.unescaped() {
+                    ::std::result::Result::Ok(unescaped) => unescaped,
+                    ::std::result::Result::Err(e) => return ::std::result::Result::Err(::xmlib::de::Error {
+                        ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
+                        kind: ::xmlib::de::ErrorKind::XmlError(e),
+                    }),
+                };
+                text__.get_or_insert_with(::std::vec::Vec::new).extend_from_slice(&unescaped);
+            }
+            Event::CData(e) => {
+                text__.get_or_insert_with(::std::vec::Vec::new).extend_from_slice(&e);
+            }
             /*Event::Start(e) if e.local_name() == <#ty as ::xmlib::de::Deserialize<R>>::name() => {
                 // read the inner text
                 #ident = match reader.read_event(&mut buf) {
@@ -241,59 +976,220 @@ fn expand_named_struct(s: NamedStruct, struct_ident: Ident) -> TokenStream {
         });
     }
 
+    if let Some(field) = ty_mixed {
+        let ident = &field.ident;
+        let ty = &field.ty;
+        let item_ty = vec_item_ty(ty).unwrap();
+        trait_checks.push(assert_impl(
+            item_ty,
+            &reader_ty,
+            quote! {::xmlib::de::DeserializeMixed<R__>},
+        ));
+        if let Some(validation) = &field.validation {
+            validation_code.push(create_validation(validation, ident, &field.de_name));
+        }
+        init_code.push(quote! {let mut #ident: #ty = ::std::default::Default::default();});
+        finish_code.push(quote! {#ident, });
+
+        // text and children are collected in document order
+        value_ser_code.push(quote! {
+            Event::Text(e) if !e.is_empty() => {
+                let unescaped = match e.unescaped() {
+                    ::std::result::Result::Ok(unescaped) => unescaped,
+                    ::std::result::Result::Err(e) => return ::std::result::Result::Err(::xmlib::de::Error {
+                        ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
+                        kind: ::xmlib::de::ErrorKind::XmlError(e),
+                    }),
+                };
+                #ident.push(<#item_ty as ::xmlib::de::DeserializeMixed<#reader_ty>>::de_text(&unescaped)?);
+            }
+            Event::CData(e) => {
+                #ident.push(<#item_ty as ::xmlib::de::DeserializeMixed<#reader_ty>>::de_text(&e)?);
+            }
+            Event::Start(e) if <#item_ty as ::xmlib::de::DeserializeMixed<#reader_ty>>::accepts(reader__, &e) => {
+                #ident.push(<#item_ty as ::xmlib::de::DeserializeMixed<#reader_ty>>::de_child(&mut reader__, e).map_err(|e| e.in_parent(#raw_ser_name))?);
+            }
+        });
+    }
+
+    if let Some(ident) = ty_collect_comments {
+        init_code.push(quote! {let mut #ident = ::std::vec::Vec::new();});
+        // the content of comments isn't escaped
+        value_ser_code.push(quote! {
+            Event::Comment(e) => {
+                #ident.push(<::std::string::String as ::xmlib::de::DeserializeBuf>::de_buf(&e)?);
+            }
+        });
+        finish_code.push(quote! {#ident, });
+    }
+
+    // later duplicates of an attribute replace the earlier value
+    let collect_attrs_code = ty_collect_attrs.map(|(field, collection)| {
+        let ident = &field.ident;
+        let ty = &field.ty;
+        init_code.push(quote! {let mut #ident: #ty = ::std::default::Default::default();});
+        finish_code.push(quote! {#ident, });
+        let insert = match collection {
+            AttrCollection::Vec => quote! {
+                match #ident.iter_mut().find(|(key, _)| *key == name) {
+                    ::std::option::Option::Some(entry) => entry.1 = value,
+                    ::std::option::Option::None => #ident.push((name, value)),
+                }
+            },
+            AttrCollection::Map => quote! {
+                #ident.insert(name, value);
+            },
+        };
+        quote! {{
+            let value = match attr.unescaped_value() {
+                ::std::result::Result::Ok(value) => value,
+                ::std::result::Result::Err(e) => return ::std::result::Result::Err(::xmlib::de::Error {
+                    ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
+                    kind: ::xmlib::de::ErrorKind::XmlError(e),
+                }),
+            };
+            let name = <::std::string::String as ::xmlib::de::DeserializeBuf>::de_buf(name)?;
+            let value = <::std::string::String as ::xmlib::de::DeserializeBuf>::de_buf(&value)?;
+            #insert
+        }}
+    });
+    let has_collect_attrs = collect_attrs_code.is_some();
+
     if let Some(ident) = ty_collect_namespaces {
         init_code.push(quote! {let mut #ident = ::std::vec::Vec::new();});
         attr_ser_code.push(quote! {
             name => if name.len() >= 5 && &name[..5] == &b"xmlns"[..] {
-                #ident.push((name.to_owned(), attr.value.into_owned()));
+                let value = match attr.unescaped_value() {
+                    ::std::result::Result::Ok(value) => value.into_owned(),
+                    ::std::result::Result::Err(e) => return ::std::result::Result::Err(::xmlib::de::Error {
+                        ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
+                        kind: ::xmlib::de::ErrorKind::XmlError(e),
+                    }),
+                };
+                #ident.push((name.to_owned(), value));
+            } else {
+                #collect_attrs_code
             }
         });
         finish_code.push(quote! {#ident, });
+    } else if namespace.is_some() {
+        // the namespace is declared by the element itself
+        attr_ser_code.push(quote! {
+            b"xmlns" => {}
+        });
+        if let Some(code) = collect_attrs_code {
+            attr_ser_code.push(quote! {
+                name => #code
+            });
+        }
+    } else if let Some(code) = collect_attrs_code {
+        attr_ser_code.push(quote! {
+            name => #code
+        });
     }
 
+    let finish = create_finish(
+        finalize.as_ref(),
+        quote! {
+            Self {
+                #(#finish_code)*
+            }
+        },
+        &raw_ser_name,
+    );
+
     let raw_ser_name = proc_macro2::Literal::byte_string(raw_ser_name.as_bytes());
+    let namespace_code = namespace.map(|namespace| {
+        let namespace = proc_macro2::Literal::byte_string(namespace.as_bytes());
+        quote! {
+            #[inline]
+            fn namespace() -> ::std::option::Option<&'static [u8]> {
+                ::std::option::Option::Some(#namespace)
+            }
+        }
+    });
+
+    // quick-xml rejects duplicate attributes by default, but the collected attributes keep the
+    // last value
+    let attributes_code = if has_collect_attrs {
+        quote! {start__.attributes().with_checks(false)}
+    } else {
+        quote! {start__.attributes()}
+    };
+
+    let unknown_child_code = if ignore_unknown_children {
+        quote! {
+            let mut skip_buf__ = reader__.take_buf();
+            if let Err(e) = reader__.read_to_end(name, &mut skip_buf__) {
+                return ::std::result::Result::Err(::xmlib::de::Error {
+                    ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
+                    kind: ::xmlib::de::ErrorKind::XmlError(e)
+                })
+            }
+            reader__.recycle_buf(skip_buf__);
+        }
+    } else {
+        quote! {
+            return ::std::result::Result::Err(::xmlib::de::Error {
+                ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
+                kind: ::xmlib::de::ErrorKind::UnexpectedEvent(format!("start of {}",
+                    ::std::string::String::from_utf8_lossy(name),
+                ))
+            })
+        }
+    };
 
     // TODO documentation
     // TODO remove ?
     quote! {
         #[automatically_derived]
-        impl<R: ::std::io::BufRead> ::xmlib::de::DeserializeElement<R> for #struct_ident {
+        impl #impl_lifetime ::xmlib::de::XmlName for #struct_ident #impl_lifetime {
+            const NAME: &'static [u8] = #raw_ser_name;
+        }
+
+        #[automatically_derived]
+        #impl_header {
             #[inline]
             fn name() -> &'static [u8] {
                 #raw_ser_name
             }
 
+            #namespace_code
+
             #[inline]
             fn de(
-                mut reader__: &mut ::xmlib::de::XmlReader<R>,
+                mut reader__: &mut ::xmlib::de::XmlReader<#reader_ty>,
                 start__: ::xmlib::exports::events::BytesStart,
             ) -> ::std::result::Result<Self, ::xmlib::de::Error> {
                 use ::xmlib::exports::events::Event;
 
+                #(#trait_checks)*
+
+                reader__.check_depth(start__.name())?;
                 #(#init_code)*
 
-                for attr in start__.attributes() {
+                for attr in #attributes_code {
                     let attr = match attr {
                         Ok(attr) => attr,
                         Err(e) => return ::std::result::Result::Err(::xmlib::de::Error {
-                            ty_name: ::std::string::String::from_utf8_lossy(<#struct_ident as ::xmlib::de::DeserializeElement<R>>::name()).to_string(),
+                            ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
                             kind: ::xmlib::de::ErrorKind::XmlError(::xmlib::exports::Error::InvalidAttr(e)),
                         })
                     };
                     match attr.key {
                         #(#attr_ser_code)*
                         name => {
-                            if let ::std::option::Option::Some(i) = ::xmlib::exports::memchr(b':', name) {
-                                 println!("ignored attribute with namespace {} for {} (name = {})",
-                                     ::std::string::String::from_utf8_lossy(&name[..i]),
-                                     ::std::string::String::from_utf8_lossy(<#struct_ident as ::xmlib::de::DeserializeElement<R>>::name()),
-                                     ::std::string::String::from_utf8_lossy(name)
-                                );
+                            if ::xmlib::exports::memchr(b':', name).is_some() {
+                                if let ::std::result::Result::Err(e) = reader__.resolve_prefix(name) {
+                                    return ::std::result::Result::Err(e.in_parent(
+                                        &::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name())
+                                    ));
+                                }
                             } else {
                                 return ::std::result::Result::Err(::xmlib::de::Error {
                                     ty_name: ::std::string::String::from_utf8_lossy(name).to_string(),
                                     kind: ::xmlib::de::ErrorKind::UnexpectedEvent(
-                                        ::std::string::String::from_utf8_lossy(<#struct_ident as ::xmlib::de::DeserializeElement<R>>::name()).to_string(),
+                                        ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
                                     ),
                                     }
                                 )
@@ -302,84 +1198,150 @@ fn expand_named_struct(s: NamedStruct, struct_ident: Ident) -> TokenStream {
                     }
                 }
 
-                let mut buf = ::std::vec::Vec::with_capacity(64);
+                #buf_code
+                #element_buf_code
 
                 loop {
-                    match reader__.read_event(&mut buf).unwrap() {
+                    let event = match #read_event_code {
+                        ::std::result::Result::Ok(event) => event,
+                        ::std::result::Result::Err(e) => return ::std::result::Result::Err(::xmlib::de::Error {
+                            ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
+                            kind: ::xmlib::de::ErrorKind::XmlError(e),
+                        }),
+                    };
+                    match event {
                         #(#value_ser_code)*
-                        Event::End(e) if e.local_name() == #raw_ser_name => {
+                        Event::End(e) if e.local_name() == start__.local_name() => {
                             break;
                         }
                         Event::Text(e) if e.is_empty() => {}
+                        Event::Comment(_) => {}
                         // TODO
                         Event::Start(bytes) => {
                             let name = bytes.name();
-                            if let ::std::option::Option::Some(i) = ::xmlib::exports::memchr(b':', name) {
-                                println!("ignored namespaced element {} for {} (name = {})",
-                                     ::std::string::String::from_utf8_lossy(&name[..i]),
-                                     ::std::string::String::from_utf8_lossy(<#struct_ident as ::xmlib::de::DeserializeElement<R>>::name()),
-                                     ::std::string::String::from_utf8_lossy(name)
-                                );
-                                if let Err(e) = reader__.read_to_end(name, &mut ::std::vec::Vec::with_capacity(64)) {
+                            if ::xmlib::exports::memchr(b':', name).is_some() {
+                                if let ::std::result::Result::Err(e) = reader__.resolve_prefix(name) {
+                                    return ::std::result::Result::Err(e.in_parent(
+                                        &::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name())
+                                    ));
+                                }
+                                let mut skip_buf__ = reader__.take_buf();
+                                if let Err(e) = reader__.read_to_end(name, &mut skip_buf__) {
                                     return ::std::result::Result::Err(::xmlib::de::Error {
-                                        ty_name: ::std::string::String::from_utf8_lossy(<#struct_ident as ::xmlib::de::DeserializeElement<R>>::name()).to_string(),
+                                        ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
                                         kind: ::xmlib::de::ErrorKind::XmlError(e)
                                     })
                                 }
+                                reader__.recycle_buf(skip_buf__);
                             } else {
-                                return ::std::result::Result::Err(::xmlib::de::Error {
-                                    ty_name: ::std::string::String::from_utf8_lossy(<#struct_ident as ::xmlib::de::DeserializeElement<R>>::name()).to_string(),
-                                    kind: ::xmlib::de::ErrorKind::UnexpectedEvent(format!("start of {}",
-                                        ::std::string::String::from_utf8_lossy(name),
-                                    ))
-                                })
+                                #unknown_child_code
                             }
                         }
                         e => {
                             return ::std::result::Result::Err(::xmlib::de::Error {
-                                ty_name: ::std::string::String::from_utf8_lossy(<#struct_ident as ::xmlib::de::DeserializeElement<R>>::name()).to_string(),
+                                ty_name: ::std::string::String::from_utf8_lossy(<Self as ::xmlib::de::DeserializeElement<#reader_ty>>::name()).to_string(),
                                 kind: ::xmlib::de::ErrorKind::UnexpectedEvent(format!("{:?}", e))
                             })
                         }
                     }
                 }
+                #recycle_code
+                #element_recycle_code
 
+                #text_finish_code
                 #(#pre_finish_code)*
                 #(#validation_code)*
 
-                Ok(Self {
-                    #(#finish_code)*
-                })
+                #finish
             }
         }
     }
     .into()
 }
 
+// Creates an assertion that the type implements the trait with the span of the type,
+// so the compiler error points at the field instead of the derive
+// The bound may use the lifetime `'de` and the reader type `R__`.
+fn assert_impl(
+    ty: &syn::Type,
+    reader_ty: &proc_macro2::TokenStream,
+    bound: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote_spanned! {ty.span()=>
+        {
+            fn assert_impl__<'de, R__: ::std::io::BufRead, T: #bound>() {}
+            assert_impl__::<#reader_ty, #ty>();
+        }
+    }
+}
+
+// Deserializes the buffer with the `with` module of the field, in the radix of the field, as
+// lenient bool or with the `DeserializeBuf` trait
+fn de_buf_call(field: &Field, buf: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let buf = if field.lenient {
+        quote! {::xmlib::de::lenient_number(#buf)}
+    } else {
+        buf
+    };
+    if field.lenient_bool {
+        return quote! {::xmlib::de::de_bool_lenient(#buf)?};
+    }
+    match (&field.with, field.radix) {
+        (Some(with), _) => quote! {#with::de_buf(#buf)?},
+        (None, Some(radix)) => quote! {::xmlib::types::RadixInteger::de_radix(#buf, #radix)?},
+        (None, None) => quote! {::xmlib::de::DeserializeBuf::de_buf(#buf)?},
+    }
+}
+
 fn create_validation(
-    validation: &syn::Lit,
+    validation: &Validation,
     ident: &Ident,
     ty_name: &String,
 ) -> proc_macro2::TokenStream {
-    match validation {
-        syn::Lit::Str(lit) => {
-            let validation: proc_macro2::TokenStream =
-                syn::parse2(syn::parse_str(&lit.value()).unwrap()).unwrap();
-            quote! {
-                if let ::std::result::Result::Err(e) = #validation(&#ident) {
-                    return ::std::result::Result::Err(
-                        ::xmlib::de::Error {
-                            ty_name: ::std::string::String::from(#ty_name),
-                            kind: ::xmlib::de::ErrorKind::Validation(format!("{:?}", e)),
-                        }
-                    );
+    let func = &validation.func;
+    let kind = if validation.boxed {
+        quote! {::xmlib::de::ErrorKind::CustomValidation(::std::boxed::Box::new(e))}
+    } else {
+        quote! {::xmlib::de::ErrorKind::Validation(format!("{:?}", e))}
+    };
+    quote! {
+        if let ::std::result::Result::Err(e) = #func(&#ident) {
+            return ::std::result::Result::Err(
+                ::xmlib::de::Error {
+                    ty_name: ::std::string::String::from(#ty_name),
+                    kind: #kind,
                 }
-            }
+            );
         }
-        lit => error!(
-            validation.span(),
-            format!("expected literal string but got {}", lit.to_token_stream())
-        )
-        .into(),
+    }
+}
+
+// Creates the code which returns the deserialized value and calls the finalize function if needed
+fn create_finish(
+    finalize: Option<&syn::Expr>,
+    value: proc_macro2::TokenStream,
+    ty_name: &str,
+) -> proc_macro2::TokenStream {
+    match finalize {
+        None => quote! { ::std::result::Result::Ok(#value) },
+        Some(finalize) => quote! {
+            let mut res__ = #value;
+            if let ::std::result::Result::Err(e) = #finalize(&mut res__) {
+                return ::std::result::Result::Err(
+                    ::xmlib::de::Error {
+                        ty_name: ::std::string::String::from(#ty_name),
+                        kind: ::xmlib::de::ErrorKind::Validation(format!("{:?}", e)),
+                    }
+                );
+            }
+            ::std::result::Result::Ok(res__)
+        },
+    }
+}
+
+fn option_tokens(value: Option<usize>) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote! {::std::option::Option::Some(#value)},
+        None => quote! {::std::option::Option::None},
     }
 }
@@ -8,6 +8,11 @@
 //! #
 //! # fn main() {}
 //! ```
+//!
+//! If a type derives both, `#[derive(Xml)]` can be used instead. It accepts the same attributes and
+//! additionally `#[xmlib(ser_only)]` and `#[xmlib(de_only)]` on the type to create only one of the
+//! implementations.
+//!
 //! # Examples
 //! ## Simple Struct
 //! ```
@@ -113,38 +118,158 @@
 //! Only newtype structs (unnamed structs with exactly one field) are supported.
 //! They serialize/ deserialize only the inner value.
 //!
+//! If the newtype is annotated with `#[xmlib(list)]` the inner value must be a [`std::vec::Vec`]
+//! of elements. The elements will be serialized/ deserialized as children of an element with the
+//! name of the struct (renamed to lower camel case) or the name given with
+//! `#[xmlib(rename = "name")]`.
+//!
 //! See also [Validation](#validation)
 //!
 //! ## Named structs
+//! A struct with exactly one field annotated with `#[xmlib(transparent)]` is serialized/
+//! deserialized like a newtype as its inner value, e.g. `struct Meters { value: f64 }`.
+//!
 //! All attribute names will be renamed to lower camel case.
 //! Use `#[xmlib(rename = "name")]` to serialize and deserialize
 //! the field with the given name instead of the rust name.
+//! Different names for serialization and deserialization can be given with
+//! `#[xmlib(rename(serialize = "new", deserialize = "old"))]`, a missing name keeps the default.
+//! Two attributes or child elements with the same name after renaming are a compile error.
 //!
 //! Unless you attribute the struct with `#[xmlib(no_constructor)]` a public function
-//! `with_default` will be generated to instantiate the struct.
+//! `with_default` will be generated to instantiate the struct. It takes all fields without a
+//! default value as parameters in the order of their declaration.
+//!
+//! If every field has a default, `#[xmlib(derive_default)]` additionally implements [`Default`]
+//! with these values. Fields without a default are a compile error then. This can be combined
+//! with `no_constructor`.
+//!
+//! Annotate the struct with `#[xmlib(reflect)]` to implement `xmlib::meta::XmlSchema`, which
+//! describes the attributes, children and text of the element, e.g. for documentation tools. Like
+//! the constructor it is generated by `Serialize`. The types of `value` fields must implement
+//! `xmlib::de::XmlName` for their name, e.g. by deriving `Deserialize`.
 //!
 //! Each field will be an xml attribute by default, unless you specify it otherwise.
+//! `&`, `<` and the quote character of `xmlib::ser::QuoteStyle` are escaped in attribute values.
 //!
 //! If the field has it's default value it won't be serialized to shorten the text.
 //!
 //! If you want the field to be serialized and deserialized as a child instead of an attribute,
-//! consider annotating the field with `#[xmlib(value)]`.
+//! consider annotating the field with `#[xmlib(value)]`. If the type of such a field is
+//! [`Option`] it will be `None` when the child is missing and won't be serialized if it's `None`.
+//! Recursive types like trees can use `Option<Box<Self>>` or `Vec<Self>` for their children.
+//! Add `#[xmlib(nillable)]` to such a field or an optional `element` to write `None` as empty
+//! element with `xsi:nil="true"` instead, which is also read back as `None`. The nil element of a
+//! `value` field gets the name and namespace of the type from `xmlib::ser::Serialize::element_name`
+//! and `element_namespace`, so the type has to be a named struct or list.
 //!
 //! In addition to `value` you can annotate a field with `#[xmlib(multiple)]` to allow multiple
-//! children with the same name. Note that the type of the field must be [`std::vec::Vec`].
+//! children with the same name. The field can be any collection implementing
+//! `xmlib::de::CollectChild`, e.g. [`std::vec::Vec`] or [`std::collections::BTreeSet`].
+//! The number of children can be restricted with `#[xmlib(min = 1, max = 64)]`. A field of type
+//! `[T; N]` requires exactly `N` children instead.
+//! Use `#[xmlib(wrapper = "name")]` to wrap the children in an additional element.
+//!
+//! With `#[xmlib(value, multiple, key = "name")]` the children are collected into a map like
+//! `BTreeMap<String, Sheet>` instead, keyed by a clone of the field `name` of each child.
+//! Children with the same key are an error, which contains the debug representation of the key.
+//! Only the values of the map are serialized, in the iteration order of the map.
+//!
+//! Fields annotated with `#[xmlib(element)]` are serialized/ deserialized as child element
+//! containing only the value as text, e.g. `<width>42</width>`. Combined with
+//! `#[xmlib(multiple)]` each item of the [`std::vec::Vec`] is a separate child element, so you
+//! probably want to rename the field to the singular name.
+//!
+//! The presence of an empty child element like `<flag/>` can be modeled with a field of type
+//! `Option<()>` annotated with `#[xmlib(element)]`.
 //!
 //! `#[xmlib(value_buf)]` can be used to used to serialize/ deserialize the text content of the
-//! element.
+//! element. The text is escaped when serializing, add `#[xmlib(cdata)]` to write it as CDATA
+//! section instead.
+//! The field can have any type implementing `DeserializeBuf` and `Serialize`, e.g. enums and
+//! newtypes deriving them. Text consisting only of whitespace is treated as missing, so use
+//! `#[xmlib(default)]` if it may be empty.
+//! With `#[xmlib(value_buf, multiple)]` every run of text and every CDATA section becomes one
+//! item of a collection like `Vec<String>`, e.g. the text separated by comments. The items are
+//! separated by empty comments `<!---->` when serializing, so they are read back separately.
+//!
+//! Annotate an attribute with `#[xmlib(trim)]` to remove surrounding whitespace before
+//! deserializing, e.g. `visible=" true "`. Numbers of attributes, `value_buf` and elements
+//! annotated with `#[xmlib(lenient)]` may additionally start with `+`, e.g. `count=" +42"`, see
+//! `xmlib::de::lenient_number`.
+//!
+//! [`bool`] values are serialized as `1` and `0`. Annotate an attribute, `value_buf` or single
+//! `element` field with `#[xmlib(bool_format = "words")]` to write `true` and `false` instead.
+//! Both spellings are accepted when deserializing. `xmlib::ser::XmlWriter::with_bool_style`
+//! changes the default for all fields without `bool_format`. Add `#[xmlib(lenient_bool)]` to
+//! additionally accept `yes`/`no`, `y`/`n` and `on`/`off` in any case, see
+//! `xmlib::de::de_bool_lenient`.
+//!
+//! Integer attributes, `value_buf` and `element` fields are written in another radix with
+//! `#[xmlib(radix = 16)]`. The radix may be 2, 8 or 16, see `xmlib::types::RadixInteger`.
+//!
+//! Floats are written in the shortest representation which may use an exponent, e.g. `1e-7`.
+//! `#[xmlib(float_format(no_exponent))]` on attributes, `value_buf` and `element` fields writes
+//! them without exponent and `#[xmlib(float_format(precision = 2))]` additionally rounds them to
+//! at most two decimal places, see `xmlib::ser::FormatFloat`. Deserializing is not affected.
+//!
+//! Types without implementations of the traits, e.g. of other crates, can be handled with
+//! `#[xmlib(with = "module")]` on attributes, `value_buf` and `element` fields. The field is
+//! then deserialized with `module::de_buf(&[u8]) -> Result<T, xmlib::Error>` and serialized with
+//! `module::ser<W: Write>(&T, &mut XmlWriter<W>) -> Result<(), xmlib::ser::Error>`, where `T` is
+//! the type of the field or the item type for `multiple` elements.
+//!
+//! Types implementing [`Display`](std::fmt::Display) and [`FromStr`](std::str::FromStr), whose
+//! error implements `Debug`, can be annotated with `#[xmlib(display)]` instead of writing such a
+//! module, see `xmlib::types::display`. The output of `Display` is escaped.
+//! Scalar types implementing only the traits of serde, e.g. enums of other crates, can be
+//! annotated with `#[xmlib(serde)]` if the `serde` feature of xmlib is enabled, see
+//! `xmlib::compat::SerdeBuf`.
 //!
 //! `#[xmlib(collect_namespaces)]` can be used to serialize/ deserialize all `xmlns=""` attributes.
 //!
+//! Comments are skipped when deserializing. Annotate a field of type `Vec<String>` with
+//! `#[xmlib(collect_comments)]` to keep the comments which are direct children of the element.
+//! They are written back before the children of the element, so their exact position isn't
+//! preserved.
+//!
+//! Attributes without a matching field are an error when deserializing. Annotate one field of the
+//! type `Vec<(String, String)>`, `HashMap<String, String>` or `BTreeMap<String, String>` with
+//! `#[xmlib(collect_attrs)]` to collect them instead. They are written back after the other
+//! attributes in the iteration order of the collection, so a `Vec` keeps the document order and
+//! a `BTreeMap` sorts them by name. If an attribute appears multiple times, the last value is
+//! kept.
+//!
+//! Annotate the struct with `#[xmlib(namespace = "uri")]` to only match elements in the namespace
+//! `uri` when deserializing, e.g. `<a:rect xmlns:a="uri"/>`. The namespace is declared with
+//! `xmlns="uri"` when serializing. The same can be done for fields annotated with `element`.
+//! Without a namespace only the local name of the elements is compared.
+//!
+//! Unknown child elements without a namespace prefix cause an error when deserializing. Annotate
+//! the struct with `#[xmlib(ignore_unknown_children)]` to skip them instead, e.g. to read documents
+//! of newer versions. Unknown attributes and children with a prefix are skipped
+//! if the prefix is declared, otherwise deserializing fails with `ErrorKind::UnboundPrefix`.
+//!
+//! By default the deserialization code is generated for every reader type used. Annotate a struct
+//! or list with `#[xmlib(erased)]` to implement `DeserializeElement` only for
+//! `xmlib::de::ErasedReader`, which compiles the code only once. Such types are deserialized with
+//! `xmlib::de::from_reader_erased` and all parents containing them must be erased as well.
+//!
+//! Structs may have one single lifetime parameter. Attributes annotated with `#[xmlib(borrow)]`
+//! can then borrow from the input, e.g. `Cow<'a, str>` borrows if the value doesn't need to be
+//! unescaped and `&'a str` fails in this case. Such structs can only be deserialized from slices,
+//! e.g. with `xmlib::de::from_str_borrowed`.
+//!
 //! You can annotate a field with `#[xmlib(default)]` or `#[xmlib(default = value)]` to use
 //! [`Default::default()`] or `value` if the field is not present when deserializing.
+//! This works for attributes as well as for `value`, `element` and `value_buf` fields, e.g.
+//! `#[xmlib(value, default = "Inner::empty()")]` uses `Inner::empty()` if the child is missing.
 //!
 //! See also [Validation](#validation)
 //!
 //! # Enums
-//! Currently either all variants must have data or all mustn't have data.
+//! Either all variants must have data or all mustn't have data, unless the enum is
+//! [tagged](#tagged-enums).
 //!
 //! ## Enums without Data:
 //! All variants will be renamed to lower camel case.
@@ -154,10 +279,93 @@
 //! ## Enums with data
 //! When deserializing the first successfull variant will be chosen.
 //!
+//! ## Tagged enums
+//! Enums annotated with `#[xmlib(tagged)]` are serialized as element with the (renamed) name of
+//! the variant. Unit variants are empty elements like `<empty/>` and variants with data contain
+//! their value as escaped text like `<circle>3</circle>`, so they can be mixed. When deserializing
+//! the variant is chosen by the name of the element, e.g. for fields annotated with
+//! `#[xmlib(value)]`. This can be used to model `<xs:choice>`.
+//!
+//! Variants annotated with `#[xmlib(value)]` contain an element like a derived named struct
+//! instead of text. It is written as it is and chosen by its own name and namespace.
+//!
+//! ## Enums with a discriminant attribute
+//! Elements with the same name can be distinguished by an attribute like
+//! `<node type="circle" r="1"/>` with `#[xmlib(tag_attr = "type")]`. Each variant contains a
+//! struct for the element and the attribute contains the (renamed) name of the variant. It is
+//! removed before deserializing the struct and written as first attribute when serializing, so
+//! the type of each variant has to implement `xmlib::ser::SerializeWithAttr` like derived named
+//! structs.
+//!
+//! ```
+//! # use xmlib_derive::{Serialize, Deserialize};
+//! #[derive(Serialize, Deserialize)]
+//! #[xmlib(rename = "node")]
+//! struct Circle {
+//!     r: u32,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! #[xmlib(rename = "node")]
+//! struct Rect {
+//!     w: u32,
+//!     h: u32,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! #[xmlib(tag_attr = "type")]
+//! enum Shape {
+//!     Circle(Circle),
+//!     Rect(Rect),
+//! }
+//! ```
+//!
+//! ## Mixed content
+//! Text interleaved with child elements, e.g. `<p>Hello <b>world</b>!</p>`, can be represented
+//! by an enum annotated with `#[xmlib(mixed)]`. One variant must be annotated with
+//! `#[xmlib(text)]` and contains the text, all other variants contain one of the child elements.
+//! A struct field of type `Vec<Enum>` annotated with `#[xmlib(mixed)]` then contains the text and
+//! the children in document order. The text is escaped when serializing.
+//!
+//! ```
+//! # use xmlib_derive::{Serialize, Deserialize};
+//! #[derive(Serialize, Deserialize)]
+//! #[xmlib(rename = "b")]
+//! struct Bold {
+//!     #[xmlib(value_buf)]
+//!     text: String,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! #[xmlib(mixed)]
+//! enum Node {
+//!     #[xmlib(text)]
+//!     Text(String),
+//!     Bold(Bold),
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! #[xmlib(rename = "p")]
+//! struct Paragraph {
+//!     #[xmlib(mixed)]
+//!     content: Vec<Node>,
+//! }
+//! ```
+//!
 //! # Validation
 //! You can annotate struct fields with `#[xmlib(validate = "fn_name")]` to cause an error in the
-//! deserialization. The function must take one single shared reference to the type of the field as
-//! the argument and return `Result<(), Error>` where Error is any type implementing debug.
+//! serialization and deserialization. The function must take one single shared reference to the
+//! type of the field as the argument and return `Result<(), Error>` where Error is any type
+//! implementing debug.
+//!
+//! Use `#[xmlib(validate_boxed = "fn_name")]` instead if the error type implements
+//! [`std::error::Error`] and should be kept when deserializing. The error is boxed into
+//! `ErrorKind::CustomValidation` and can be retrieved with `downcast_ref`.
+//!
+//! Structs can be annotated with `#[xmlib(finalize = "fn_name")]` to check invariants spanning
+//! multiple fields or to adjust the value after deserialization. The function must take one single
+//! mutable reference to the struct and return `Result<(), Error>` where Error is any type
+//! implementing debug. It is called after the validation of all fields.
 use proc_macro::TokenStream;
 
 macro_rules! error {
@@ -170,27 +378,54 @@ macro_rules! error {
 }
 
 mod de;
+mod meta;
 mod parse;
 mod ser;
 
 /// Creates an implementation of [`xmlib::ser::Serialize`](../xmlib/ser/trait.Serialize.html).
 ///
+/// Named structs and lists also return their name and namespace from `element_name` and
+/// `element_namespace`.
+///
 /// See the [crate documentation][crate] for more details.
 #[proc_macro_derive(Serialize, attributes(xmlib))]
 pub fn expand_ser(input: TokenStream) -> TokenStream {
-    match parse::parse_input(input) {
+    match parse::parse_input(input, false) {
         Ok(input) => ser::expand(input),
         Err(e) => e,
     }
 }
 
-/// Creates an implementation of [`xmlib::de::DeserializeElement`](../xmlib/de/trait.DeserializeElement.html) for named structs
-/// and an implementation of [`xmlib::de::DeserializeBuf`](../xmlib/de/trait.DeserializeBuf.html) for enums and unnamed structs.
+/// Creates the implementations of both [`Serialize`](derive@Serialize) and
+/// [`Deserialize`](derive@Deserialize) while parsing the input only once.
+///
+/// Use `#[xmlib(ser_only)]` or `#[xmlib(de_only)]` on the type to create only one of them.
+///
+/// See the [crate documentation][crate] for more details.
+#[proc_macro_derive(Xml, attributes(xmlib))]
+pub fn expand_xml(input: TokenStream) -> TokenStream {
+    let input = match parse::parse_input(input, true) {
+        Ok(input) => input,
+        Err(e) => return e,
+    };
+    let mut res = TokenStream::new();
+    if !input.de_only {
+        res.extend(ser::expand(input.clone()));
+    }
+    if !input.ser_only {
+        res.extend(de::expand(input));
+    }
+    res
+}
+
+/// Creates an implementation of [`xmlib::de::DeserializeElement`](../xmlib/de/trait.DeserializeElement.html)
+/// and [`xmlib::de::XmlName`](../xmlib/de/trait.XmlName.html) for named structs
+/// and lists and an implementation of [`xmlib::de::DeserializeBuf`](../xmlib/de/trait.DeserializeBuf.html) for enums and unnamed structs.
 ///
 /// See the [crate documentation][crate] for more details.
 #[proc_macro_derive(Deserialize, attributes(xmlib))]
 pub fn expand_de(input: TokenStream) -> TokenStream {
-    match parse::parse_input(input) {
+    match parse::parse_input(input, false) {
         Ok(input) => de::expand(input),
         Err(e) => e,
     }
@@ -0,0 +1,146 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::Ident;
+
+use crate::parse::{option_item_ty, Field, NamedStruct};
+
+// Implements `xmlib::meta::XmlSchema` with the structure of the parsed struct
+pub(crate) fn expand_named_struct(
+    s: &NamedStruct,
+    ident: &Ident,
+    lifetime: Option<&syn::Lifetime>,
+) -> TokenStream2 {
+    let name = proc_macro2::Literal::byte_string(s.raw_ser_name.as_bytes());
+    let namespace = option_bytes(s.namespace.as_ref());
+
+    let attributes = s.ty_attribute.iter().map(|field| {
+        let name = proc_macro2::Literal::byte_string(field.ser_name.as_bytes());
+        let ty = type_name(value_ty(&field.ty));
+        let optional = field.default.is_some();
+        let default = option_str(field.default_text.as_ref());
+        quote! {
+            ::xmlib::meta::AttributeSchema {
+                name: #name,
+                ty: #ty,
+                optional: #optional,
+                default: #default,
+            }
+        }
+    });
+
+    let children = s.ty_value.iter().map(|field| {
+        let ty = if field.has_multiple {
+            field.item_ty()
+        } else {
+            value_ty(&field.ty)
+        };
+        let name = if field.element {
+            proc_macro2::Literal::byte_string(field.ser_name.as_bytes()).into_token_stream()
+        } else {
+            // the name of `value` fields is only known from the type
+            quote! {<#ty as ::xmlib::de::XmlName>::NAME}
+        };
+        let namespace = option_bytes(field.namespace.as_ref());
+        let type_name = type_name(ty);
+        let text_only = field.element;
+        let (min, max) = occurrences(field);
+        let wrapper = option_bytes(field.wrapper.as_ref());
+        let default = option_str(field.default_text.as_ref());
+        quote! {
+            ::xmlib::meta::ChildSchema {
+                name: #name,
+                namespace: #namespace,
+                ty: #type_name,
+                text_only: #text_only,
+                min_occurs: #min,
+                max_occurs: #max,
+                wrapper: #wrapper,
+                default: #default,
+            }
+        }
+    });
+
+    let text = match &s.ty_value_buf {
+        Some(field) => {
+            let ty = if field.has_multiple {
+                field.item_ty()
+            } else {
+                value_ty(&field.ty)
+            };
+            let ty = type_name(ty);
+            quote! {::std::option::Option::Some(#ty)}
+        }
+        None => quote! {::std::option::Option::None},
+    };
+    let mixed = s.ty_mixed.is_some();
+
+    let lifetime = lifetime.map(|lifetime| quote! {<#lifetime>});
+    quote! {
+        #[automatically_derived]
+        impl #lifetime ::xmlib::meta::XmlSchema for #ident #lifetime {
+            const SCHEMA: ::xmlib::meta::ElementSchema = ::xmlib::meta::ElementSchema {
+                name: #name,
+                namespace: #namespace,
+                attributes: &[#(#attributes,)*],
+                children: &[#(#children,)*],
+                text: #text,
+                mixed: #mixed,
+            };
+        }
+    }
+}
+
+// Returns the minimal and maximal number of children of the field
+fn occurrences(field: &Field) -> (TokenStream2, TokenStream2) {
+    if let Some(len) = &field.array_len {
+        (quote! {#len}, quote! {::std::option::Option::Some(#len)})
+    } else if field.has_multiple {
+        let min = field.min.unwrap_or(0);
+        let max = match field.max {
+            Some(max) => quote! {::std::option::Option::Some(#max)},
+            None => quote! {::std::option::Option::None},
+        };
+        (quote! {#min}, max)
+    } else {
+        let min = usize::from(!field.optional && field.default.is_none());
+        (quote! {#min}, quote! {::std::option::Option::Some(1)})
+    }
+}
+
+// `Option<T>` only says that the value may be missing, so the type of the value is `T`
+fn value_ty(ty: &syn::Type) -> &syn::Type {
+    option_item_ty(ty).unwrap_or(ty)
+}
+
+// Writes the type without the spaces between the tokens, e.g. `Vec<String>`
+fn type_name(ty: &syn::Type) -> String {
+    let tokens = ty.to_token_stream().to_string();
+    let is_word = |c: Option<char>| c.map_or(false, |c| c.is_alphanumeric() || c == '_');
+    let mut name = String::with_capacity(tokens.len());
+    let mut chars = tokens.chars().peekable();
+    while let Some(c) = chars.next() {
+        // spaces are only needed between words like in `dyn Trait`
+        if c == ' ' && !(is_word(name.chars().last()) && is_word(chars.peek().copied())) {
+            continue;
+        }
+        name.push(c);
+    }
+    name
+}
+
+fn option_bytes(value: Option<&String>) -> TokenStream2 {
+    match value {
+        Some(value) => {
+            let value = proc_macro2::Literal::byte_string(value.as_bytes());
+            quote! {::std::option::Option::Some(#value)}
+        }
+        None => quote! {::std::option::Option::None},
+    }
+}
+
+fn option_str(value: Option<&String>) -> TokenStream2 {
+    match value {
+        Some(value) => quote! {::std::option::Option::Some(#value)},
+        None => quote! {::std::option::Option::None},
+    }
+}
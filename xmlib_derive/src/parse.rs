@@ -1,8 +1,62 @@
 use heck::ToLowerCamelCase;
 use proc_macro::TokenStream;
-use syn::{spanned::Spanned, Data, Fields, Ident};
+use quote::{quote, ToTokens};
+use syn::{ext::IdentExt, spanned::Spanned, Data, Fields, Ident};
 
-pub(crate) fn parse_input(input: TokenStream) -> Result<Input, TokenStream> {
+// known keys of `#[xmlib(...)]` at the different positions
+const NAMED_STRUCT_KEYS: &[&str] = &[
+    "rename",
+    "no_constructor",
+    "derive_default",
+    "reflect",
+    "finalize",
+    "namespace",
+    "ignore_unknown_children",
+    "erased",
+    "ser_only",
+    "de_only",
+    "transparent",
+];
+const UNNAMED_STRUCT_KEYS: &[&str] = &[
+    "list", "rename", "finalize", "erased", "ser_only", "de_only",
+];
+const ENUM_KEYS: &[&str] = &["mixed", "tagged", "tag_attr", "ser_only", "de_only"];
+const NAMED_FIELD_KEYS: &[&str] = &[
+    "rename",
+    "default",
+    "value",
+    "value_buf",
+    "collect_namespaces",
+    "collect_attrs",
+    "collect_comments",
+    "element",
+    "multiple",
+    "wrapper",
+    "min",
+    "max",
+    "cdata",
+    "validate",
+    "validate_boxed",
+    "borrow",
+    "namespace",
+    "bool_format",
+    "mixed",
+    "with",
+    "nillable",
+    "radix",
+    "lenient_bool",
+    "float_format",
+    "trim",
+    "lenient",
+    "key",
+    "display",
+    "serde",
+];
+const UNNAMED_FIELD_KEYS: &[&str] = &["validate", "validate_boxed"];
+const VARIANT_KEYS: &[&str] = &["rename", "text", "value"];
+
+// `combined` is set for `derive(Xml)`, the only derive which accepts `ser_only` and `de_only`
+pub(crate) fn parse_input(input: TokenStream, combined: bool) -> Result<Input, TokenStream> {
     let input = match syn::parse_macro_input::parse::<syn::DeriveInput>(input) {
         Ok(data) => data,
         Err(err) => {
@@ -10,23 +64,187 @@ pub(crate) fn parse_input(input: TokenStream) -> Result<Input, TokenStream> {
         }
     };
 
+    check_attrs(
+        &input.attrs,
+        match &input.data {
+            Data::Struct(syn::DataStruct {
+                fields: Fields::Unnamed(_),
+                ..
+            }) => UNNAMED_STRUCT_KEYS,
+            Data::Enum(_) => ENUM_KEYS,
+            _ => NAMED_STRUCT_KEYS,
+        },
+    )?;
+
+    let lifetime = get_lifetime(&input.generics)?;
+
+    let mut only = [false; 2];
+    for (only, name) in only.iter_mut().zip(["ser_only", "de_only"]) {
+        *only = match get_attr(&input.attrs, name)? {
+            AttrResult::Existing => true,
+            AttrResult::NotFound => false,
+            _ => return Err(error!(input.span(), format!("expected \"{}\"", name))),
+        };
+    }
+    let [ser_only, de_only] = only;
+    if !combined && (ser_only || de_only) {
+        return Err(error!(
+            input.span(),
+            "\"ser_only\" and \"de_only\" can only be used with `derive(Xml)`"
+        ));
+    }
+    if ser_only && de_only {
+        return Err(error!(
+            input.span(),
+            "\"ser_only\" and \"de_only\" can not be combined"
+        ));
+    }
+
+    let erased = match get_attr(&input.attrs, "erased")? {
+        AttrResult::Existing => {
+            if lifetime.is_some() {
+                error!(ret: input.generics.span(), "erased can not be combined with a lifetime");
+            }
+            if get_attr(&input.attrs, "list")? == AttrResult::NotFound
+                && !matches!(
+                    input.data,
+                    Data::Struct(syn::DataStruct {
+                        fields: Fields::Named(_),
+                        ..
+                    })
+                )
+            {
+                error!(ret: input.span(), "erased can only used with named structs and lists");
+            }
+            true
+        }
+        AttrResult::NotFound => false,
+        _ => return Err(error!(input.span(), "expected \"erased\"")),
+    };
+
     let data = match input.data {
         Data::Struct(ref s) => match &s.fields {
+            Fields::Named(fields)
+                if get_attr(&input.attrs, "transparent")? != AttrResult::NotFound =>
+            {
+                if get_attr(&input.attrs, "transparent")? != AttrResult::Existing {
+                    return Err(error!(input.span(), "expected \"transparent\""));
+                }
+                for key in [
+                    "rename",
+                    "no_constructor",
+                    "derive_default",
+                    "reflect",
+                    "namespace",
+                    "ignore_unknown_children",
+                    "erased",
+                ] {
+                    if get_attr(&input.attrs, key)? != AttrResult::NotFound {
+                        error!(ret: input.span(), format!("\"transparent\" can not be combined with \"{}\"", key));
+                    }
+                }
+                if lifetime.is_some() {
+                    error!(ret: input.generics.span(), "\"transparent\" can not be combined with a lifetime");
+                }
+                if fields.named.len() != 1 {
+                    error!(ret: fields.span(), "transparent structs must have exactly one field");
+                }
+                let field = &fields.named[0];
+                InputData::UnnamedStruct(UnnamedStruct::parse(
+                    field,
+                    syn::Member::Named(field.ident.clone().unwrap()),
+                    None,
+                    get_finalize(&input)?,
+                )?)
+            }
             Fields::Named(fields) => {
-                let raw_ser_name = match get_attr(&input.attrs, "rename")? {
-                    AttrResult::Lit(lit) => get_literal_str(lit)?,
-                    AttrResult::NotFound => input.ident.to_string().to_lower_camel_case(),
-                    _ => return Err(error!(input.span(), "expected one single literal str")),
-                };
+                let raw_ser_name = get_raw_ser_name(&input)?;
 
                 let no_constructor = match get_attr(&input.attrs, "no_constructor")? {
                     AttrResult::Existing => true,
                     AttrResult::NotFound => false,
                     _ => return Err(error!(input.span(), "expected \"no_constructor\"")),
                 };
-                InputData::NamedStruct(NamedStruct::parse(fields, no_constructor, raw_ser_name)?)
+                let derive_default = match get_attr(&input.attrs, "derive_default")? {
+                    AttrResult::Existing => true,
+                    AttrResult::NotFound => false,
+                    _ => return Err(error!(input.span(), "expected \"derive_default\"")),
+                };
+                let reflect = match get_attr(&input.attrs, "reflect")? {
+                    AttrResult::Existing => true,
+                    AttrResult::NotFound => false,
+                    _ => return Err(error!(input.span(), "expected \"reflect\"")),
+                };
+                let namespace = match get_attr(&input.attrs, "namespace")? {
+                    AttrResult::Lit(lit) => Some(get_literal_str(lit)?),
+                    AttrResult::NotFound => None,
+                    _ => {
+                        return Err(error!(
+                            input.span(),
+                            "expected one single literal str for namespace"
+                        ))
+                    }
+                };
+                let ignore_unknown_children =
+                    match get_attr(&input.attrs, "ignore_unknown_children")? {
+                        AttrResult::Existing => true,
+                        AttrResult::NotFound => false,
+                        _ => {
+                            return Err(error!(
+                                input.span(),
+                                "expected \"ignore_unknown_children\""
+                            ))
+                        }
+                    };
+                let mut s = NamedStruct::parse(
+                    fields,
+                    no_constructor,
+                    raw_ser_name,
+                    namespace,
+                    ignore_unknown_children,
+                    get_finalize(&input)?,
+                    lifetime.is_some(),
+                )?;
+                if derive_default {
+                    let required = s
+                        .ty_attribute
+                        .iter()
+                        .chain(&s.ty_value)
+                        .chain(&s.ty_value_buf)
+                        .find(|field| field.default.is_none() && !field.optional);
+                    if let Some(field) = required {
+                        error!(ret: field.ident.span(), format!(
+                            "derive_default requires a default for every field but \"{}\" has none",
+                            field.ident
+                        ));
+                    }
+                }
+                s.derive_default = derive_default;
+                s.reflect = reflect;
+                InputData::NamedStruct(s)
+            }
+            _ if lifetime.is_some() => {
+                return Err(error!(
+                    input.generics.span(),
+                    "lifetimes are only supported for structs with named fields",
+                ))
+            }
+            Fields::Unnamed(fields) => {
+                let list = match get_attr(&input.attrs, "list")? {
+                    AttrResult::Existing => Some(get_raw_ser_name(&input)?),
+                    AttrResult::NotFound => None,
+                    _ => return Err(error!(input.span(), "expected \"list\"")),
+                };
+                if fields.unnamed.len() != 1 {
+                    error!(ret: fields.span(), "only unnamed structs with one field are supported");
+                }
+                InputData::UnnamedStruct(UnnamedStruct::parse(
+                    &fields.unnamed[0],
+                    syn::Member::Unnamed(0.into()),
+                    list,
+                    get_finalize(&input)?,
+                )?)
             }
-            Fields::Unnamed(fields) => InputData::UnnamedStruct(UnnamedStruct::parse(fields)?),
             Fields::Unit => {
                 return Err(error!(
                     input.span(),
@@ -34,7 +252,48 @@ pub(crate) fn parse_input(input: TokenStream) -> Result<Input, TokenStream> {
                 ))
             }
         },
-        Data::Enum(ref e) => InputData::Enum(Enum::parse(e)?),
+        Data::Enum(_) if lifetime.is_some() => {
+            return Err(error!(
+                input.generics.span(),
+                "lifetimes are only supported for structs with named fields",
+            ))
+        }
+        Data::Enum(ref e) => {
+            let mixed = match get_attr(&input.attrs, "mixed")? {
+                AttrResult::Existing => true,
+                AttrResult::NotFound => false,
+                _ => return Err(error!(input.span(), "expected \"mixed\"")),
+            };
+            let tagged = match get_attr(&input.attrs, "tagged")? {
+                AttrResult::Existing => true,
+                AttrResult::NotFound => false,
+                _ => return Err(error!(input.span(), "expected \"tagged\"")),
+            };
+            if mixed && tagged {
+                return Err(error!(
+                    input.span(),
+                    "\"mixed\" and \"tagged\" can not be combined"
+                ));
+            }
+            let tag_attr = match get_attr(&input.attrs, "tag_attr")? {
+                AttrResult::Lit(lit) => {
+                    if mixed || tagged {
+                        error!(ret: input.span(),
+                            "\"tag_attr\" can not be combined with \"mixed\" or \"tagged\""
+                        );
+                    }
+                    Some(get_literal_str(lit)?)
+                }
+                AttrResult::NotFound => None,
+                _ => {
+                    return Err(error!(
+                        input.span(),
+                        "expected one single literal str for tag_attr"
+                    ))
+                }
+            };
+            InputData::Enum(Enum::parse(e, mixed, tagged, tag_attr)?)
+        }
         _ => {
             return Err(error!(
                 input.span(),
@@ -46,58 +305,93 @@ pub(crate) fn parse_input(input: TokenStream) -> Result<Input, TokenStream> {
     Ok(Input {
         data,
         ident: input.ident,
+        lifetime,
+        erased,
+        ser_only,
+        de_only,
     })
 }
 
+#[derive(Clone)]
 pub(crate) struct Input {
     pub(crate) ident: syn::Ident,
     pub(crate) data: InputData,
+    // the lifetime of the borrowed data
+    pub(crate) lifetime: Option<syn::Lifetime>,
+    // deserialize from `ErasedReader` only instead of any reader
+    pub(crate) erased: bool,
+    // opt-outs of `#[derive(Xml)]`
+    pub(crate) ser_only: bool,
+    pub(crate) de_only: bool,
 }
 
+// only one instance exists per derive, so the size doesn't matter
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
 pub(crate) enum InputData {
     Enum(Enum),
     NamedStruct(NamedStruct),
     UnnamedStruct(UnnamedStruct),
 }
 
+#[derive(Clone)]
 pub(crate) struct Enum {
     pub(crate) has_data: bool,
-    pub(crate) variants: Vec<(Ident, proc_macro2::Literal, Option<syn::Type>)>,
+    pub(crate) variants: Vec<(Ident, String, Option<syn::Type>)>,
+    // the variant containing the text of mixed content, only set for mixed enums
+    pub(crate) text_variant: Option<Ident>,
+    // each variant is an element with the name of the variant
+    pub(crate) tagged: bool,
+    // variants of tagged enums whose data is the element itself instead of its text
+    pub(crate) value_variants: Vec<Ident>,
+    // the attribute containing the name of the variant, whose data is the element
+    pub(crate) tag_attr: Option<String>,
 }
 
 impl Enum {
-    fn parse(input: &syn::DataEnum) -> Result<Self, TokenStream> {
+    fn parse(
+        input: &syn::DataEnum,
+        mixed: bool,
+        tagged: bool,
+        tag_attr: Option<String>,
+    ) -> Result<Self, TokenStream> {
         // is not decided yet
         let mut has_data = None;
+        let mut text_variant = None;
+        let mut value_variants = Vec::new();
 
         let variants: Vec<_> = input
             .variants
             .iter()
             .map(|variant| {
+                check_attrs(&variant.attrs, VARIANT_KEYS)?;
                 let name = match get_attr(&variant.attrs, "rename")? {
                     AttrResult::Lit(lit) => {
-                        if has_data == Some(true) {
+                        if has_data == Some(true) && !tagged && tag_attr.is_none() {
                             error!(ret: variant.span(), "rename has no effect for enums with data");
                         }
                         get_literal_str(lit)?
                     }
-                    AttrResult::NotFound => variant.ident.to_string().to_lower_camel_case(),
+                    AttrResult::NotFound => variant.ident.unraw().to_string().to_lower_camel_case(),
                     _ => error!(ret: variant.span(), "expected one single literal str"),
                 };
 
                 let ty = match &variant.fields {
                     Fields::Unit => {
-                        if has_data == Some(true) {
+                        if tag_attr.is_some() {
+                            error!(ret: variant.span(), "variants of enums with tag_attr must have data");
+                        }
+                        if has_data == Some(true) && !tagged {
                             error!(ret: variant.span(),
-                            "enums can be either with or without data but not both.");
+                            "enums can be either with or without data but not both. Use `#[xmlib(tagged)]` to allow both.");
                         }
-                        has_data = Some(false);
+                        has_data = has_data.or(Some(false));
                         None
                     }
                     Fields::Unnamed(fields) => {
-                        if has_data == Some(false) {
+                        if has_data == Some(false) && !tagged {
                             error!(ret: variant.span(),
-                            "enums can be either with or without values but not both.");
+                            "enums can be either with or without values but not both. Use `#[xmlib(tagged)]` to allow both.");
                         }
                         has_data = Some(true);
                         if fields.unnamed.len() != 1 {
@@ -117,35 +411,141 @@ impl Enum {
                 };
 
                 let ident = variant.ident.clone();
-                let name = proc_macro2::Literal::byte_string(name.as_bytes());
-
+                match get_attr(&variant.attrs, "text")? {
+                    AttrResult::Existing => {
+                        if !mixed {
+                            error!(ret: variant.span(), "text can only used in mixed enums");
+                        }
+                        if ty.is_none() {
+                            error!(ret: variant.span(), "the text variant must have data");
+                        }
+                        if text_variant.is_some() {
+                            error!(ret: variant.span(), "only one variant may be annotated with \"text\"");
+                        }
+                        text_variant = Some(ident.clone());
+                    }
+                    AttrResult::NotFound => {}
+                    _ => error!(ret: variant.span(), "expected \"text\""),
+                }
+                match get_attr(&variant.attrs, "value")? {
+                    AttrResult::Existing => {
+                        if !tagged {
+                            error!(ret: variant.span(), "value can only be used in tagged enums");
+                        }
+                        if ty.is_none() {
+                            error!(ret: variant.span(), "the value variant must have data");
+                        }
+                        value_variants.push(ident.clone());
+                    }
+                    AttrResult::NotFound => {}
+                    _ => error!(ret: variant.span(), "expected \"value\""),
+                }
                 Ok((ident, name, ty))
             })
             .collect::<Result<_, _>>()?;
 
+        let has_data = match has_data {
+            Some(has_data) => has_data,
+            None => error!(ret: input.enum_token.span, "enums without variants are not supported"),
+        };
+
+        if mixed && text_variant.is_none() {
+            error!(ret: input.enum_token.span,
+                "mixed enums need one variant annotated with \"text\""
+            );
+        }
+
         Ok(Self {
-            has_data: has_data.unwrap(),
+            has_data,
             variants,
+            text_variant,
+            tagged,
+            value_variants,
+            tag_attr,
         })
     }
 }
 
+#[derive(Clone)]
 pub(crate) struct Field {
     pub(crate) ident: syn::Ident,
-    pub(crate) name: String,
-    pub(crate) default: Option<syn::Lit>,
+    // the names only differ with `rename(serialize = "...", deserialize = "...")`
+    pub(crate) ser_name: String,
+    pub(crate) de_name: String,
+    pub(crate) default: Option<proc_macro2::TokenStream>,
+    // the xml text of literal defaults like `42`, only used for the schema
+    pub(crate) default_text: Option<String>,
     pub(crate) ty: syn::Type,
     pub(crate) has_multiple: bool,
-    pub(crate) validation: Option<syn::Lit>,
+    pub(crate) optional: bool,
+    pub(crate) element: bool,
+    pub(crate) wrapper: Option<String>,
+    pub(crate) cdata: bool,
+    pub(crate) min: Option<usize>,
+    pub(crate) max: Option<usize>,
+    pub(crate) validation: Option<Validation>,
+    pub(crate) borrow: bool,
+    pub(crate) namespace: Option<String>,
+    // serialize a `bool` as `true`/`false` or `1`/`0` instead of the style of the writer
+    pub(crate) bool_words: Option<bool>,
+    // module with `de_buf` and `ser` functions used instead of the traits
+    pub(crate) with: Option<syn::Path>,
+    // integers are written in this radix instead of 10
+    pub(crate) radix: Option<u32>,
+    // accept spellings like `yes` and `off` for a `bool`
+    pub(crate) lenient_bool: bool,
+    // `None` is written as element with `xsi:nil="true"` instead of being omitted
+    pub(crate) nillable: bool,
+    // floats are written without exponent, rounded to the precision if there is one
+    pub(crate) float_format: Option<Option<usize>>,
+    // surrounding whitespace of the attribute value is removed before deserializing
+    pub(crate) trim: bool,
+    // numbers may have surrounding whitespace and a leading `+`
+    pub(crate) lenient: bool,
+    // `multiple` children are collected into a map keyed by this field of the children
+    pub(crate) key: Option<Ident>,
+    // `multiple` children are collected into an array of this length
+    pub(crate) array_len: Option<syn::Expr>,
+}
+
+impl Field {
+    // The type of a single child of `multiple` fields, otherwise the type of the field
+    pub(crate) fn item_ty(&self) -> &syn::Type {
+        if !self.has_multiple {
+            &self.ty
+        } else if self.key.is_some() {
+            map_value_ty(&self.ty).unwrap()
+        } else if self.array_len.is_some() {
+            array_item_ty(&self.ty).unwrap()
+        } else {
+            collection_item_ty(&self.ty).unwrap()
+        }
+    }
 }
 
+#[derive(Clone)]
 pub(crate) struct NamedStruct {
     pub(crate) no_constructor: bool,
+    // implement `Default`, only set if all fields have a default
+    pub(crate) derive_default: bool,
+    // implement `xmlib::meta::XmlSchema`
+    pub(crate) reflect: bool,
+    pub(crate) finalize: Option<syn::Expr>,
     pub(crate) raw_ser_name: String,
+    pub(crate) namespace: Option<String>,
+    // skip unknown child elements instead of returning an error
+    pub(crate) ignore_unknown_children: bool,
     pub(crate) ty_attribute: Vec<Field>,
     pub(crate) ty_value: Vec<Field>,
     pub(crate) ty_value_buf: Option<Field>,
+    pub(crate) ty_mixed: Option<Field>,
     pub(crate) ty_collect_namespaces: Option<Ident>,
+    // field receiving all attributes without a matching field
+    pub(crate) ty_collect_attrs: Option<(Field, AttrCollection)>,
+    // field receiving the comments of the element
+    pub(crate) ty_collect_comments: Option<Ident>,
+    // all fields in declaration order
+    pub(crate) field_order: Vec<Ident>,
 }
 
 impl NamedStruct {
@@ -153,39 +553,67 @@ impl NamedStruct {
         fields: &syn::FieldsNamed,
         no_constructor: bool,
         raw_ser_name: String,
+        namespace: Option<String>,
+        ignore_unknown_children: bool,
+        finalize: Option<syn::Expr>,
+        has_lifetime: bool,
     ) -> Result<Self, TokenStream> {
         let mut ty_attribute = Vec::new();
         let mut ty_value = Vec::new();
         let mut ty_value_buf = None;
+        let mut ty_mixed = None;
         let mut ty_collect_namespaces = None;
+        let mut ty_collect_attrs = None;
+        let mut ty_collect_comments = None;
 
         for field in &fields.named {
-            let name = match get_attr(&field.attrs, "rename")? {
-                AttrResult::Lit(lit) => get_literal_str(lit)?,
-                AttrResult::NotFound => field
+            check_attrs(&field.attrs, NAMED_FIELD_KEYS)?;
+            // raw identifiers like `r#type` are named without the prefix
+            let default_name = || {
+                field
                     .ident
                     .as_ref()
                     .unwrap()
+                    .unraw()
                     .to_string()
-                    .to_lower_camel_case(),
+                    .to_lower_camel_case()
+            };
+            let (ser_name, de_name) = match get_attr(&field.attrs, "rename")? {
+                AttrResult::Lit(lit) => {
+                    let name = get_literal_str(lit)?;
+                    (name.clone(), name)
+                }
+                AttrResult::List(list) => {
+                    let (ser_name, de_name) = get_asymmetric_rename(&list)?;
+                    (
+                        ser_name.unwrap_or_else(default_name),
+                        de_name.unwrap_or_else(default_name),
+                    )
+                }
+                AttrResult::NotFound => (default_name(), default_name()),
                 _ => error!(ret: field.span(), "expected one single literal str"),
             };
 
-            let default_lit = syn::Lit::Str(syn::LitStr::new(
-                "::std::default::Default::default()",
-                field.span(),
-            ));
-
             let val_ty = get_val_ty(field)?;
 
-            let default = match get_attr(&field.attrs, "default")? {
+            let default_attr = get_attr(&field.attrs, "default")?;
+            let default_text = match &default_attr {
+                AttrResult::Lit(default) => get_default_text(default, &field.ty),
+                _ => None,
+            };
+            let default = match default_attr {
                 AttrResult::Lit(default) => {
-                    if val_ty == ValueTy::CollectNamespaces {
+                    if matches!(
+                        val_ty,
+                        ValueTy::CollectNamespaces
+                            | ValueTy::CollectAttrs
+                            | ValueTy::CollectComments
+                    ) {
                         error!(ret: field.span(),
-                            "\"default\" can't be combined with \"collect_namespaces\"",
+                            "\"default\" can't be combined with \"collect_namespaces\", \"collect_attrs\" or \"collect_comments\"",
                         );
                     } else {
-                        Some(default)
+                        Some(get_default_expr(default, &field.ty)?)
                     }
                 }
                 AttrResult::NotFound => None,
@@ -194,13 +622,41 @@ impl NamedStruct {
                         "multiple attribute values found for \"default\"",
                     );
                 }
-                AttrResult::Existing => Some(default_lit),
+                AttrResult::Existing => Some(quote! {::std::default::Default::default()}),
+                AttrResult::List(_) => {
+                    error!(ret: field.span(), "expected `default` or `default = value`")
+                }
+            };
+
+            let key = match get_attr(&field.attrs, "key")? {
+                AttrResult::Lit(syn::Lit::Str(lit)) => match lit.parse::<Ident>() {
+                    Ok(key) => Some(key),
+                    Err(e) => error!(ret: lit.span(), format!("could not parse key: {}", e)),
+                },
+                AttrResult::NotFound => None,
+                _ => error!(ret: field.span(), "expected one single literal str for key"),
             };
 
             let has_multiple = match get_attr(&field.attrs, "multiple")? {
                 AttrResult::Existing => {
-                    if val_ty != ValueTy::Value {
-                        error!(ret: fields.span(), "multiple can only used with value");
+                    if !matches!(
+                        val_ty,
+                        ValueTy::Value | ValueTy::Element | ValueTy::ValueBuf
+                    ) {
+                        error!(ret: fields.span(), "multiple can only used with value, element or value_buf");
+                    }
+                    if key.is_some() {
+                        if map_value_ty(&field.ty).is_none() {
+                            error!(ret: field.ty.span(),
+                                "multiple with key requires a map like `BTreeMap<K, V>`"
+                            );
+                        }
+                    } else if collection_item_ty(&field.ty).is_none()
+                        && array_item_ty(&field.ty).is_none()
+                    {
+                        error!(ret: field.ty.span(),
+                            "multiple requires a collection like `Vec<T>` or an array `[T; N]`"
+                        );
                     }
                     true
                 }
@@ -210,29 +666,353 @@ impl NamedStruct {
                         "multiple attribute values found for \"multiple\"",
                     );
                 }
-                AttrResult::Lit(_) => {
+                AttrResult::Lit(_) | AttrResult::List(_) => {
                     error!(ret: field.span(), "expected multiple");
                 }
             };
 
-            let validation = match get_attr(&field.attrs, "validate")? {
-                AttrResult::Lit(lit) => Some(lit),
+            if key.is_some() && (!has_multiple || val_ty != ValueTy::Value) {
+                error!(ret: field.span(), "key can only used with multiple value fields");
+            }
+
+            let array_len = match &field.ty {
+                syn::Type::Array(array) if has_multiple => {
+                    if default.is_some() {
+                        error!(ret: field.span(), "default can not be combined with a multiple array");
+                    }
+                    Some(array.len.clone())
+                }
+                _ => None,
+            };
+
+            let optional = (val_ty == ValueTy::Value || val_ty == ValueTy::Element)
+                && !has_multiple
+                && default.is_none()
+                && is_option(&field.ty);
+
+            let wrapper = match get_attr(&field.attrs, "wrapper")? {
+                AttrResult::Lit(lit) => {
+                    if !has_multiple {
+                        error!(ret: field.span(), "wrapper can only used with multiple");
+                    }
+                    if val_ty == ValueTy::ValueBuf {
+                        error!(ret: field.span(), "wrapper can not be combined with value_buf");
+                    }
+                    Some(get_literal_str(lit)?)
+                }
+                AttrResult::NotFound => None,
+                _ => error!(ret: field.span(), "expected one single literal str for wrapper"),
+            };
+
+            let min = get_occurrence(field, "min", has_multiple)?;
+            let max = get_occurrence(field, "max", has_multiple)?;
+            if array_len.is_some() && (min.is_some() || max.is_some()) {
+                error!(ret: field.span(), "the number of children of an array is always its length");
+            }
+            if let (Some(min), Some(max)) = (min, max) {
+                if min > max {
+                    error!(ret: field.span(), "min must not be greater than max");
+                }
+            }
+
+            let cdata = match get_attr(&field.attrs, "cdata")? {
+                AttrResult::Existing => {
+                    if val_ty != ValueTy::ValueBuf {
+                        error!(ret: field.span(), "cdata can only used with value_buf");
+                    }
+                    true
+                }
+                AttrResult::NotFound => false,
+                AttrResult::Multiple => {
+                    error!(ret: field.span(),
+                        "multiple attribute values found for \"cdata\"",
+                    );
+                }
+                AttrResult::Lit(_) | AttrResult::List(_) => {
+                    error!(ret: field.span(), "expected cdata");
+                }
+            };
+
+            let validation = get_validation(field)?;
+
+            let borrow = match get_attr(&field.attrs, "borrow")? {
+                AttrResult::Existing => {
+                    if val_ty != ValueTy::Attr {
+                        error!(ret: field.span(), "borrow can only used with attributes");
+                    }
+                    if !has_lifetime {
+                        error!(ret: field.span(), "borrow requires a lifetime on the struct");
+                    }
+                    true
+                }
+                AttrResult::NotFound => false,
+                AttrResult::Multiple => {
+                    error!(ret: field.span(),
+                        "multiple attribute values found for \"borrow\"",
+                    );
+                }
+                AttrResult::Lit(_) | AttrResult::List(_) => {
+                    error!(ret: field.span(), "expected borrow");
+                }
+            };
+
+            let field_namespace = match get_attr(&field.attrs, "namespace")? {
+                AttrResult::Lit(lit) => {
+                    if val_ty != ValueTy::Element {
+                        error!(ret: field.span(), "namespace can only used with element");
+                    }
+                    Some(get_literal_str(lit)?)
+                }
+                AttrResult::NotFound => None,
+                _ => error!(ret: field.span(), "expected one single literal str for namespace"),
+            };
+
+            let bool_words = match get_attr(&field.attrs, "bool_format")? {
+                AttrResult::Lit(lit) => {
+                    if has_multiple
+                        || optional
+                        || !matches!(val_ty, ValueTy::Attr | ValueTy::ValueBuf | ValueTy::Element)
+                    {
+                        error!(ret: field.span(),
+                            "bool_format can only used with attributes, value_buf or required single elements"
+                        );
+                    }
+                    match get_literal_str(lit)?.as_str() {
+                        "words" => Some(true),
+                        "digits" => Some(false),
+                        _ => {
+                            error!(ret: field.span(), "bool_format must be \"words\" or \"digits\"")
+                        }
+                    }
+                }
+                AttrResult::NotFound => None,
+                _ => error!(ret: field.span(), "expected one single literal str for bool_format"),
+            };
+
+            let with = match get_attr(&field.attrs, "with")? {
+                AttrResult::Lit(lit) => {
+                    // the module converts the items of multiple elements
+                    if !matches!(val_ty, ValueTy::Attr | ValueTy::ValueBuf | ValueTy::Element) {
+                        error!(ret: field.span(),
+                            "with can only used with attributes, value_buf or elements"
+                        );
+                    }
+                    if borrow || bool_words.is_some() {
+                        error!(ret: field.span(), "with can not be combined with borrow or bool_format");
+                    }
+                    let lit = match lit {
+                        syn::Lit::Str(lit) => lit,
+                        lit => error!(ret: lit.span(), "expected literal str for with"),
+                    };
+                    match lit.parse() {
+                        Ok(path) => Some(path),
+                        Err(e) => {
+                            error!(ret: lit.span(), format!("could not parse with path: {}", e))
+                        }
+                    }
+                }
+                AttrResult::NotFound => None,
+                _ => error!(ret: field.span(), "expected one single literal str for with"),
+            };
+
+            // `display` and `serde` are shortcuts for `with` modules of xmlib
+            let mut with = with;
+            let shortcuts: [(&str, syn::Path); 2] = [
+                ("display", syn::parse_quote!(::xmlib::types::display)),
+                ("serde", syn::parse_quote!(::xmlib::compat::serde_buf)),
+            ];
+            for (key, module) in shortcuts {
+                match get_attr(&field.attrs, key)? {
+                    AttrResult::Existing => {
+                        if !matches!(val_ty, ValueTy::Attr | ValueTy::ValueBuf | ValueTy::Element) {
+                            error!(ret: field.span(), format!(
+                                "{} can only used with attributes, value_buf or elements", key
+                            ));
+                        }
+                        if borrow || bool_words.is_some() || with.is_some() {
+                            error!(ret: field.span(), format!(
+                                "{} can not be combined with borrow, bool_format, with, display or serde", key
+                            ));
+                        }
+                        with = Some(module);
+                    }
+                    AttrResult::NotFound => {}
+                    AttrResult::Multiple => {
+                        error!(ret: field.span(), format!(
+                            "multiple attribute values found for \"{}\"", key
+                        ));
+                    }
+                    AttrResult::Lit(_) | AttrResult::List(_) => {
+                        error!(ret: field.span(), format!("expected {}", key));
+                    }
+                }
+            }
+
+            let radix = match get_attr(&field.attrs, "radix")? {
+                AttrResult::Lit(syn::Lit::Int(lit)) => {
+                    if !matches!(val_ty, ValueTy::Attr | ValueTy::ValueBuf | ValueTy::Element) {
+                        error!(ret: field.span(),
+                            "radix can only used with attributes, value_buf or elements"
+                        );
+                    }
+                    if borrow || bool_words.is_some() || with.is_some() {
+                        error!(ret: field.span(), "radix can not be combined with borrow, bool_format or with");
+                    }
+                    match lit.base10_parse() {
+                        Ok(radix @ (2 | 8 | 16)) => Some(radix),
+                        Ok(_) => error!(ret: lit.span(), "radix must be 2, 8 or 16"),
+                        Err(e) => return Err(TokenStream::from(e.to_compile_error())),
+                    }
+                }
+                AttrResult::NotFound => None,
+                _ => error!(ret: field.span(), "expected one single literal integer for radix"),
+            };
+
+            let lenient_bool = match get_attr(&field.attrs, "lenient_bool")? {
+                AttrResult::Existing => {
+                    if has_multiple
+                        || optional
+                        || !matches!(val_ty, ValueTy::Attr | ValueTy::ValueBuf | ValueTy::Element)
+                    {
+                        error!(ret: field.span(),
+                            "lenient_bool can only used with attributes, value_buf or required single elements"
+                        );
+                    }
+                    if borrow || with.is_some() || radix.is_some() {
+                        error!(ret: field.span(), "lenient_bool can not be combined with borrow, with or radix");
+                    }
+                    true
+                }
+                AttrResult::NotFound => false,
+                AttrResult::Multiple => {
+                    error!(ret: field.span(),
+                        "multiple attribute values found for \"lenient_bool\"",
+                    );
+                }
+                AttrResult::Lit(_) | AttrResult::List(_) => {
+                    error!(ret: field.span(), "expected lenient_bool");
+                }
+            };
+
+            let nillable = match get_attr(&field.attrs, "nillable")? {
+                AttrResult::Existing => {
+                    if !optional {
+                        error!(ret: field.span(),
+                            "nillable can only used with optional value or element fields"
+                        );
+                    }
+                    true
+                }
+                AttrResult::NotFound => false,
+                AttrResult::Multiple => {
+                    error!(ret: field.span(),
+                        "multiple attribute values found for \"nillable\"",
+                    );
+                }
+                AttrResult::Lit(_) | AttrResult::List(_) => {
+                    error!(ret: field.span(), "expected nillable");
+                }
+            };
+
+            let float_format = match get_attr(&field.attrs, "float_format")? {
+                AttrResult::List(list) => {
+                    if !matches!(val_ty, ValueTy::Attr | ValueTy::ValueBuf | ValueTy::Element) {
+                        error!(ret: field.span(),
+                            "float_format can only used with attributes, value_buf or elements"
+                        );
+                    }
+                    if borrow
+                        || bool_words.is_some()
+                        || with.is_some()
+                        || radix.is_some()
+                        || lenient_bool
+                    {
+                        error!(ret: field.span(),
+                            "float_format can not be combined with borrow, bool_format, with, radix or lenient_bool"
+                        );
+                    }
+                    Some(get_float_format(&list)?)
+                }
                 AttrResult::NotFound => None,
-                _ => error!(ret: field.span(), "expected one single literal str for validate"),
+                _ => error!(ret: field.span(), FLOAT_FORMAT_EXPECTED),
+            };
+
+            let trim = match get_attr(&field.attrs, "trim")? {
+                AttrResult::Existing => {
+                    if val_ty != ValueTy::Attr {
+                        error!(ret: field.span(), "trim can only used with attributes");
+                    }
+                    if borrow {
+                        error!(ret: field.span(), "trim can not be combined with borrow");
+                    }
+                    true
+                }
+                AttrResult::NotFound => false,
+                AttrResult::Multiple => {
+                    error!(ret: field.span(),
+                        "multiple attribute values found for \"trim\"",
+                    );
+                }
+                AttrResult::Lit(_) | AttrResult::List(_) => {
+                    error!(ret: field.span(), "expected trim");
+                }
+            };
+
+            let lenient = match get_attr(&field.attrs, "lenient")? {
+                AttrResult::Existing => {
+                    if !matches!(val_ty, ValueTy::Attr | ValueTy::ValueBuf | ValueTy::Element) {
+                        error!(ret: field.span(),
+                            "lenient can only used with attributes, value_buf or elements"
+                        );
+                    }
+                    if borrow || lenient_bool {
+                        error!(ret: field.span(), "lenient can not be combined with borrow or lenient_bool");
+                    }
+                    true
+                }
+                AttrResult::NotFound => false,
+                AttrResult::Multiple => {
+                    error!(ret: field.span(),
+                        "multiple attribute values found for \"lenient\"",
+                    );
+                }
+                AttrResult::Lit(_) | AttrResult::List(_) => {
+                    error!(ret: field.span(), "expected lenient");
+                }
             };
 
             let constructed_field = Field {
                 ident: field.ident.clone().unwrap(),
-                name,
+                ser_name,
+                de_name,
                 default,
+                default_text,
                 ty: field.ty.clone(),
                 has_multiple,
+                optional,
+                element: val_ty == ValueTy::Element,
+                wrapper,
+                cdata,
+                min,
+                max,
                 validation,
+                borrow,
+                namespace: field_namespace,
+                bool_words,
+                with,
+                radix,
+                lenient_bool,
+                nillable,
+                float_format,
+                trim,
+                lenient,
+                key,
+                array_len,
             };
 
             match val_ty {
                 ValueTy::Attr => ty_attribute.push(constructed_field),
-                ValueTy::Value => ty_value.push(constructed_field),
+                ValueTy::Value | ValueTy::Element => ty_value.push(constructed_field),
                 ValueTy::ValueBuf => {
                     if ty_value_buf.is_some() {
                         error!(ret: field.span(),
@@ -241,6 +1021,17 @@ impl NamedStruct {
                     }
                     ty_value_buf = Some(constructed_field);
                 }
+                ValueTy::Mixed => {
+                    if ty_mixed.is_some() {
+                        error!(ret: field.span(),
+                            "only one attribute may be annotated with \"mixed\""
+                        )
+                    }
+                    if vec_item_ty(&field.ty).is_none() {
+                        error!(ret: field.ty.span(), "mixed requires a field of type `Vec<T>`");
+                    }
+                    ty_mixed = Some(constructed_field);
+                }
                 ValueTy::CollectNamespaces => {
                     if ty_collect_namespaces.is_some() {
                         error!(ret: field.span(),
@@ -249,45 +1040,488 @@ impl NamedStruct {
                     }
                     ty_collect_namespaces = Some(constructed_field.ident);
                 }
+                ValueTy::CollectAttrs => {
+                    if ty_collect_attrs.is_some() {
+                        error!(ret: field.span(),
+                            "only one attribute may be annotated with \"collect_attrs\""
+                        )
+                    }
+                    let collection = match attr_collection(&field.ty) {
+                        Some(collection) => collection,
+                        None => error!(ret: field.ty.span(),
+                            "collect_attrs requires a field of type `Vec<(String, String)>`, `HashMap<String, String>` or `BTreeMap<String, String>`"
+                        ),
+                    };
+                    ty_collect_attrs = Some((constructed_field, collection));
+                }
+                ValueTy::CollectComments => {
+                    if ty_collect_comments.is_some() {
+                        error!(ret: field.span(),
+                            "only one attribute may be annotated with \"collect_comments\""
+                        )
+                    }
+                    if vec_item_ty(&field.ty).is_none() {
+                        error!(ret: field.ty.span(), "collect_comments requires a field of type `Vec<String>`");
+                    }
+                    ty_collect_comments = Some(constructed_field.ident);
+                }
             }
         }
 
+        if ty_mixed.is_some() && ty_value_buf.is_some() {
+            error!(ret: fields.span(), "\"mixed\" and \"value_buf\" can not be combined");
+        }
+
+        // the names are checked separately for serialization and deserialization
+        let names: [fn(&Field) -> &String; 2] = [|field| &field.ser_name, |field| &field.de_name];
+        for name in names {
+            check_duplicate_names(
+                "attribute",
+                ty_attribute
+                    .iter()
+                    .map(|field| (&field.ident, name(field), None)),
+            )?;
+            // the names of value fields are only known after expansion, so only elements and
+            // wrappers are checked. Wrappers are matched in every namespace.
+            check_duplicate_names(
+                "child element",
+                ty_value
+                    .iter()
+                    .filter_map(|field| match (&field.wrapper, field.element) {
+                        (Some(wrapper), _) => Some((&field.ident, wrapper, None)),
+                        (None, true) => Some((&field.ident, name(field), field.namespace.as_ref())),
+                        (None, false) => None,
+                    }),
+            )?;
+        }
+
         Ok(Self {
             no_constructor,
+            derive_default: false,
+            reflect: false,
+            finalize,
             raw_ser_name,
+            namespace,
+            ignore_unknown_children,
             ty_attribute,
             ty_value,
             ty_value_buf,
+            ty_mixed,
             ty_collect_namespaces,
+            ty_collect_attrs,
+            ty_collect_comments,
+            field_order: fields
+                .named
+                .iter()
+                .map(|field| field.ident.clone().unwrap())
+                .collect(),
         })
     }
 }
 
+#[derive(Clone)]
 pub(crate) struct UnnamedStruct {
-    pub(crate) validation: Option<syn::Lit>,
+    // name of the element if the struct is a list of elements
+    pub(crate) list: Option<String>,
+    pub(crate) validation: Option<Validation>,
+    pub(crate) finalize: Option<syn::Expr>,
     pub(crate) ty: syn::Type,
+    // the field, `0` or the name of the field of a transparent named struct
+    pub(crate) member: syn::Member,
 }
 
 impl UnnamedStruct {
-    fn parse(fields: &syn::FieldsUnnamed) -> Result<Self, TokenStream> {
-        if fields.unnamed.len() != 1 {
-            Err(error!(
-                fields.span(),
-                "only unnamed structs with one field are supported"
-            ))
+    fn parse(
+        field: &syn::Field,
+        member: syn::Member,
+        list: Option<String>,
+        finalize: Option<syn::Expr>,
+    ) -> Result<Self, TokenStream> {
+        check_attrs(&field.attrs, UNNAMED_FIELD_KEYS)?;
+        let validation = get_validation(field)?;
+
+        Ok(Self {
+            list,
+            validation,
+            finalize,
+            ty: field.ty.clone(),
+            member,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Validation {
+    pub(crate) func: syn::Expr,
+    // box the error into `ErrorKind::CustomValidation` instead of formatting it
+    pub(crate) boxed: bool,
+}
+
+// Parses `rename(serialize = "...", deserialize = "...")` where both names are optional
+fn get_asymmetric_rename(
+    list: &syn::MetaList,
+) -> Result<(Option<String>, Option<String>), TokenStream> {
+    const EXPECTED: &str = "expected `serialize = \"...\"` or `deserialize = \"...\"`";
+    let mut ser_name = None;
+    let mut de_name = None;
+    for meta in &list.nested {
+        let (ident, lit) = match meta {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(meta)) => {
+                match (meta.path.get_ident(), &meta.lit) {
+                    (Some(ident), syn::Lit::Str(lit)) => (ident, lit.value()),
+                    _ => error!(ret: meta.span(), EXPECTED),
+                }
+            }
+            meta => error!(ret: meta.span(), EXPECTED),
+        };
+        let target = if ident == "serialize" {
+            &mut ser_name
+        } else if ident == "deserialize" {
+            &mut de_name
         } else {
-            let field = &fields.unnamed[0];
-            let validation = match get_attr(&field.attrs, "validate")? {
-                AttrResult::Lit(lit) => Some(lit),
-                AttrResult::NotFound => None,
-                _ => error!(ret: field.span(), "expected one single literal str for validate"),
-            };
+            error!(ret: ident.span(), EXPECTED);
+        };
+        if target.is_some() {
+            error!(ret: ident.span(), format!("multiple names found for \"{}\"", ident));
+        }
+        *target = Some(lit);
+    }
+    if ser_name.is_none() && de_name.is_none() {
+        error!(ret: list.span(), EXPECTED);
+    }
+    Ok((ser_name, de_name))
+}
 
-            Ok(Self {
-                validation,
-                ty: field.ty.clone(),
-            })
+const FLOAT_FORMAT_EXPECTED: &str =
+    "expected `float_format(precision = 6)` or `float_format(no_exponent)`";
+
+/// Returns the precision of `float_format(precision = 6, no_exponent)`.
+fn get_float_format(list: &syn::MetaList) -> Result<Option<usize>, TokenStream> {
+    let mut precision = None;
+    let mut no_exponent = false;
+    for meta in &list.nested {
+        match meta {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                lit: syn::Lit::Int(lit),
+                ..
+            })) if path.is_ident("precision") => {
+                if precision.is_some() {
+                    error!(ret: path.span(), "multiple values found for \"precision\"");
+                }
+                match lit.base10_parse() {
+                    Ok(value) => precision = Some(value),
+                    Err(e) => return Err(TokenStream::from(e.to_compile_error())),
+                }
+            }
+            syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("no_exponent") => {
+                if no_exponent {
+                    error!(ret: path.span(), "multiple values found for \"no_exponent\"");
+                }
+                no_exponent = true;
+            }
+            meta => error!(ret: meta.span(), FLOAT_FORMAT_EXPECTED),
+        }
+    }
+    if precision.is_none() && !no_exponent {
+        error!(ret: list.span(), FLOAT_FORMAT_EXPECTED);
+    }
+    Ok(precision)
+}
+
+/// Returns an error pointing at both fields if two fields use the same name.
+fn check_duplicate_names<'a>(
+    kind: &str,
+    names: impl Iterator<Item = (&'a Ident, &'a String, Option<&'a String>)>,
+) -> Result<(), TokenStream> {
+    let names: Vec<_> = names.collect();
+    for (i, (ident, name, namespace)) in names.iter().enumerate() {
+        let duplicate = names[..i].iter().find(|(_, other_name, other_namespace)| {
+            name == other_name
+                && (namespace.is_none()
+                    || other_namespace.is_none()
+                    || namespace == other_namespace)
+        });
+        if let Some((other, _, _)) = duplicate {
+            let mut err = syn::Error::new(
+                ident.span(),
+                format!(
+                    "{} name \"{}\" is already used by field `{}`",
+                    kind, name, other
+                ),
+            );
+            err.combine(syn::Error::new(
+                other.span(),
+                format!(
+                    "{} name \"{}\" is also used by field `{}`",
+                    kind, name, ident
+                ),
+            ));
+            return Err(TokenStream::from(err.to_compile_error()));
+        }
+    }
+    Ok(())
+}
+
+fn get_validation(field: &syn::Field) -> Result<Option<Validation>, TokenStream> {
+    let mut validation = None;
+    for (name, boxed) in [("validate", false), ("validate_boxed", true)] {
+        match get_attr(&field.attrs, name)? {
+            AttrResult::Lit(func) => {
+                if validation.is_some() {
+                    error!(ret: field.span(), "\"validate\" and \"validate_boxed\" can not be combined");
+                }
+                validation = Some(Validation {
+                    func: parse_expr(func, name)?,
+                    boxed,
+                });
+            }
+            AttrResult::NotFound => {}
+            _ => error!(ret: field.span(), format!("expected one single literal str for {}", name)),
+        }
+    }
+    Ok(validation)
+}
+
+fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path
+            .segments
+            .last()
+            .map_or(false, |segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+// Returns `T` if the type is `Option<T>`
+pub(crate) fn option_item_ty(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// How the attributes of a `collect_attrs` field are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AttrCollection {
+    // `Vec<(String, String)>` in document order
+    Vec,
+    // `HashMap<String, String>` or `BTreeMap<String, String>`
+    Map,
+}
+
+fn attr_collection(ty: &syn::Type) -> Option<AttrCollection> {
+    let segment = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident == "Vec" {
+        Some(AttrCollection::Vec)
+    } else if segment.ident == "HashMap" || segment.ident == "BTreeMap" {
+        Some(AttrCollection::Map)
+    } else {
+        None
+    }
+}
+
+// Returns `T` if the type is `Vec<T>`
+pub(crate) fn vec_item_ty(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Vec" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Returns `T` if the type is a collection like `Vec<T>`, `HashSet<T, S>` or `SmallVec<[T; N]>`
+pub(crate) fn collection_item_ty(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    let item_ty = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(ty) => ty,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    // the inline capacity of a `SmallVec` is given by an array type
+    if segment.ident == "SmallVec" {
+        array_item_ty(item_ty)
+    } else {
+        Some(item_ty)
+    }
+}
+
+// Returns `T` if the type is `[T; N]`
+pub(crate) fn array_item_ty(ty: &syn::Type) -> Option<&syn::Type> {
+    match ty {
+        syn::Type::Array(array) => Some(&array.elem),
+        _ => None,
+    }
+}
+
+// Returns `V` if the type is a map like `BTreeMap<K, V>`, `HashMap<K, V, S>` or `IndexMap<K, V>`
+pub(crate) fn map_value_ty(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    if !segment.ident.to_string().ends_with("Map") {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() >= 2 => match &args.args[1] {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Returns the map type with `usize` as value type, see `map_value_ty`
+pub(crate) fn map_with_positions(ty: &syn::Type) -> syn::Type {
+    let mut ty = ty.clone();
+    if let syn::Type::Path(syn::TypePath { path, .. }) = &mut ty {
+        if let Some(syn::PathArguments::AngleBracketed(args)) = path
+            .segments
+            .last_mut()
+            .map(|segment| &mut segment.arguments)
+        {
+            args.args[1] = syn::parse_quote!(usize);
+        }
+    }
+    ty
+}
+
+fn get_occurrence(
+    field: &syn::Field,
+    name: &str,
+    has_multiple: bool,
+) -> Result<Option<usize>, TokenStream> {
+    match get_attr(&field.attrs, name)? {
+        AttrResult::Lit(syn::Lit::Int(lit)) => {
+            if !has_multiple {
+                error!(ret: field.span(), format!("{} can only used with multiple", name));
+            }
+            match lit.base10_parse() {
+                Ok(v) => Ok(Some(v)),
+                Err(e) => Err(TokenStream::from(e.to_compile_error())),
+            }
         }
+        AttrResult::NotFound => Ok(None),
+        _ => Err(error!(
+            field.span(),
+            format!("expected one single literal integer for {}", name)
+        )),
+    }
+}
+
+fn get_lifetime(generics: &syn::Generics) -> Result<Option<syn::Lifetime>, TokenStream> {
+    let mut lifetime = None;
+    for param in &generics.params {
+        match param {
+            syn::GenericParam::Lifetime(def) if lifetime.is_none() && def.bounds.is_empty() => {
+                lifetime = Some(def.lifetime.clone());
+            }
+            param => error!(ret: param.span(), "only one single lifetime parameter is supported"),
+        }
+    }
+    if let Some(where_clause) = &generics.where_clause {
+        error!(ret: where_clause.span(), "where clauses are not supported");
+    }
+    Ok(lifetime)
+}
+
+fn get_raw_ser_name(input: &syn::DeriveInput) -> Result<String, TokenStream> {
+    match get_attr(&input.attrs, "rename")? {
+        AttrResult::Lit(lit) => get_literal_str(lit),
+        AttrResult::NotFound => Ok(input.ident.unraw().to_string().to_lower_camel_case()),
+        _ => Err(error!(input.span(), "expected one single literal str")),
+    }
+}
+
+fn get_finalize(input: &syn::DeriveInput) -> Result<Option<syn::Expr>, TokenStream> {
+    match get_attr(&input.attrs, "finalize")? {
+        AttrResult::Lit(lit) => Ok(Some(parse_expr(lit, "finalize")?)),
+        AttrResult::NotFound => Ok(None),
+        _ => Err(error!(
+            input.span(),
+            "expected one single literal str for finalize"
+        )),
+    }
+}
+
+// Parses the expression inside of a literal string, e.g. `validate = "check"`
+fn parse_expr(lit: syn::Lit, name: &str) -> Result<syn::Expr, TokenStream> {
+    match lit {
+        syn::Lit::Str(lit) => lit.parse().map_err(|e| {
+            error!(
+                lit.span(),
+                format!("could not parse {} expression: {}", name, e)
+            )
+        }),
+        lit => Err(error!(
+            lit.span(),
+            format!(
+                "expected literal str for {}, got {}",
+                name,
+                lit.to_token_stream()
+            )
+        )),
+    }
+}
+
+// Creates the expression of the default value.
+// Literal strings are parsed as expression unless the field is a `String`.
+fn get_default_expr(
+    default: syn::Lit,
+    ty: &syn::Type,
+) -> Result<proc_macro2::TokenStream, TokenStream> {
+    match (default, ty) {
+        (syn::Lit::Str(lit), syn::Type::Path(syn::TypePath { qself: None, path }))
+            if path.is_ident("String") =>
+        {
+            Ok(quote! {::std::string::String::from(#lit)})
+        }
+        (default @ syn::Lit::Str(_), _) => Ok(parse_expr(default, "default")?.into_token_stream()),
+        (default, _) => Ok(default.into_token_stream()),
+    }
+}
+
+// Returns the default as xml text if it is a literal which is deserialized from the same text
+fn get_default_text(default: &syn::Lit, ty: &syn::Type) -> Option<String> {
+    match default {
+        syn::Lit::Str(lit) => match ty {
+            syn::Type::Path(syn::TypePath { qself: None, path }) if path.is_ident("String") => {
+                Some(lit.value())
+            }
+            _ => None,
+        },
+        syn::Lit::Int(lit) => Some(lit.base10_digits().to_owned()),
+        syn::Lit::Float(lit) => Some(lit.base10_digits().to_owned()),
+        syn::Lit::Bool(lit) => Some(lit.value.to_string()),
+        _ => None,
     }
 }
 
@@ -308,57 +1542,46 @@ enum ValueTy {
     Value,
     ValueBuf,
     CollectNamespaces,
+    CollectAttrs,
+    CollectComments,
+    Element,
+    Mixed,
 }
 
 fn get_val_ty(field: &syn::Field) -> Result<ValueTy, TokenStream> {
-    use AttrResult::{Existing, Lit, Multiple, NotFound};
-
-    Ok(
-        match (
-            get_attr(&field.attrs, "value")?,
-            get_attr(&field.attrs, "value_buf")?,
-            get_attr(&field.attrs, "collect_namespaces")?,
-        ) {
-            (NotFound, NotFound, NotFound) => ValueTy::Attr,
-
-            (Existing, NotFound, NotFound) => ValueTy::Value,
-            (NotFound, Existing, NotFound) => ValueTy::ValueBuf,
-            (NotFound, NotFound, Existing) => ValueTy::CollectNamespaces,
-
-            (NotFound, Existing, Existing)
-            | (Existing, NotFound, Existing)
-            | (Existing, Existing, NotFound)
-            | (Existing, Existing, Existing) => {
-                return Err(error!(
-                    field.span(),
-                    "\"value\", \"value_buf\" and \"collect_namespaces\" can not be combined.",
-                ));
-            }
+    let mut val_ty = ValueTy::Attr;
 
-            (Multiple, _, _) => {
-                return Err(error!(
-                    field.span(),
-                    "multiple attribute values found for \"value\"",
-                ));
+    for (name, ty) in [
+        ("value", ValueTy::Value),
+        ("value_buf", ValueTy::ValueBuf),
+        ("collect_namespaces", ValueTy::CollectNamespaces),
+        ("collect_attrs", ValueTy::CollectAttrs),
+        ("collect_comments", ValueTy::CollectComments),
+        ("element", ValueTy::Element),
+        ("mixed", ValueTy::Mixed),
+    ] {
+        match get_attr(&field.attrs, name)? {
+            AttrResult::NotFound => {}
+            AttrResult::Existing => {
+                if val_ty != ValueTy::Attr {
+                    error!(ret: field.span(),
+                        "\"value\", \"value_buf\", \"collect_namespaces\", \"collect_attrs\", \"collect_comments\", \"element\" and \"mixed\" can not be combined.",
+                    );
+                }
+                val_ty = ty;
             }
-            (_, Multiple, _) => {
-                return Err(error!(
-                    field.span(),
-                    "multiple attribute values found for \"value_buf\"",
-                ));
+            AttrResult::Multiple => {
+                error!(ret: field.span(),
+                    format!("multiple attribute values found for \"{}\"", name),
+                );
             }
-            (_, _, Multiple) => {
-                return Err(error!(
-                    field.span(),
-                    "multiple attribute values found for \"collect_namespaces\"",
-                ));
+            AttrResult::Lit(_) | AttrResult::List(_) => {
+                error!(ret: field.span(), "expected value");
             }
+        }
+    }
 
-            (Lit(_), _, _) | (_, Lit(_), _) | (_, _, Lit(_)) => {
-                return Err(error!(field.span(), "expected value"));
-            }
-        },
-    )
+    Ok(val_ty)
 }
 
 #[derive(Debug, PartialEq)]
@@ -366,6 +1589,8 @@ enum AttrResult {
     NotFound,
     Multiple,
     Lit(syn::Lit),
+    // `key(...)`
+    List(syn::MetaList),
     Existing,
 }
 
@@ -384,9 +1609,8 @@ fn get_attr(attrs: &[syn::Attribute], name: &str) -> Result<AttrResult, TokenStr
                 for meta in &meta.nested {
                     let meta = match meta {
                         syn::NestedMeta::Meta(meta) => meta,
-                        syn::NestedMeta::Lit(_) => {
-                            // TODO is this correct?
-                            continue;
+                        syn::NestedMeta::Lit(lit) => {
+                            error!(ret: lit.span(), "expected `key` or `key = value`")
                         }
                     };
                     match meta {
@@ -406,12 +1630,51 @@ fn get_attr(attrs: &[syn::Attribute], name: &str) -> Result<AttrResult, TokenStr
                                 res = AttrResult::Existing;
                             }
                         }
-                        a => todo!("{:?}", a),
+                        syn::Meta::List(ref list) => {
+                            if list.path.is_ident(name) {
+                                if res != AttrResult::NotFound {
+                                    return Ok(AttrResult::Multiple);
+                                }
+                                res = AttrResult::List(list.clone());
+                            }
+                        }
                     }
                 }
             }
-            _ => panic!("expected ser(...)"),
+            meta => error!(ret: meta.span(), "expected #[xmlib(...)]"),
         }
     }
     Ok(res)
 }
+
+// Checks that `#[xmlib(...)]` only contains the given keys
+fn check_attrs(attrs: &[syn::Attribute], known: &[&str]) -> Result<(), TokenStream> {
+    for attr in attrs {
+        if !attr.path.is_ident("xmlib") {
+            continue;
+        }
+        let meta = match attr.parse_meta() {
+            Ok(syn::Meta::List(meta)) => meta,
+            Ok(meta) => error!(ret: meta.span(), "expected #[xmlib(...)]"),
+            Err(e) => error!(ret: attr.span(), e),
+        };
+        for meta in &meta.nested {
+            let path = match meta {
+                syn::NestedMeta::Meta(meta) => meta.path(),
+                meta => error!(ret: meta.span(), "expected `key` or `key = value`"),
+            };
+            if !known.iter().any(|key| path.is_ident(key)) {
+                let key = quote::ToTokens::to_token_stream(path);
+                if known.is_empty() {
+                    error!(ret: path.span(), format!("unknown attribute `{}`, no attributes are supported here", key));
+                }
+                error!(ret: path.span(), format!(
+                    "unknown attribute `{}`, expected one of: {}",
+                    key,
+                    known.join(", ")
+                ));
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,36 +1,202 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
-use quote::{quote, ToTokens};
-use syn::Ident;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{spanned::Spanned, Ident};
 
-use crate::parse::{Enum, Field, Input, InputData, NamedStruct, UnnamedStruct};
+use crate::parse::{Enum, Field, Input, InputData, NamedStruct, UnnamedStruct, Validation};
 
-pub(crate) fn expand(Input { data, ident }: Input) -> TokenStream {
+pub(crate) fn expand(
+    Input {
+        data,
+        ident,
+        lifetime,
+        erased: _,
+        ser_only: _,
+        de_only: _,
+    }: Input,
+) -> TokenStream {
+    // only named structs and lists are written as a single element with a fixed name
+    let element = match &data {
+        InputData::NamedStruct(v) => Some((&v.raw_ser_name, v.namespace.as_ref())),
+        InputData::UnnamedStruct(v) => v.list.as_ref().map(|name| (name, None)),
+        InputData::Enum(_) => None,
+    }
+    .map(|(name, namespace)| {
+        let name = proc_macro2::Literal::byte_string(name.as_bytes());
+        let namespace = match namespace {
+            Some(namespace) => {
+                let namespace = proc_macro2::Literal::byte_string(namespace.as_bytes());
+                quote! {::std::option::Option::Some(#namespace)}
+            }
+            None => quote! {::std::option::Option::None},
+        };
+        quote! {
+            #[inline]
+            fn element_name() -> ::std::option::Option<&'static [u8]> {
+                ::std::option::Option::Some(#name)
+            }
+
+            #[inline]
+            fn element_namespace() -> ::std::option::Option<&'static [u8]> {
+                #namespace
+            }
+        }
+    });
+    let named = matches!(data, InputData::NamedStruct(_));
     let (pre, inner) = match data {
         InputData::Enum(v) => (Default::default(), expand_enum(v)),
-        InputData::NamedStruct(v) => expand_named_struct(v, &ident),
-        InputData::UnnamedStruct(v) => (Default::default(), expand_unnamed_struct(v)),
+        InputData::NamedStruct(v) => expand_named_struct(v, &ident, lifetime.as_ref()),
+        InputData::UnnamedStruct(v) => (Default::default(), expand_unnamed_struct(v, &ident)),
+    };
+    let (lifetime, ty_lifetime) = match lifetime {
+        Some(lifetime) => (quote! {#lifetime,}, quote! {<#lifetime>}),
+        None => Default::default(),
+    };
+
+    // named structs write an additional attribute in `SerializeWithAttr`, e.g. the discriminant
+    // of enums with `tag_attr`
+    let (inner, with_attr_impl) = if named {
+        let with_attr_impl = quote! {
+            #[automatically_derived]
+            impl<#lifetime W: ::std::io::Write> ::xmlib::ser::SerializeWithAttr<W> for #ident #ty_lifetime {
+                #[inline]
+                fn ser_with_attr(
+                    &self,
+                    writer__: &mut ::xmlib::ser::XmlWriter<W>,
+                    attr__: ::std::option::Option<(&[u8], &[u8])>,
+                ) -> ::std::result::Result<(), ::xmlib::ser::Error> {
+                    #inner
+                }
+            }
+        };
+        (
+            quote! {
+                ::xmlib::ser::SerializeWithAttr::ser_with_attr(
+                    self,
+                    writer__,
+                    ::std::option::Option::None,
+                )
+            },
+            Some(with_attr_impl),
+        )
+    } else {
+        (inner, None)
     };
 
     quote! {
         #pre
+        #with_attr_impl
 
         #[automatically_derived]
-        impl<W: ::std::io::Write> ::xmlib::ser::Serialize<W> for #ident {
+        impl<#lifetime W: ::std::io::Write> ::xmlib::ser::Serialize<W> for #ident #ty_lifetime {
             #[inline]
-            fn ser(&self, writer__: &mut ::xmlib::ser::XmlWriter<W>) -> ::std::io::Result<()> {
+            fn ser(
+                &self,
+                writer__: &mut ::xmlib::ser::XmlWriter<W>,
+            ) -> ::std::result::Result<(), ::xmlib::ser::Error> {
                 #inner
             }
+
+            #element
         }
     }
     .into()
 }
 
-fn expand_enum(Enum { has_data, variants }: Enum) -> TokenStream2 {
+fn expand_enum(
+    Enum {
+        has_data,
+        variants,
+        text_variant,
+        tagged,
+        value_variants,
+        tag_attr,
+    }: Enum,
+) -> TokenStream2 {
+    if let Some(tag_attr) = tag_attr {
+        // the element of the variant writes the attribute
+        let key = proc_macro2::Literal::byte_string(tag_attr.as_bytes());
+        let variants = variants.into_iter().map(|(ident, name, ty)| {
+            let name = proc_macro2::Literal::byte_string(name.as_bytes());
+            let check = ty.map(|ty| {
+                quote_spanned! {ty.span()=>
+                    fn assert_impl__<W: ::std::io::Write, T: ::xmlib::ser::SerializeWithAttr<W>>() {}
+                    assert_impl__::<W, #ty>();
+                }
+            });
+            quote! {
+                Self::#ident(v) => {
+                    #check
+                    ::xmlib::ser::SerializeWithAttr::ser_with_attr(
+                        v,
+                        writer__,
+                        ::std::option::Option::Some((#key, #name)),
+                    )?;
+                }
+            }
+        });
+        return quote! {
+            match self {
+                #(#variants)*
+            }
+            ::std::result::Result::Ok(())
+        };
+    }
+    if tagged {
+        let variants = variants.into_iter().map(|(ident, name, ty)| {
+            if value_variants.contains(&ident) {
+                // the data is the element
+                quote! {
+                    Self::#ident(v) => ::xmlib::ser::Serialize::ser(v, writer__)?,
+                }
+            } else if ty.is_some() {
+                let start = proc_macro2::Literal::byte_string(format!("<{}>", name).as_bytes());
+                let end = proc_macro2::Literal::byte_string(format!("</{}>", name).as_bytes());
+                quote! {
+                    Self::#ident(v) => {
+                        let mut text__ = writer__.text_buffer();
+                        ::xmlib::ser::Serialize::ser(v, &mut text__)?;
+                        writer__.start_element()?;
+                        writer__.write_all(#start)?;
+                        writer__.write_text_buffer(text__)?;
+                        writer__.end_element(false)?;
+                        writer__.write_all(#end)?;
+                    }
+                }
+            } else {
+                let empty = proc_macro2::Literal::byte_string(format!("<{}/>", name).as_bytes());
+                quote! {
+                    Self::#ident => {
+                        writer__.start_element()?;
+                        writer__.write_all(#empty)?;
+                        writer__.end_element(true)?;
+                    }
+                }
+            }
+        });
+        return quote! {
+            match self {
+                #(#variants)*
+            }
+            ::std::result::Result::Ok(())
+        };
+    }
     if has_data {
         let variants = variants.into_iter().map(|(ident, _name, _ty)| {
-            quote! {
-                Self::#ident(v) => ::xmlib::ser::Serialize::ser(v, writer__),
+            if text_variant.as_ref() == Some(&ident) {
+                // the text of mixed content has to be escaped
+                quote! {
+                    Self::#ident(v) => {
+                        let mut text__ = writer__.text_buffer();
+                        ::xmlib::ser::Serialize::ser(v, &mut text__)?;
+                        writer__.write_text_buffer(text__)?;
+                        ::std::result::Result::Ok(())
+                    }
+                }
+            } else {
+                quote! {
+                    Self::#ident(v) => ::xmlib::ser::Serialize::ser(v, writer__),
+                }
             }
         });
         quote! {
@@ -39,34 +205,93 @@ fn expand_enum(Enum { has_data, variants }: Enum) -> TokenStream2 {
             }
         }
     } else {
-        let variants = variants
-            .into_iter()
-            .map(|(ident, name, _ty)| quote! { Self::#ident => #name, });
+        let variants = variants.into_iter().map(|(ident, name, _ty)| {
+            let name = proc_macro2::Literal::byte_string(name.as_bytes());
+            quote! { Self::#ident => #name, }
+        });
         quote! {
             writer__.write_all(match self {
                 #(#variants)*
-            })
+            })?;
+            ::std::result::Result::Ok(())
         }
     }
 }
 
 // Unnamed structs are just new-types and serialized as them
-fn expand_unnamed_struct(_s: UnnamedStruct) -> TokenStream2 {
-    quote! { self.0.ser(writer__) }
+fn expand_unnamed_struct(
+    UnnamedStruct {
+        list,
+        validation,
+        finalize: _,
+        ty: _,
+        member,
+    }: UnnamedStruct,
+    ident: &Ident,
+) -> TokenStream2 {
+    let ty_name = list.clone().unwrap_or_else(|| ident.to_string());
+    let validation = validation
+        .map(|validation| create_validation(&validation, &quote! {self.#member}, &ty_name));
+
+    if let Some(raw_ser_name) = list {
+        let start = proc_macro2::Literal::byte_string(format!("<{}>", raw_ser_name).as_bytes());
+        let end = proc_macro2::Literal::byte_string(format!("</{}>", raw_ser_name).as_bytes());
+        let empty = proc_macro2::Literal::byte_string(format!("<{}/>", raw_ser_name).as_bytes());
+        quote! {
+            #validation
+            writer__.start_element()?;
+            if self.0.is_empty() {
+                writer__.write_all(#empty)?;
+                writer__.end_element(true)?;
+                return ::std::result::Result::Ok(());
+            }
+            writer__.write_all(#start)?;
+            for inner in &self.0 {
+                ::xmlib::ser::Serialize::ser(inner, writer__)?;
+            }
+            writer__.end_element(false)?;
+            writer__.write_all(#end)?;
+            ::std::result::Result::Ok(())
+        }
+    } else {
+        quote! {
+            #validation
+            ::xmlib::ser::Serialize::ser(&self.#member, writer__)
+        }
+    }
 }
 
-fn expand_named_struct(s: NamedStruct, ident: &Ident) -> (TokenStream2, TokenStream2) {
+fn expand_named_struct(
+    s: NamedStruct,
+    ident: &Ident,
+    lifetime: Option<&syn::Lifetime>,
+) -> (TokenStream2, TokenStream2) {
+    let schema_impl = s
+        .reflect
+        .then(|| crate::meta::expand_named_struct(&s, ident, lifetime));
     let NamedStruct {
         no_constructor,
+        derive_default,
+        reflect: _,
+        finalize: _,
         raw_ser_name,
+        namespace,
+        ignore_unknown_children: _,
         ty_attribute,
         ty_value,
         ty_value_buf,
+        ty_mixed,
         ty_collect_namespaces,
+        ty_collect_attrs,
+        ty_collect_comments,
+        field_order,
     } = s;
+    let mut validation_code = Vec::new();
+    // required fields with their documentation
     let mut default_params = Vec::new();
     let mut default_inits = Vec::new();
-    let mut required_params_doc = String::new();
+    // assertions pointing at the field if its type doesn't implement `Serialize`
+    let mut trait_checks = Vec::new();
 
     let namespace_ser_code = if let Some(ident) = ty_collect_namespaces {
         default_inits.push(quote! {#ident: ::std::default::Default::default()});
@@ -74,69 +299,170 @@ fn expand_named_struct(s: NamedStruct, ident: &Ident) -> (TokenStream2, TokenStr
             for (name, value) in &self.#ident {
                 writer__.write_all(b" ")?;
                 writer__.write_all(&name)?;
-                writer__.write_all(b"=\"")?;
-                writer__.write_all(&value)?;
-                writer__.write_all(b"\"")?;
+                writer__.write_all(b"=")?;
+                writer__.write_all(quote__)?;
+                writer__.write_all(&::xmlib::exports::escape::escape(value))?;
+                writer__.write_all(quote__)?;
             }
         }
     } else {
         Default::default()
     };
 
+    let collect_attrs_ser_code = ty_collect_attrs.map(|(field, _)| {
+        let ident = &field.ident;
+        default_inits.push(quote! {#ident: ::std::default::Default::default()});
+        quote! {
+            for (name, value) in &self.#ident {
+                writer__.write_all(b" ")?;
+                writer__.write_all(name.as_bytes())?;
+                writer__.write_all(b"=")?;
+                writer__.write_all(quote__)?;
+                writer__.write_all(&::xmlib::exports::escape::escape(value.as_bytes()))?;
+                writer__.write_all(quote__)?;
+            }
+        }
+    });
+
+    let comments_ser_code = ty_collect_comments.as_ref().map(|ident| {
+        default_inits.push(quote! {#ident: ::std::default::Default::default()});
+        quote! {
+            for comment in &self.#ident {
+                writer__.write_comment(comment.as_bytes())?;
+            }
+        }
+    });
+
+    let mixed_ser_code = ty_mixed.map(|field| {
+        let ident = &field.ident;
+        default_inits.push(quote! {#ident: ::std::default::Default::default()});
+        if let Some(validation) = &field.validation {
+            validation_code.push(create_validation(
+                validation,
+                &quote! {self.#ident},
+                &field.ser_name,
+            ));
+        }
+        quote! {
+            ::xmlib::ser::Serialize::ser(&self.#ident, writer__)?;
+        }
+    });
+
     let mut process_field = |field: &Field| -> (_, _) {
         let ty = &field.ty;
 
-        let default = field.default.as_ref().map(|default| {
-            if let syn::Lit::Str(lit) = default {
-                match ty {
-                    syn::Type::Path(syn::TypePath { qself: None, path })
-                        if path.is_ident("String") =>
-                    {
-                        quote! {::std::string::String::from(#lit)}
-                    }
-                    _ => syn::parse2(syn::parse_str(&lit.value()).unwrap()).unwrap(),
-                }
-            } else {
-                quote! {#default}
-            }
-        });
+        let default = field.default.clone();
 
         let ident = &field.ident;
 
+        let default = if field.optional {
+            Some(quote! {::std::option::Option::None})
+        } else {
+            default
+        };
+
+        let mut checks = Vec::new();
+        if let Some(min) = field.min {
+            checks.push(quote! {self.#ident.len() < #min});
+        }
+        if let Some(max) = field.max {
+            checks.push(quote! {self.#ident.len() > #max});
+        }
+        if !checks.is_empty() {
+            let name = &field.ser_name;
+            validation_code.push(quote! {
+                if #(#checks)||* {
+                    return ::std::result::Result::Err(::xmlib::ser::Error::Validation {
+                        ty_name: ::std::string::String::from(#name),
+                        message: format!("invalid number of children: {}", self.#ident.len()),
+                    });
+                }
+            });
+        }
+
+        if let Some(validation) = &field.validation {
+            validation_code.push(create_validation(
+                validation,
+                &quote! {self.#ident},
+                &field.ser_name,
+            ));
+        }
+
         default_inits.push(
             default
                 .as_ref()
                 .map(|default| quote! {#ident: #default})
                 .unwrap_or_else(|| {
-                    required_params_doc.push_str(&format!(
-                        "\n{}: [`{}`]",
-                        ident,
-                        ty.to_token_stream()
+                    default_params.push((
+                        ident.clone(),
+                        quote! {#ident: #ty},
+                        format!("\n{}: [`{}`]", ident, ty.to_token_stream()),
                     ));
-                    default_params.push(quote! {#ident: #ty});
                     quote! {#ident}
                 }),
         );
-        let code = quote! {::xmlib::ser::Serialize::ser(&self.#ident, writer__)?;};
+        // children of `multiple` fields are serialized one by one
+        let item_ty = field.item_ty();
+        if field.with.is_none() {
+            trait_checks.push(quote_spanned! {item_ty.span()=>
+                {
+                    fn assert_impl__<W: ::std::io::Write, T: ::xmlib::ser::Serialize<W> + ?Sized>() {}
+                    assert_impl__::<W, #item_ty>();
+                }
+            });
+        }
+        if field.key.is_some() {
+            let code = quote! {
+                for item in self.#ident.values() {
+                    ::xmlib::ser::Serialize::ser(item, writer__)?;
+                }
+            };
+            return (default, code);
+        }
+        let code = match field.bool_words {
+            Some(true) => {
+                quote! {writer__.write_all(if self.#ident { b"true" } else { b"false" })?;}
+            }
+            Some(false) => quote! {writer__.write_all(if self.#ident { b"1" } else { b"0" })?;},
+            None if field.has_multiple && !field.element => quote! {
+                for item in &self.#ident {
+                    ::xmlib::ser::Serialize::ser(item, writer__)?;
+                }
+            },
+            None => ser_call(field, quote! {&self.#ident}, quote! {writer__}),
+        };
         (default, code)
     };
 
     let attr_ser_code: Vec<_> = ty_attribute
         .into_iter()
         .map(|field| {
-            let (default, code) = process_field(&field);
-            let start = proc_macro2::Literal::byte_string(format!(" {}=\"", field.name).as_bytes());
+            let (default, mut code) = process_field(&field);
+            let start =
+                proc_macro2::Literal::byte_string(format!(" {}=", field.ser_name).as_bytes());
             let ident = &field.ident;
+            // `with` modules escape the value themselves
+            if field.bool_words.is_none() && field.with.is_none() {
+                let value_code = ser_call(&field, quote! {&self.#ident}, quote! {&mut value__});
+                code = quote! {
+                    let mut value__ = writer__.text_buffer();
+                    #value_code
+                    writer__.write_attr_buffer(value__)?;
+                };
+            }
 
             let inner = quote! {
                 writer__.write_all(#start)?;
+                writer__.write_all(quote__)?;
                 #code
-                writer__.write_all(b"\"")?;
+                writer__.write_all(quote__)?;
             };
 
             if let Some(default) = default {
+                let ty = &field.ty;
                 quote! {
-                    if self.#ident != #default {
+                    let default__: #ty = #default;
+                    if self.#ident != default__ {
                         #inner
                     }
                 }
@@ -146,20 +472,166 @@ fn expand_named_struct(s: NamedStruct, ident: &Ident) -> (TokenStream2, TokenStr
         })
         .collect();
 
-    let inner_ser_code = if ty_value.is_empty() && ty_value_buf.is_none() {
-        quote! {
-            writer__.write_all(b"/>")?;
+    // whitespace would change the text of the element
+    let inline_code = if ty_value_buf.is_some() || mixed_ser_code.is_some() {
+        Some(quote! {writer__.start_inline();})
+    } else {
+        None
+    };
+
+    let inner_ser_code = if ty_value.is_empty()
+        && ty_value_buf.is_none()
+        && mixed_ser_code.is_none()
+    {
+        let end = proc_macro2::Literal::byte_string(format!("</{}>", &raw_ser_name).as_bytes());
+        match &ty_collect_comments {
+            // the element is only empty without comments
+            Some(ident) => quote! {
+                if self.#ident.is_empty() {
+                    writer__.write_all(b"/>")?;
+                    writer__.end_element(true)?;
+                } else {
+                    writer__.write_all(b">")?;
+                    #comments_ser_code
+                    writer__.end_element(false)?;
+                    writer__.write_all(#end)?;
+                }
+            },
+            None => quote! {
+                writer__.write_all(b"/>")?;
+                writer__.end_element(true)?;
+            },
         }
     } else {
         let values: Vec<_> = ty_value
             .into_iter()
-            .chain(ty_value_buf)
-            .map(|field| {
-                let (default, code) = process_field(&field);
-                let ident = field.ident;
-                if let Some(default) = default {
+            .map(|field| (field, false))
+            .chain(ty_value_buf.map(|field| (field, true)))
+            .map(|(field, is_value_buf)| {
+                let (default, mut code) = process_field(&field);
+                let ident = &field.ident;
+                if field.element {
+                    let start = match &field.namespace {
+                        Some(namespace) => {
+                            let name = proc_macro2::Literal::byte_string(
+                                format!("<{} xmlns=", field.ser_name).as_bytes(),
+                            );
+                            let namespace = proc_macro2::Literal::byte_string(namespace.as_bytes());
+                            quote! {
+                                writer__.write_all(#name)?;
+                                writer__.write_all(quote__)?;
+                                writer__.write_all(#namespace)?;
+                                writer__.write_all(quote__)?;
+                                writer__.write_all(b">")?;
+                            }
+                        }
+                        None => {
+                            let start = proc_macro2::Literal::byte_string(
+                                format!("<{}>", field.ser_name).as_bytes(),
+                            );
+                            quote! {writer__.write_all(#start)?;}
+                        }
+                    };
+                    let end = proc_macro2::Literal::byte_string(
+                        format!("</{}>", field.ser_name).as_bytes(),
+                    );
+                    // the text of the element has to be escaped like `value_buf`
+                    let item_code = ser_text(&field, quote! {item});
+                    if field.bool_words.is_none() {
+                        code = ser_text(&field, quote! {&self.#ident});
+                    }
+                    code = if field.has_multiple {
+                        quote! {
+                            for item in &self.#ident {
+                                writer__.start_element()?;
+                                #start
+                                #item_code
+                                writer__.end_element(false)?;
+                                writer__.write_all(#end)?;
+                            }
+                        }
+                    } else {
+                        quote! {
+                            writer__.start_element()?;
+                            #start
+                            #code
+                            writer__.end_element(false)?;
+                            writer__.write_all(#end)?;
+                        }
+                    };
+                }
+                if let Some(wrapper) = &field.wrapper {
+                    let start =
+                        proc_macro2::Literal::byte_string(format!("<{}>", wrapper).as_bytes());
+                    let end =
+                        proc_macro2::Literal::byte_string(format!("</{}>", wrapper).as_bytes());
+                    let empty =
+                        proc_macro2::Literal::byte_string(format!("<{}/>", wrapper).as_bytes());
+                    code = quote! {
+                        writer__.start_element()?;
+                        if self.#ident.is_empty() {
+                            writer__.write_all(#empty)?;
+                            writer__.end_element(true)?;
+                        } else {
+                            writer__.write_all(#start)?;
+                            #code
+                            writer__.end_element(false)?;
+                            writer__.write_all(#end)?;
+                        }
+                    };
+                }
+                if is_value_buf && field.has_multiple {
+                    let item_code = ser_text(&field, quote! {item});
+                    // adjacent text would be read back as one item, CDATA sections stay separate
+                    let separator = (!field.cdata).then(|| {
+                        quote! {
+                            if i__ > 0 {
+                                writer__.write_comment(b"")?;
+                            }
+                        }
+                    });
+                    code = quote! {
+                        for (i__, item) in self.#ident.iter().enumerate() {
+                            #separator
+                            #item_code
+                        }
+                    };
+                } else if field.cdata {
+                    let cdata_code = ser_call(&field, quote! {&self.#ident}, quote! {&mut cdata__});
+                    code = quote! {
+                        let mut cdata__ = writer__.text_buffer();
+                        #cdata_code
+                        writer__.write_cdata_buffer(cdata__)?;
+                    };
+                } else if is_value_buf && field.with.is_none() {
+                    // `with` modules escape the text themselves
+                    let text_code = ser_call(&field, quote! {&self.#ident}, quote! {&mut text__});
+                    code = quote! {
+                        let mut text__ = writer__.text_buffer();
+                        #text_code
+                        writer__.write_text_buffer(text__)?;
+                    };
+                }
+                if field.optional && field.nillable {
+                    let nil_code = ser_nil(&field);
+                    quote! {
+                        if ::std::option::Option::is_some(&self.#ident) {
+                            #code
+                        } else {
+                            #nil_code
+                        }
+                    }
+                } else if field.optional {
+                    quote! {
+                        if ::std::option::Option::is_some(&self.#ident) {
+                            #code
+                        }
+                    }
+                } else if let Some(default) = default {
+                    let ty = &field.ty;
                     quote! {
-                        if self.#ident != #default {
+                        let default__: #ty = #default;
+                        if self.#ident != default__ {
                             #code
                         }
                     }
@@ -173,20 +645,60 @@ fn expand_named_struct(s: NamedStruct, ident: &Ident) -> (TokenStream2, TokenStr
 
         quote! {
             writer__.write_all(b">")?;
+            #inline_code
+            #comments_ser_code
             #(#values)*
+            #mixed_ser_code
+            writer__.end_element(false)?;
             writer__.write_all(#end)?;
         }
     };
 
     let tag_start = proc_macro2::Literal::byte_string(format!("<{}", &raw_ser_name).as_bytes());
+    let namespace_attr = namespace.map(|namespace| {
+        let namespace = proc_macro2::Literal::byte_string(namespace.as_bytes());
+        quote! {
+            writer__.write_all(b" xmlns=")?;
+            writer__.write_all(quote__)?;
+            writer__.write_all(#namespace)?;
+            writer__.write_all(quote__)?;
+        }
+    });
 
     let literal_name = ident.to_string();
 
+    // the parameters of the constructor are in declaration order
+    default_params.sort_by_key(|(ident, _, _)| field_order.iter().position(|field| field == ident));
+    let required_params_doc: String = default_params
+        .iter()
+        .map(|(_, _, doc)| doc.as_str())
+        .collect();
+    let default_params = default_params.into_iter().map(|(_, param, _)| param);
+
+    let lifetime = lifetime.map(|lifetime| quote! {<#lifetime>});
+    let default_impl = derive_default.then(|| {
+        quote! {
+            #[automatically_derived]
+            impl #lifetime ::std::default::Default for #ident #lifetime {
+                fn default() -> Self {
+                    Self {
+                       #(#default_inits,)*
+                    }
+                }
+            }
+        }
+    });
     let constructor = if no_constructor {
-        quote! {}
+        quote! {
+            #default_impl
+            #schema_impl
+        }
     } else {
         quote! {
-            impl #ident {
+            #default_impl
+            #schema_impl
+
+            impl #lifetime #ident #lifetime {
                 #[doc=concat!(" Create a [`", #literal_name, "`] from required values.\n\n## Required values:", #required_params_doc)]
                 #[allow(clippy::too_many_arguments)]
                 pub fn with_default(#(#default_params,)*) -> Self {
@@ -199,10 +711,25 @@ fn expand_named_struct(s: NamedStruct, ident: &Ident) -> (TokenStream2, TokenStr
     };
 
     let inner = quote! {
+        #(#trait_checks)*
+        #(#validation_code)*
+
+        let quote__ = writer__.quote();
+        writer__.start_element()?;
         writer__.write_all(#tag_start)?;
+        if let ::std::option::Option::Some((key__, value__)) = attr__ {
+            writer__.write_all(b" ")?;
+            writer__.write_all(key__)?;
+            writer__.write_all(b"=")?;
+            writer__.write_all(quote__)?;
+            writer__.write_attr_value(value__)?;
+            writer__.write_all(quote__)?;
+        }
+        #namespace_attr
 
         #(#attr_ser_code)*
         #namespace_ser_code
+        #collect_attrs_ser_code
         #inner_ser_code
 
         Ok(())
@@ -210,3 +737,111 @@ fn expand_named_struct(s: NamedStruct, ident: &Ident) -> (TokenStream2, TokenStr
 
     (constructor, inner)
 }
+
+// Writes the empty element of a nillable field with `xsi:nil="true"`.
+// The name and namespace of `value` fields are only known from the type.
+fn ser_nil(field: &Field) -> TokenStream2 {
+    let start = if field.element {
+        let start = proc_macro2::Literal::byte_string(format!("<{}", field.ser_name).as_bytes());
+        let namespace = field.namespace.as_ref().map(|namespace| {
+            let namespace = proc_macro2::Literal::byte_string(namespace.as_bytes());
+            quote! {
+                writer__.write_all(b" xmlns=")?;
+                writer__.write_all(quote__)?;
+                writer__.write_all(#namespace)?;
+                writer__.write_all(quote__)?;
+            }
+        });
+        quote! {
+            writer__.write_all(#start)?;
+            #namespace
+        }
+    } else {
+        let ty = &field.ty;
+        quote! {
+            let name__ = <#ty as ::xmlib::ser::Serialize<W>>::element_name().ok_or_else(|| {
+                ::xmlib::ser::Error::Unrepresentable {
+                    ty_name: ::std::string::String::from(::std::any::type_name::<#ty>()),
+                    reason: ::std::string::String::from("the nil element has no name"),
+                }
+            })?;
+            writer__.write_all(b"<")?;
+            writer__.write_all(name__)?;
+            if let ::std::option::Option::Some(namespace__) =
+                <#ty as ::xmlib::ser::Serialize<W>>::element_namespace()
+            {
+                writer__.write_all(b" xmlns=")?;
+                writer__.write_all(quote__)?;
+                writer__.write_all(namespace__)?;
+                writer__.write_all(quote__)?;
+            }
+        }
+    };
+    quote! {
+        writer__.start_element()?;
+        #start
+        writer__.write_all(b" xmlns:xsi=")?;
+        writer__.write_all(quote__)?;
+        writer__.write_all(::xmlib::de::XSI_NAMESPACE)?;
+        writer__.write_all(quote__)?;
+        writer__.write_all(b" xsi:nil=")?;
+        writer__.write_all(quote__)?;
+        writer__.write_all(b"true")?;
+        writer__.write_all(quote__)?;
+        writer__.write_all(b"/>")?;
+        writer__.end_element(true)?;
+    }
+}
+
+// Serializes the value with the `with` module of the field, in the radix or float format of the
+// field or with the `Serialize` trait
+fn ser_call(field: &Field, value: TokenStream2, writer: TokenStream2) -> TokenStream2 {
+    if let Some(precision) = field.float_format {
+        let precision = match precision {
+            Some(precision) => quote! {::std::option::Option::Some(#precision)},
+            None => quote! {::std::option::Option::None},
+        };
+        return quote! {::xmlib::ser::FormatFloat::ser_formatted(#value, #precision, #writer)?;};
+    }
+    match (&field.with, field.radix) {
+        (Some(with), _) => quote! {#with::ser(#value, #writer)?;},
+        (None, Some(radix)) => {
+            quote! {::xmlib::types::RadixInteger::ser_radix(#value, #radix, #writer)?;}
+        }
+        (None, None) => quote! {::xmlib::ser::Serialize::ser(#value, #writer)?;},
+    }
+}
+
+// Serializes the value of a field as escaped text or as CDATA section
+fn ser_text(field: &Field, value: TokenStream2) -> TokenStream2 {
+    if field.cdata {
+        let cdata_code = ser_call(field, value, quote! {&mut cdata__});
+        quote! {
+            let mut cdata__ = writer__.text_buffer();
+            #cdata_code
+            writer__.write_cdata_buffer(cdata__)?;
+        }
+    } else if field.with.is_none() {
+        let text_code = ser_call(field, value, quote! {&mut text__});
+        quote! {
+            let mut text__ = writer__.text_buffer();
+            #text_code
+            writer__.write_text_buffer(text__)?;
+        }
+    } else {
+        // `with` modules escape the text themselves
+        ser_call(field, value, quote! {writer__})
+    }
+}
+
+fn create_validation(validation: &Validation, value: &TokenStream2, ty_name: &str) -> TokenStream2 {
+    let func = &validation.func;
+    quote! {
+        if let ::std::result::Result::Err(e) = #func(&#value) {
+            return ::std::result::Result::Err(::xmlib::ser::Error::Validation {
+                ty_name: ::std::string::String::from(#ty_name),
+                message: format!("{:?}", e),
+            });
+        }
+    }
+}
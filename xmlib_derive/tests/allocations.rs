@@ -0,0 +1,66 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use xmlib_derive::Deserialize;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[derive(Deserialize)]
+struct Item {
+    #[xmlib(element)]
+    id: u32,
+    // empty in the input, so only the buffer of the wrapper could allocate
+    #[xmlib(element, multiple, rename = "code", wrapper = "codes")]
+    codes: Vec<u32>,
+}
+
+#[derive(Deserialize)]
+struct Items {
+    #[xmlib(value, multiple)]
+    items: Vec<Item>,
+}
+
+fn count_allocations(items: usize) -> usize {
+    let input = format!(
+        "<items>{}</items>",
+        "<item><id>1</id><codes></codes></item>".repeat(items)
+    );
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let parsed: Items = xmlib::de::from_str(&input).unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    assert_eq!(parsed.items.len(), items);
+    assert!(parsed
+        .items
+        .iter()
+        .all(|item| item.id == 1 && item.codes.is_empty()));
+    allocations
+}
+
+// the buffers of the reader are reused for every item, so only the `Vec` of the items grows
+#[test]
+fn buffers_are_reused() {
+    let few = count_allocations(100);
+    let many = count_allocations(10_000);
+    assert!(
+        many - few < 100,
+        "{} allocations for 100 items, {} for 10000",
+        few,
+        many
+    );
+}
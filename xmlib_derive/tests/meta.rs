@@ -0,0 +1,88 @@
+use xmlib::meta::{AttributeSchema, ChildSchema, XmlSchema};
+use xmlib_derive::Xml;
+
+#[derive(Xml)]
+#[xmlib(rename = "author")]
+struct Author {
+    name: String,
+}
+
+#[derive(Xml)]
+#[xmlib(reflect, no_constructor)]
+struct Book<'a> {
+    #[xmlib(rename = "isbn-13", borrow)]
+    isbn: &'a str,
+    #[xmlib(default = 1)]
+    edition: u32,
+    #[xmlib(default = "en")]
+    language: String,
+    #[xmlib(default)]
+    rating: Option<f32>,
+    #[xmlib(value, multiple, min = 1, wrapper = "authors")]
+    authors: Vec<Author>,
+    #[xmlib(element, namespace = "urn:notes")]
+    note: Option<String>,
+    #[xmlib(element, multiple)]
+    tags: [String; 2],
+    #[xmlib(value_buf)]
+    summary: String,
+}
+
+#[test]
+fn named_struct_schema() {
+    let attr = |name: &'static [u8], ty, optional, default| AttributeSchema {
+        name,
+        ty,
+        optional,
+        default,
+    };
+    let schema = Book::SCHEMA;
+    assert_eq!(schema.name, b"book");
+    assert_eq!(schema.namespace, None);
+    assert_eq!(
+        schema.attributes,
+        [
+            attr(b"isbn-13", "&'a str", false, None),
+            attr(b"edition", "u32", true, Some("1")),
+            attr(b"language", "String", true, Some("en")),
+            attr(b"rating", "f32", true, None),
+        ]
+    );
+    assert_eq!(
+        schema.children,
+        [
+            ChildSchema {
+                name: b"author",
+                namespace: None,
+                ty: "Author",
+                text_only: false,
+                min_occurs: 1,
+                max_occurs: None,
+                wrapper: Some(b"authors"),
+                default: None,
+            },
+            ChildSchema {
+                name: b"note",
+                namespace: Some(b"urn:notes"),
+                ty: "String",
+                text_only: true,
+                min_occurs: 0,
+                max_occurs: Some(1),
+                wrapper: None,
+                default: None,
+            },
+            ChildSchema {
+                name: b"tags",
+                namespace: None,
+                ty: "String",
+                text_only: true,
+                min_occurs: 2,
+                max_occurs: Some(2),
+                wrapper: None,
+                default: None,
+            },
+        ]
+    );
+    assert_eq!(schema.text, Some("String"));
+    assert!(!schema.mixed);
+}
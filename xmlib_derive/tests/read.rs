@@ -35,10 +35,11 @@ fn deser_newtype() {
     assert!(NewType::de_buf(&b"e"[..]).is_err());
 }
 
+#[allow(clippy::len_zero)]
 fn read_struct<'a, T: DeserializeElement<std::io::BufReader<&'a [u8]>>>(
     input: &'a [u8],
 ) -> Option<T> {
-    let mut reader = xmlib::de::XmlReader::new(std::io::BufReader::new(input));
+    let mut reader = xmlib::de::XmlReader::from_bytes(input);
 
     use xmlib::exports::events::Event;
     let mut buf = Vec::with_capacity(32);
@@ -257,3 +258,2596 @@ fn namespaces() {
     assert_eq!(s.a, 1);
     assert_eq!(s.i.b, 42);
 }
+
+#[test]
+fn shared_pointers() {
+    use std::{rc::Rc, sync::Arc};
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Struct {
+        a: Rc<u8>,
+        #[xmlib(value)]
+        inner: Arc<InnerStruct>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct InnerStruct {
+        b: Arc<String>,
+    }
+
+    let s = Struct {
+        a: Rc::new(1),
+        inner: Arc::new(InnerStruct {
+            b: Arc::new(String::from("Hi")),
+        }),
+    };
+
+    let serialized = xmlib::ser::write_to_string(&s).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<struct a="1"><innerStruct b="Hi"/></struct>"#
+    );
+    let deserialized: Struct = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, s);
+}
+
+#[test]
+fn value_buf_enums_and_newtypes() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    enum Kind {
+        Circle,
+        #[xmlib(rename = "rect")]
+        Rectangle,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    enum Size {
+        Number(u32),
+        Text(String),
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Id(u64);
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Maybe(Option<u8>);
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Shape {
+        #[xmlib(value_buf)]
+        kind: Kind,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Measure {
+        #[xmlib(value_buf)]
+        size: Size,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Item {
+        #[xmlib(element)]
+        maybe: Maybe,
+        #[xmlib(value_buf)]
+        id: Id,
+    }
+
+    for (shape, serialized) in [
+        (Shape { kind: Kind::Circle }, "<shape>circle</shape>"),
+        (
+            Shape {
+                kind: Kind::Rectangle,
+            },
+            "<shape>rect</shape>",
+        ),
+    ] {
+        assert_eq!(xmlib::ser::write_to_string(&shape).unwrap(), serialized);
+        assert_eq!(xmlib::de::from_str::<Shape>(serialized).unwrap(), shape);
+    }
+    // surrounding whitespace is part of the text
+    assert!(xmlib::de::from_str::<Shape>("<shape> circle </shape>").is_err());
+    assert!(xmlib::de::from_str::<Shape>("<shape>square</shape>").is_err());
+
+    for (measure, serialized) in [
+        (
+            Measure {
+                size: Size::Number(3),
+            },
+            "<measure>3</measure>",
+        ),
+        (
+            Measure {
+                size: Size::Text(String::from("big")),
+            },
+            "<measure>big</measure>",
+        ),
+    ] {
+        assert_eq!(xmlib::ser::write_to_string(&measure).unwrap(), serialized);
+        assert_eq!(xmlib::de::from_str::<Measure>(serialized).unwrap(), measure);
+    }
+
+    let item = Item {
+        maybe: Maybe(Some(7)),
+        id: Id(42),
+    };
+    let serialized = xmlib::ser::write_to_string(&item).unwrap();
+    assert_eq!(serialized, "<item><maybe>7</maybe>42</item>");
+    assert_eq!(xmlib::de::from_str::<Item>(&serialized).unwrap(), item);
+}
+
+#[test]
+fn transparent() {
+    fn is_positive(value: &f64) -> Result<(), &'static str> {
+        if *value > 0.0 {
+            Ok(())
+        } else {
+            Err("not positive")
+        }
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(transparent)]
+    struct Meters {
+        #[xmlib(validate = "is_positive")]
+        value: f64,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Road {
+        length: Meters,
+        #[xmlib(element)]
+        width: Meters,
+    }
+
+    let road = Road {
+        length: Meters { value: 1.5 },
+        width: Meters { value: 3.0 },
+    };
+    let serialized = xmlib::ser::write_to_string(&road).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<road length="1.5"><width>3.0</width></road>"#
+    );
+    assert_eq!(xmlib::de::from_str::<Road>(&serialized).unwrap(), road);
+    assert_eq!(Meters::de_buf(b"2").unwrap(), Meters { value: 2.0 });
+
+    assert!(Meters::de_buf(b"-1").is_err());
+    let road = Road {
+        length: Meters { value: -1.0 },
+        width: Meters { value: 3.0 },
+    };
+    assert!(xmlib::ser::write_to_string(&road).is_err());
+}
+
+#[test]
+fn value_buf_split_text() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Struct {
+        #[xmlib(value_buf)]
+        b: String,
+    }
+
+    let input: Vec<u8> = br#"<struct>Hello &amp; World</struct>"#.to_vec();
+    let s = read_struct(&input);
+    assert_eq!(
+        s,
+        Some(Struct {
+            b: String::from("Hello & World"),
+        })
+    );
+
+    let input: Vec<u8> =
+        br#"<struct>Hello <!-- comment -->&amp;<![CDATA[ <World>]]></struct>"#.to_vec();
+    let s = read_struct(&input);
+    assert_eq!(
+        s,
+        Some(Struct {
+            b: String::from("Hello & <World>"),
+        })
+    );
+}
+
+#[test]
+fn finalize() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[xmlib(finalize = "check_range")]
+    struct Range {
+        min: u8,
+        max: u8,
+    }
+
+    fn check_range(range: &mut Range) -> Result<(), String> {
+        if range.min <= range.max {
+            Ok(())
+        } else {
+            Err(format!("{} > {}", range.min, range.max))
+        }
+    }
+
+    let range: Range = xmlib::de::from_str(r#"<range min="1" max="2"/>"#).unwrap();
+    assert_eq!(range, Range { min: 1, max: 2 });
+
+    let err = xmlib::de::from_str::<Range>(r#"<range min="3" max="2"/>"#).unwrap_err();
+    assert_eq!(err.ty_name, "range");
+    assert!(matches!(err.kind, xmlib::ErrorKind::Validation(_)));
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[xmlib(finalize = "normalize")]
+    struct Name(String);
+
+    fn normalize(name: &mut Name) -> Result<(), ()> {
+        name.0 = name.0.to_lowercase();
+        Ok(())
+    }
+
+    assert_eq!(Name::de_buf(b"HeLLo").unwrap(), Name(String::from("hello")));
+}
+
+#[test]
+fn multiple_cardinality() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Struct {
+        #[xmlib(value, multiple, min = 1, max = 2)]
+        i: Vec<InnerStruct>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct InnerStruct {
+        a: u8,
+    }
+
+    let s: Struct =
+        xmlib::de::from_str(r#"<struct><innerStruct a="1"/><innerStruct a="2"/></struct>"#)
+            .unwrap();
+    assert_eq!(s.i.len(), 2);
+
+    let err = xmlib::de::from_str::<Struct>(r#"<struct></struct>"#).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        xmlib::ErrorKind::Cardinality {
+            got: 0,
+            min: Some(1),
+            max: Some(2),
+            ..
+        }
+    ));
+
+    let err = xmlib::de::from_str::<Struct>(
+        r#"<struct><innerStruct a="1"/><innerStruct a="2"/><innerStruct a="3"/></struct>"#,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err.kind,
+        xmlib::ErrorKind::Cardinality { got: 3, .. }
+    ));
+
+    assert!(xmlib::ser::write_to_string(Struct { i: Vec::new() }).is_err());
+}
+
+#[test]
+fn optional_value() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Struct {
+        a: u8,
+        #[xmlib(value)]
+        i: Option<InnerStruct>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct InnerStruct {
+        a: u8,
+    }
+
+    let input: Vec<u8> = br#"<struct a="1"></struct>"#.to_vec();
+    let s = read_struct(&input);
+    assert_eq!(s, Some(Struct { a: 1, i: None }));
+
+    let input: Vec<u8> = br#"<struct a="1"><innerStruct a="2"/></struct>"#.to_vec();
+    let s = read_struct(&input);
+    assert_eq!(
+        s,
+        Some(Struct {
+            a: 1,
+            i: Some(InnerStruct { a: 2 })
+        })
+    );
+
+    let s = Struct::with_default(1);
+    assert_eq!(
+        xmlib::ser::write_to_string(&s).unwrap(),
+        r#"<struct a="1"></struct>"#
+    );
+}
+
+#[test]
+fn wrapper() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Struct {
+        a: u8,
+        #[xmlib(value, multiple, wrapper = "items")]
+        items: Vec<InnerStruct>,
+        #[xmlib(value)]
+        other: InnerStruct2,
+        #[xmlib(value, multiple, wrapper = "others")]
+        others: Vec<InnerStruct2>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct InnerStruct {
+        a: u8,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct InnerStruct2 {
+        b: u8,
+    }
+
+    let s = Struct {
+        a: 1,
+        items: vec![InnerStruct { a: 2 }, InnerStruct { a: 3 }],
+        other: InnerStruct2 { b: 4 },
+        others: Vec::new(),
+    };
+
+    let serialized = xmlib::ser::write_to_string(&s).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<struct a="1"><items><innerStruct a="2"/><innerStruct a="3"/></items><innerStruct2 b="4"/><others/></struct>"#
+    );
+    let deserialized: Struct = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, s);
+
+    let input = r#"<struct a="1"><others> <innerStruct2 b="5"/> </others><innerStruct2 b="4"/><items><innerStruct a="2"/></items></struct>"#;
+    let deserialized: Struct = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        deserialized,
+        Struct {
+            a: 1,
+            items: vec![InnerStruct { a: 2 }],
+            other: InnerStruct2 { b: 4 },
+            others: vec![InnerStruct2 { b: 5 }],
+        }
+    );
+}
+
+#[test]
+fn list() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct InnerStruct {
+        a: u8,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(list)]
+    struct List(Vec<InnerStruct>);
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(list, rename = "items")]
+    struct Renamed(Vec<InnerStruct>);
+
+    let list = List(vec![InnerStruct { a: 1 }, InnerStruct { a: 2 }]);
+    let serialized = xmlib::ser::write_to_string(&list).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<list><innerStruct a="1"/><innerStruct a="2"/></list>"#
+    );
+    let deserialized: List = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, list);
+
+    let renamed = Renamed(Vec::new());
+    let serialized = xmlib::ser::write_to_string(&renamed).unwrap();
+    assert_eq!(serialized, r#"<items/>"#);
+    let deserialized: Renamed = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, renamed);
+    let deserialized: Renamed = xmlib::de::from_str("<items></items>").unwrap();
+    assert_eq!(deserialized, renamed);
+
+    assert!(xmlib::de::from_str::<Renamed>("<items><list/></items>").is_err());
+}
+
+#[test]
+fn namespaces_roundtrip() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Struct {
+        a: u8,
+        #[xmlib(collect_namespaces)]
+        namespaces: CollectNamespaces,
+    }
+
+    let input = r#"<struct xmlns:r="a&amp;b" xmlns="c&lt;d" a="1"/>"#;
+    let s: Struct = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        s.namespaces,
+        vec![
+            (b"xmlns:r".to_vec(), b"a&b".to_vec()),
+            (b"xmlns".to_vec(), b"c<d".to_vec())
+        ]
+    );
+
+    let serialized = xmlib::ser::write_to_string(&s).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<struct a="1" xmlns:r="a&amp;b" xmlns="c&lt;d"/>"#
+    );
+    let deserialized: Struct = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, s);
+}
+
+#[test]
+fn ser_deser_vec_count() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    pub struct Foo {
+        inner: u8,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Bar(pub Vec<Foo>);
+
+    xmlib::ser_deser_vec!(Bar, b"bar", b"foo", count = b"count");
+
+    let bar = Bar(vec![Foo { inner: 13 }, Foo { inner: 42 }]);
+    let serialized = xmlib::ser::write_to_string(&bar).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<bar count="2"><foo inner="13"/><foo inner="42"/></bar>"#
+    );
+    let deserialized: Bar = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, bar);
+
+    let mut writer = xmlib::ser::XmlWriter::new(Vec::new())
+        .unwrap()
+        .with_quote_style(xmlib::ser::QuoteStyle::Single);
+    xmlib::ser::Serialize::ser(&bar, &mut writer).unwrap();
+    assert_eq!(
+        writer.into_inner(),
+        &b"<bar count='2'><foo inner='13'/><foo inner='42'/></bar>"[..]
+    );
+
+    let deserialized: Bar = xmlib::de::from_str(r#"<bar><foo inner="13"/></bar>"#).unwrap();
+    assert_eq!(deserialized, Bar(vec![Foo { inner: 13 }]));
+
+    let err = xmlib::de::from_str::<Bar>(r#"<bar count="3"><foo inner="13"/></bar>"#).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        xmlib::ErrorKind::Cardinality {
+            got: 1,
+            min: Some(3),
+            max: Some(3),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn ser_deser_vec_empty() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    pub struct Foo {
+        inner: u8,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Bar(pub Vec<Foo>);
+
+    xmlib::ser_deser_vec!(Bar, b"bar", b"foo");
+
+    fn read(input: &str, expand_empty_elements: bool) -> Bar {
+        let mut reader =
+            xmlib::exports::Reader::from_reader(std::io::BufReader::new(input.as_bytes()));
+        reader.expand_empty_elements(expand_empty_elements);
+        xmlib::de::deserialize_single_struct(xmlib::de::XmlReader::from_xml_reader(reader)).unwrap()
+    }
+
+    let serialized = xmlib::ser::write_to_string(Bar(Vec::new())).unwrap();
+    assert_eq!(serialized, "<bar/>");
+
+    let bar = Bar(vec![Foo { inner: 13 }, Foo { inner: 42 }]);
+    let serialized_bar = xmlib::ser::write_to_string(&bar).unwrap();
+
+    for expand_empty_elements in [true, false] {
+        assert_eq!(read(&serialized, expand_empty_elements), Bar(Vec::new()));
+        assert_eq!(read("<bar></bar>", expand_empty_elements), Bar(Vec::new()));
+        assert_eq!(read(&serialized_bar, expand_empty_elements), bar);
+    }
+}
+
+#[test]
+fn ser_deser_vec_namespaced() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    pub struct Foo {
+        inner: u8,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "foo", namespace = "urn:ns")]
+    pub struct NsFoo {
+        inner: u8,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Bar(pub Vec<Foo>);
+    xmlib::ser_deser_vec!(Bar, b"bar", b"foo");
+
+    #[derive(Debug, PartialEq)]
+    pub struct NsBar(pub Vec<NsFoo>);
+    xmlib::ser_deser_vec!(NsBar, b"bar", inner = NsFoo, count = b"count");
+
+    let input = r#"<bar xmlns:ns="urn:ns"><ns:foo inner="1"/><foo inner="2"/></bar>"#;
+    let deserialized: Bar = xmlib::de::from_str(input).unwrap();
+    assert_eq!(deserialized, Bar(vec![Foo { inner: 1 }, Foo { inner: 2 }]));
+
+    let input =
+        r#"<bar xmlns:ns="urn:ns"><ns:foo inner="1"/><foo xmlns="urn:ns" inner="2"/></bar>"#;
+    let deserialized: NsBar = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        deserialized,
+        NsBar(vec![NsFoo { inner: 1 }, NsFoo { inner: 2 }])
+    );
+
+    // the child is in a different namespace
+    let input = r#"<bar xmlns:o="urn:other"><o:foo inner="1"/></bar>"#;
+    assert!(xmlib::de::from_str::<NsBar>(input).is_err());
+
+    let serialized = xmlib::ser::write_to_string(&deserialized).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<bar count="2"><foo xmlns="urn:ns" inner="1"/><foo xmlns="urn:ns" inner="2"/></bar>"#
+    );
+    let err =
+        xmlib::de::from_str::<NsBar>(r#"<bar count="3"><foo xmlns="urn:ns" inner="1"/></bar>"#)
+            .unwrap_err();
+    assert!(matches!(
+        err.kind,
+        xmlib::ErrorKind::Cardinality { ref name, got: 1, .. } if name == "foo"
+    ));
+}
+
+#[test]
+fn bom_and_leading_whitespace() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Rectangle {
+        width: u32,
+        height: u32,
+    }
+
+    let expected = Rectangle {
+        width: 13,
+        height: 42,
+    };
+    let inputs = [
+        "\u{feff}<rectangle width=\"13\" height=\"42\"/>",
+        "\u{feff}<?xml version=\"1.0\"?>\n<rectangle width=\"13\" height=\"42\"/>\n",
+        "\n  <?xml-stylesheet href=\"a.css\"?>\n<!-- comment -->\n<rectangle width=\"13\" height=\"42\"/>",
+    ];
+    for input in inputs {
+        assert_eq!(xmlib::de::from_str::<Rectangle>(input).unwrap(), expected);
+        let reader = xmlib::de::XmlReader::new(input.as_bytes());
+        assert_eq!(
+            xmlib::de::deserialize_single_struct::<_, Rectangle>(reader).unwrap(),
+            expected
+        );
+    }
+
+    assert!(xmlib::de::from_str::<Rectangle>("\u{feff}  ").is_err());
+}
+
+#[test]
+fn element() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "rect")]
+    struct Rectangle {
+        #[xmlib(element)]
+        width: u32,
+        #[xmlib(element, rename = "h", validate = "is_positive")]
+        height: i32,
+        #[xmlib(element, default = 1)]
+        depth: u32,
+        #[xmlib(element)]
+        name: Option<String>,
+        #[xmlib(element)]
+        label: String,
+    }
+
+    fn is_positive(v: &i32) -> Result<(), i32> {
+        if *v > 0 {
+            Ok(())
+        } else {
+            Err(*v)
+        }
+    }
+
+    let input = r#"<rect><width>13</width><h>42</h><name>a &amp; b</name><label/></rect>"#;
+    let rect: Rectangle = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        rect,
+        Rectangle {
+            width: 13,
+            height: 42,
+            depth: 1,
+            name: Some(String::from("a & b")),
+            label: String::new(),
+        }
+    );
+
+    let rect = Rectangle {
+        name: None,
+        depth: 2,
+        ..rect
+    };
+    let serialized = xmlib::ser::write_to_string(&rect).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<rect><width>13</width><h>42</h><depth>2</depth><label></label></rect>"#
+    );
+    let deserialized: Rectangle = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, rect);
+
+    assert!(
+        xmlib::de::from_str::<Rectangle>(r#"<rect><width>13</width><h>-1</h><label/></rect>"#)
+            .is_err()
+    );
+    assert!(xmlib::de::from_str::<Rectangle>(r#"<rect><h>1</h><label/></rect>"#).is_err());
+}
+
+#[test]
+fn element_split_text() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Query {
+        #[xmlib(element)]
+        filter: String,
+        #[xmlib(element, multiple)]
+        terms: Vec<String>,
+    }
+
+    let input = "<query><filter>a <![CDATA[<]]> b<!-- c --> &amp;&amp; d</filter>\
+                 <terms><![CDATA[e]]>f</terms><terms>g<![CDATA[]]>h</terms></query>";
+    let query: Query = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        query,
+        Query {
+            filter: String::from("a < b && d"),
+            terms: vec![String::from("ef"), String::from("gh")],
+        }
+    );
+
+    assert!(xmlib::de::from_str::<Query>("<query><filter>a<b/></filter></query>").is_err());
+}
+
+#[test]
+fn integer_overflow() {
+    assert_eq!(u8::de_buf(b"255").unwrap(), 255);
+    assert!(matches!(
+        u8::de_buf(b"300").unwrap_err().kind,
+        xmlib::ErrorKind::Overflow { ty, value } if ty == "u8" && value == "300"
+    ));
+    assert!(matches!(
+        i8::de_buf(b"-129").unwrap_err().kind,
+        xmlib::ErrorKind::Overflow { .. }
+    ));
+    assert!(matches!(
+        u64::de_buf(b"99999999999999999999").unwrap_err().kind,
+        xmlib::ErrorKind::Overflow { .. }
+    ));
+    assert!(matches!(
+        u8::de_buf(b"3a").unwrap_err().kind,
+        xmlib::ErrorKind::InvalidType(_)
+    ));
+}
+
+#[test]
+fn integer_whitespace_and_sign() {
+    use xmlib::de::lenient_number;
+
+    // integers are strict by default
+    for input in [&b" 42"[..], b"42 ", b"+3"] {
+        assert!(u32::de_buf(input).is_err());
+    }
+
+    assert_eq!(u32::de_buf(lenient_number(b" 42")).unwrap(), 42);
+    assert_eq!(u32::de_buf(lenient_number(b"42 ")).unwrap(), 42);
+    assert_eq!(i32::de_buf(lenient_number(b"\t-7\n")).unwrap(), -7);
+    assert_eq!(u8::de_buf(lenient_number(b"+3")).unwrap(), 3);
+    assert_eq!(i8::de_buf(lenient_number(b" +3 ")).unwrap(), 3);
+    assert_eq!(u8::de_buf(lenient_number(b"+0")).unwrap(), 0);
+    assert_eq!(i64::de_buf(lenient_number(b"+0")).unwrap(), 0);
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Point {
+        #[xmlib(lenient)]
+        x: i32,
+        #[xmlib(lenient)]
+        y: u32,
+        #[xmlib(element, lenient)]
+        z: u8,
+    }
+    let point: Point = xmlib::de::from_str(r#"<point x=" -1 " y="+2"><z> 3 </z></point>"#).unwrap();
+    assert_eq!(point, Point { x: -1, y: 2, z: 3 });
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Strict {
+        y: u32,
+    }
+    assert!(xmlib::de::from_str::<Strict>(r#"<strict y="+2"/>"#).is_err());
+
+    for input in [&b"4 2"[..], b"++1", b"+-1", b"+ 1"] {
+        assert!(u32::de_buf(lenient_number(input)).is_err());
+    }
+    let err = u8::de_buf(b"3a").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        u8::de_buf(lenient_number(b" 3a ")).unwrap_err().to_string()
+    );
+    assert!(matches!(
+        err.kind,
+        xmlib::ErrorKind::InvalidType(message) if message == "read only 1 of 2 bytes in 3a"
+    ));
+}
+
+#[test]
+fn trimmed_attributes() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Kind {
+        Small,
+        Large,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        #[xmlib(trim)]
+        count: u32,
+        #[xmlib(trim)]
+        visible: bool,
+        #[xmlib(trim)]
+        ratio: f32,
+        #[xmlib(trim)]
+        kind: Kind,
+        #[xmlib(trim, default)]
+        name: String,
+    }
+
+    let input =
+        "<item count=\" 42 \" visible=\"\ttrue \" ratio=\" 0.5\" kind=\"large\n\" name=\" a b \"/>";
+    let item: Item = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        item,
+        Item {
+            count: 42,
+            visible: true,
+            ratio: 0.5,
+            kind: Kind::Large,
+            name: String::from("a b"),
+        }
+    );
+
+    #[derive(Debug, Deserialize)]
+    struct Untrimmed {
+        #[allow(dead_code)]
+        visible: bool,
+    }
+    assert!(xmlib::de::from_str::<Untrimmed>(r#"<untrimmed visible=" true "/>"#).is_err());
+}
+
+#[test]
+fn nillable() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Inner {
+        size: u32,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Outer {
+        #[xmlib(value, nillable)]
+        inner: Option<Inner>,
+        #[xmlib(element, nillable)]
+        label: Option<String>,
+        #[xmlib(element)]
+        comment: Option<String>,
+    }
+
+    let outer = Outer {
+        inner: None,
+        label: None,
+        comment: None,
+    };
+    let serialized = xmlib::ser::write_to_string(&outer).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<outer><inner xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:nil="true"/><label xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:nil="true"/></outer>"#
+    );
+    let deserialized: Outer = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, outer);
+
+    let outer = Outer {
+        inner: Some(Inner { size: 3 }),
+        label: Some(String::from("a")),
+        comment: None,
+    };
+    let serialized = xmlib::ser::write_to_string(&outer).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<outer><inner size="3"/><label>a</label></outer>"#
+    );
+    let deserialized: Outer = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, outer);
+
+    // the prefix is declared by a parent and the nil element may have content
+    let input = r#"<outer xmlns:i="http://www.w3.org/2001/XMLSchema-instance"><inner i:nil="1"></inner><label i:nil="true">ignored</label></outer>"#;
+    let deserialized: Outer = xmlib::de::from_str(input).unwrap();
+    assert_eq!(deserialized.inner, None);
+    assert_eq!(deserialized.label, None);
+
+    // an unbound prefix isn't `xsi:nil`
+    let input = r#"<outer><label xsi:nil="true">a</label></outer>"#;
+    let deserialized: Outer = xmlib::de::from_str(input).unwrap();
+    assert_eq!(deserialized.label, Some(String::from("a")));
+}
+
+#[test]
+fn nillable_namespace() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(namespace = "urn:inner")]
+    struct Inner {
+        size: u32,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Outer {
+        #[xmlib(value, nillable)]
+        inner: Option<Inner>,
+    }
+
+    let outer = Outer { inner: None };
+    let serialized = xmlib::ser::write_to_string(&outer).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<outer><inner xmlns="urn:inner" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:nil="true"/></outer>"#
+    );
+    let deserialized: Outer = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, outer);
+
+    let outer = Outer {
+        inner: Some(Inner { size: 3 }),
+    };
+    let serialized = xmlib::ser::write_to_string(&outer).unwrap();
+    let deserialized: Outer = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, outer);
+}
+
+#[test]
+fn radix() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Register {
+        #[xmlib(radix = 16)]
+        mask: u64,
+        #[xmlib(radix = 2, default)]
+        flags: u8,
+        #[xmlib(element, multiple, radix = 8, rename = "mode")]
+        modes: Vec<u16>,
+        #[xmlib(value_buf, radix = 16)]
+        value: i32,
+    }
+
+    let register = Register {
+        mask: u64::MAX,
+        flags: 0b101,
+        modes: vec![0o755, 0o644],
+        value: -255,
+    };
+    let serialized = xmlib::ser::write_to_string(&register).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<register mask="FFFFFFFFFFFFFFFF" flags="101"><mode>755</mode><mode>644</mode>-FF</register>"#
+    );
+    let deserialized: Register = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, register);
+
+    let input = r#"<register mask="0x1f" flags="0b1"><mode>0o7</mode>0xA</register>"#;
+    let deserialized: Register = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        deserialized,
+        Register {
+            mask: 31,
+            flags: 1,
+            modes: vec![7],
+            value: 10,
+        }
+    );
+
+    let err = xmlib::de::from_str::<Register>(r#"<register mask="1G">0</register>"#).unwrap_err();
+    assert!(
+        matches!(err.kind, xmlib::ErrorKind::InvalidType(message) if message.contains("radix 16"))
+    );
+}
+
+#[test]
+fn element_multiple() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Book {
+        #[xmlib(element, multiple, rename = "author")]
+        authors: Vec<String>,
+        #[xmlib(value)]
+        inner: InnerStruct,
+        #[xmlib(element, multiple, rename = "page")]
+        pages: Vec<u32>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct InnerStruct {
+        a: u8,
+    }
+
+    let input = r#"<book><author>Jane</author><page>1</page><innerStruct a="1"/><author>Bob &amp; Alice</author><page>42</page></book>"#;
+    let book: Book = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        book,
+        Book {
+            authors: vec![String::from("Jane"), String::from("Bob & Alice")],
+            inner: InnerStruct { a: 1 },
+            pages: vec![1, 42],
+        }
+    );
+
+    let book = Book {
+        authors: vec![String::from("Jane"), String::from("Bob")],
+        inner: InnerStruct { a: 1 },
+        pages: Vec::new(),
+    };
+    let serialized = xmlib::ser::write_to_string(&book).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<book><author>Jane</author><author>Bob</author><innerStruct a="1"/></book>"#
+    );
+    let deserialized: Book = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, book);
+}
+
+#[test]
+fn root_with_other_name() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Point {
+        x: i32,
+        #[xmlib(value)]
+        inner: Option<InnerStruct>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct InnerStruct {
+        a: u8,
+    }
+
+    let input = r#"<envelope><header/><pos x="-3"><innerStruct a="1"/></pos></envelope>"#;
+    let point: Point = xmlib::de::from_str_at(input, b"pos").unwrap();
+    assert_eq!(
+        point,
+        Point {
+            x: -3,
+            inner: Some(InnerStruct { a: 1 })
+        }
+    );
+
+    let point: Point = xmlib::de::from_str_at(r#"<pos x="7"></pos>"#, b"pos").unwrap();
+    assert_eq!(point, Point { x: 7, inner: None });
+
+    assert!(xmlib::de::from_str_at::<Point>(r#"<point x="7"/>"#, b"pos").is_err());
+}
+
+#[test]
+fn default_value() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Outer {
+        #[xmlib(value, default = "InnerStruct::empty()")]
+        inner: InnerStruct,
+        #[xmlib(element, default = 7)]
+        count: u32,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct InnerStruct {
+        a: u8,
+    }
+
+    impl InnerStruct {
+        fn empty() -> Self {
+            Self { a: 0 }
+        }
+    }
+
+    let outer: Outer = xmlib::de::from_str("<outer></outer>").unwrap();
+    assert_eq!(outer, Outer::with_default());
+    assert_eq!(
+        outer,
+        Outer {
+            inner: InnerStruct::empty(),
+            count: 7
+        }
+    );
+
+    let outer: Outer =
+        xmlib::de::from_str(r#"<outer><count>3</count><innerStruct a="1"/></outer>"#).unwrap();
+    assert_eq!(
+        outer,
+        Outer {
+            inner: InnerStruct { a: 1 },
+            count: 3
+        }
+    );
+
+    let serialized = xmlib::ser::write_to_string(Outer::with_default()).unwrap();
+    assert_eq!(serialized, "<outer></outer>");
+}
+
+#[test]
+fn borrow() {
+    use std::borrow::Cow;
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Person<'a> {
+        #[xmlib(borrow)]
+        name: Cow<'a, str>,
+        #[xmlib(borrow)]
+        id: &'a str,
+        age: u8,
+        #[xmlib(value, multiple)]
+        pets: Vec<Pet<'a>>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Pet<'a> {
+        #[xmlib(borrow)]
+        name: Cow<'a, str>,
+    }
+
+    let input = r#"<person name="Jane" id="42" age="7"><pet name="Tom &amp; Jerry"/><pet name="Rex"/></person>"#;
+    let person: Person = xmlib::de::from_str_borrowed(input).unwrap();
+    assert_eq!(
+        person,
+        Person {
+            name: Cow::Borrowed("Jane"),
+            id: "42",
+            age: 7,
+            pets: vec![
+                Pet {
+                    name: Cow::Owned(String::from("Tom & Jerry"))
+                },
+                Pet {
+                    name: Cow::Borrowed("Rex")
+                },
+            ],
+        }
+    );
+    assert!(matches!(person.name, Cow::Borrowed(_)));
+    assert!(matches!(person.pets[0].name, Cow::Owned(_)));
+    assert!(matches!(person.pets[1].name, Cow::Borrowed(_)));
+
+    let serialized = xmlib::ser::write_to_string(Person::with_default(
+        Cow::Borrowed("Bob"),
+        "1",
+        3,
+        Vec::new(),
+    ))
+    .unwrap();
+    assert_eq!(serialized, r#"<person name="Bob" id="1" age="3"></person>"#);
+
+    // &str can't hold unescaped values
+    assert!(
+        xmlib::de::from_str_borrowed::<Person>(r#"<person name="Jane" id="&lt;" age="7"/>"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn checked_end_tags() {
+    use xmlib::de::{deserialize_single_struct, XmlReader};
+    use xmlib::ErrorKind;
+
+    #[derive(Debug, Deserialize)]
+    struct A {
+        #[xmlib(value)]
+        b: B,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct B {
+        id: u32,
+    }
+
+    // structs with a lifetime read their children unbuffered
+    #[derive(Debug, Deserialize)]
+    struct Name<'a> {
+        #[xmlib(borrow)]
+        value: std::borrow::Cow<'a, str>,
+    }
+
+    let err = deserialize_single_struct::<_, A>(XmlReader::new_checked(
+        r#"<a><b id="1"></c></a>"#.as_bytes(),
+    ))
+    .unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::XmlError(_)), "{:?}", err);
+
+    let a = deserialize_single_struct::<_, A>(XmlReader::new_checked(
+        r#"<a><b id="1"></b></a>"#.as_bytes(),
+    ));
+    assert_eq!(a.unwrap().b.id, 1);
+    let name = deserialize_single_struct::<_, Name>(XmlReader::new_checked(
+        r#"<name value="x"></name>"#.as_bytes(),
+    ));
+    assert_eq!(name.unwrap().value, "x");
+
+    let err = deserialize_single_struct::<_, A>(XmlReader::new_checked(
+        r#"<a><b id="1"/></c>"#.as_bytes(),
+    ))
+    .unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::XmlError(_)), "{:?}", err);
+
+    let err = deserialize_single_struct::<_, Name>(XmlReader::new_checked(
+        r#"<name value="x"></c>"#.as_bytes(),
+    ))
+    .unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::XmlError(_)), "{:?}", err);
+}
+
+#[test]
+fn validate_boxed() {
+    #[derive(Debug, PartialEq)]
+    struct TooLong(usize);
+
+    impl std::fmt::Display for TooLong {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "name is {} characters too long", self.0)
+        }
+    }
+
+    impl std::error::Error for TooLong {}
+
+    fn check_len(name: &str) -> Result<(), TooLong> {
+        match name.len() {
+            0..=4 => Ok(()),
+            len => Err(TooLong(len - 4)),
+        }
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct User {
+        #[xmlib(validate_boxed = "check_len")]
+        name: String,
+    }
+
+    let user: User = xmlib::de::from_str(r#"<user name="Bob"/>"#).unwrap();
+    assert_eq!(user.name, "Bob");
+
+    let err = xmlib::de::from_str::<User>(r#"<user name="Alice"/>"#).unwrap_err();
+    match &err.kind {
+        xmlib::ErrorKind::CustomValidation(e) => {
+            assert_eq!(e.downcast_ref::<TooLong>(), Some(&TooLong(1)));
+        }
+        kind => panic!("unexpected error {}", kind),
+    }
+    let source = std::error::Error::source(&err).unwrap();
+    assert_eq!(source.downcast_ref::<TooLong>(), Some(&TooLong(1)));
+
+    let user = User {
+        name: String::from("Alice"),
+    };
+    assert!(matches!(
+        xmlib::ser::write_to_string(&user),
+        Err(xmlib::ser::Error::Validation { .. })
+    ));
+}
+
+#[test]
+fn raw_identifiers() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[allow(non_camel_case_types)]
+    enum Kind {
+        r#true,
+        r#false,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Link {
+        r#type: String,
+        r#ref: Kind,
+        #[xmlib(value_buf)]
+        r#match: String,
+    }
+
+    let link = Link {
+        r#type: String::from("text/html"),
+        r#ref: Kind::r#true,
+        r#match: String::from("all"),
+    };
+    let serialized = xmlib::ser::write_to_string(&link).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<link type="text/html" ref="true">all</link>"#
+    );
+    let deserialized: Link = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, link);
+    assert_eq!(Kind::de_buf(b"false").unwrap(), Kind::r#false);
+}
+
+#[test]
+fn namespace() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(namespace = "http://www.w3.org/2000/svg")]
+    struct Rect {
+        width: u32,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "rect", namespace = "urn:other")]
+    struct OtherRect {
+        size: u32,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Drawing {
+        #[xmlib(value, multiple)]
+        rects: Vec<Rect>,
+        #[xmlib(value)]
+        other: Option<OtherRect>,
+        #[xmlib(element, namespace = "urn:meta")]
+        title: String,
+    }
+
+    let input = r#"<drawing xmlns:svg="http://www.w3.org/2000/svg" xmlns:o="urn:other">
+<svg:rect width="1"/><o:rect size="2"/><title xmlns="urn:meta">A</title>
+<rect xmlns="http://www.w3.org/2000/svg" width="3"></rect></drawing>"#;
+    let drawing: Drawing = xmlib::de::from_str(&input.replace('\n', "")).unwrap();
+    let expected = Drawing {
+        rects: vec![Rect { width: 1 }, Rect { width: 3 }],
+        other: Some(OtherRect { size: 2 }),
+        title: String::from("A"),
+    };
+    assert_eq!(drawing, expected);
+
+    // the element is in a different namespace
+    let input = r#"<drawing xmlns:o="urn:other"><o:rect width="1"/></drawing>"#;
+    assert!(xmlib::de::from_str::<Drawing>(input).is_err());
+    let input = r#"<drawing><title>A</title></drawing>"#;
+    assert!(xmlib::de::from_str::<Drawing>(input).is_err());
+
+    let serialized = xmlib::ser::write_to_string(&expected).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<drawing><rect xmlns="http://www.w3.org/2000/svg" width="1"/><rect xmlns="http://www.w3.org/2000/svg" width="3"/><rect xmlns="urn:other" size="2"/><title xmlns="urn:meta">A</title></drawing>"#
+    );
+    let deserialized: Drawing = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, expected);
+}
+
+#[test]
+fn paths() {
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct File {
+        path: PathBuf,
+        #[xmlib(default)]
+        name: OsString,
+    }
+
+    let file: File = xmlib::de::from_str(r#"<file path="./foo/bar.txt" name="bar"/>"#).unwrap();
+    assert_eq!(
+        file,
+        File {
+            path: PathBuf::from("./foo/bar.txt"),
+            name: OsString::from("bar"),
+        }
+    );
+    let serialized = xmlib::ser::write_to_string(&file).unwrap();
+    assert_eq!(serialized, r#"<file path="./foo/bar.txt" name="bar"/>"#);
+
+    assert!(matches!(
+        PathBuf::de_buf(b"\xff").unwrap_err().kind,
+        xmlib::ErrorKind::FromUtf8Error(_)
+    ));
+
+    let path = std::path::Path::new("media/image1.png");
+    let mut writer = xmlib::ser::XmlWriter::new(Vec::new()).unwrap();
+    xmlib::ser::Serialize::ser(path, &mut writer).unwrap();
+    let serialized = writer.into_inner();
+    assert_eq!(serialized, b"media/image1.png");
+    assert_eq!(PathBuf::de_buf(&serialized).unwrap(), path);
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_path() {
+    use std::os::unix::ffi::OsStringExt;
+    use std::path::PathBuf;
+
+    let path = PathBuf::from(std::ffi::OsString::from_vec(
+        b"media/\xffimage.png".to_vec(),
+    ));
+    match xmlib::ser::write_to_vec(&path).unwrap_err() {
+        xmlib::ser::Error::Unrepresentable { ty_name, reason } => {
+            assert_eq!(ty_name, "Path");
+            assert!(reason.contains("media/\u{FFFD}image.png"), "{}", reason);
+        }
+        e => panic!("unexpected error: {}", e),
+    }
+}
+
+#[test]
+fn constructor_order() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Mixed {
+        #[xmlib(value)]
+        inner: InnerStruct,
+        #[xmlib(collect_namespaces)]
+        namespaces: CollectNamespaces,
+        a: u8,
+        #[xmlib(default)]
+        b: u8,
+        #[xmlib(value_buf)]
+        text: String,
+        c: String,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct InnerStruct {
+        a: u8,
+    }
+
+    let mixed = Mixed::with_default(
+        InnerStruct { a: 1 },
+        2,
+        String::from("text"),
+        String::from("c"),
+    );
+    assert_eq!(
+        mixed,
+        Mixed {
+            inner: InnerStruct { a: 1 },
+            namespaces: Vec::new(),
+            a: 2,
+            b: 0,
+            text: String::from("text"),
+            c: String::from("c"),
+        }
+    );
+}
+
+#[test]
+fn bool_format() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Flags {
+        #[xmlib(bool_format = "words")]
+        visible: bool,
+        #[xmlib(bool_format = "digits")]
+        locked: bool,
+        #[xmlib(element, bool_format = "words")]
+        hidden: bool,
+    }
+
+    let flags = Flags {
+        visible: true,
+        locked: true,
+        hidden: false,
+    };
+    let serialized = xmlib::ser::write_to_string(&flags).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<flags visible="true" locked="1"><hidden>false</hidden></flags>"#
+    );
+    assert_eq!(xmlib::de::from_str::<Flags>(&serialized).unwrap(), flags);
+}
+
+#[test]
+fn lenient_bool() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Switch {
+        #[xmlib(lenient_bool)]
+        on: bool,
+        #[xmlib(element, lenient_bool, default)]
+        locked: bool,
+        #[xmlib(default)]
+        strict: bool,
+    }
+
+    for (value, expected) in [
+        ("1", true),
+        ("true", true),
+        ("TRUE", true),
+        ("Yes", true),
+        ("y", true),
+        ("ON", true),
+        ("0", false),
+        ("False", false),
+        ("no", false),
+        ("N", false),
+        ("off", false),
+    ] {
+        let input = format!(r#"<switch on="{0}"><locked>{0}</locked></switch>"#, value);
+        let switch: Switch = xmlib::de::from_str(&input).unwrap();
+        assert_eq!(switch.on, expected, "{}", value);
+        assert_eq!(switch.locked, expected, "{}", value);
+    }
+
+    for value in ["", "2", "yess", "t", "f", "enabled", " yes"] {
+        let input = format!(r#"<switch on="{}"/>"#, value);
+        assert!(xmlib::de::from_str::<Switch>(&input).is_err(), "{}", value);
+    }
+
+    // the strict parsing doesn't change
+    assert!(xmlib::de::from_str::<Switch>(r#"<switch on="1" strict="yes"/>"#).is_err());
+    assert!(xmlib::de::from_str::<Switch>(r#"<switch on="1" strict="TRUE"/>"#).is_err());
+    assert!(bool::de_buf(b"yes").is_err());
+}
+
+#[test]
+fn derive_xml() {
+    #[derive(Debug, xmlib_derive::Xml, PartialEq)]
+    struct Point {
+        x: i32,
+        #[xmlib(element)]
+        y: i32,
+    }
+
+    #[derive(Debug, xmlib_derive::Xml, PartialEq)]
+    #[xmlib(de_only)]
+    struct Line {
+        #[xmlib(value)]
+        start: Point,
+    }
+
+    #[derive(Debug, xmlib_derive::Xml, PartialEq)]
+    #[xmlib(ser_only)]
+    struct Label {
+        #[xmlib(value)]
+        anchor: Point,
+    }
+
+    let point = Point { x: 1, y: -2 };
+    let serialized = xmlib::ser::write_to_string(&point).unwrap();
+    assert_eq!(serialized, r#"<point x="1"><y>-2</y></point>"#);
+    assert_eq!(xmlib::de::from_str::<Point>(&serialized).unwrap(), point);
+
+    assert_eq!(
+        xmlib::de::from_str::<Line>(r#"<line><point x="1"><y>-2</y></point></line>"#).unwrap(),
+        Line { start: point }
+    );
+    assert_eq!(
+        xmlib::ser::write_to_string(Label {
+            anchor: Point { x: 3, y: 4 }
+        })
+        .unwrap(),
+        r#"<label><point x="3"><y>4</y></point></label>"#
+    );
+}
+
+#[test]
+fn ignore_unknown_children() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[xmlib(ignore_unknown_children)]
+    struct Lenient {
+        #[xmlib(element)]
+        a: u8,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Strict {
+        #[xmlib(element)]
+        a: u8,
+    }
+
+    let input = "<lenient><future/><a>1</a><other><a>2</a></other></lenient>";
+    assert_eq!(
+        xmlib::de::from_str::<Lenient>(input).unwrap(),
+        Lenient { a: 1 }
+    );
+    assert!(matches!(
+        xmlib::de::from_str::<Strict>("<strict><future/><a>1</a></strict>")
+            .unwrap_err()
+            .kind,
+        xmlib::ErrorKind::UnexpectedEvent(_)
+    ));
+}
+
+#[test]
+fn de_from_reader() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[xmlib(namespace = "urn:shapes")]
+    struct Circle {
+        radius: u8,
+    }
+
+    let input = r#"<doc xmlns:s="urn:shapes"><circle radius="1"/><s:circle radius="2"/></doc>"#;
+    assert_eq!(
+        Circle::de_from_reader(input.as_bytes()).unwrap(),
+        Circle { radius: 2 }
+    );
+    assert!(Circle::de_from_reader(&b"<doc/>"[..]).is_err());
+}
+
+#[test]
+fn xml_name() {
+    use xmlib::de::XmlName;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[xmlib(rename = "rect")]
+    struct Rectangle {
+        width: u8,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[xmlib(list, rename = "rects")]
+    struct Rectangles(Vec<Rectangle>);
+
+    fn start_tag<T: XmlName>() -> String {
+        format!("<{}>", String::from_utf8_lossy(T::NAME))
+    }
+
+    assert_eq!(Rectangle::NAME, b"rect");
+    assert_eq!(start_tag::<Rectangles>(), "<rects>");
+    assert_eq!(start_tag::<Vec<Box<Rectangle>>>(), "<rect>");
+    assert_eq!(
+        <Rectangle as DeserializeElement<&[u8]>>::name(),
+        Rectangle::NAME
+    );
+}
+
+#[test]
+fn handwritten_element_without_xml_name() {
+    // implementing `XmlName` is optional for handwritten elements
+    #[derive(Debug, PartialEq)]
+    struct Marker;
+
+    impl<R: std::io::BufRead> DeserializeElement<R> for Marker {
+        fn name() -> &'static [u8] {
+            b"marker"
+        }
+
+        fn de(
+            reader: &mut xmlib::de::XmlReader<R>,
+            start: xmlib::exports::events::BytesStart,
+        ) -> Result<Self, xmlib::de::Error> {
+            reader
+                .read_to_end(start.name(), &mut Vec::new())
+                .map_err(|e| xmlib::Error {
+                    ty_name: String::from("Marker"),
+                    kind: xmlib::ErrorKind::XmlError(e),
+                })?;
+            Ok(Marker)
+        }
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Map {
+        #[xmlib(value, multiple)]
+        marker: Vec<Marker>,
+    }
+
+    assert_eq!(
+        xmlib::de::from_str::<Map>("<map><marker/><marker></marker></map>").unwrap(),
+        Map {
+            marker: vec![Marker, Marker]
+        }
+    );
+}
+
+#[test]
+fn erased() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Point {
+        x: u8,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[xmlib(list, rename = "points", erased)]
+    struct Points(Vec<Point>);
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[xmlib(erased)]
+    struct Shape {
+        name: String,
+        #[xmlib(value)]
+        points: Points,
+    }
+
+    let input = r#"<shape name="line"><points><point x="1"/><point x="2"/></points></shape>"#;
+    let shape: Shape = xmlib::de::from_reader_erased(input.as_bytes()).unwrap();
+    assert_eq!(
+        shape,
+        Shape {
+            name: String::from("line"),
+            points: Points(vec![Point { x: 1 }, Point { x: 2 }]),
+        }
+    );
+    let shape: Shape =
+        xmlib::de::from_reader_erased(std::io::BufReader::new(input.as_bytes())).unwrap();
+    assert_eq!(shape.points.0.len(), 2);
+}
+
+#[test]
+fn mixed_content() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "b")]
+    struct Bold {
+        #[xmlib(value_buf)]
+        text: String,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "a")]
+    struct Link {
+        href: String,
+        #[xmlib(value_buf)]
+        text: String,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(mixed)]
+    enum Node {
+        #[xmlib(text)]
+        Text(String),
+        Bold(Bold),
+        Link(Link),
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "p")]
+    struct Paragraph {
+        class: String,
+        #[xmlib(mixed)]
+        content: Vec<Node>,
+    }
+
+    let input = r#"<p class="intro">Hello <b>bold</b> and <a href="x">link</a> &amp; more</p>"#;
+    let paragraph: Paragraph = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        paragraph,
+        Paragraph {
+            class: String::from("intro"),
+            content: vec![
+                Node::Text(String::from("Hello ")),
+                Node::Bold(Bold {
+                    text: String::from("bold")
+                }),
+                Node::Text(String::from(" and ")),
+                Node::Link(Link {
+                    href: String::from("x"),
+                    text: String::from("link")
+                }),
+                Node::Text(String::from(" & more")),
+            ],
+        }
+    );
+    assert_eq!(xmlib::ser::write_to_string(&paragraph).unwrap(), input);
+
+    assert!(matches!(
+        xmlib::de::from_str::<Paragraph>(r#"<p class="">a<i>b</i></p>"#)
+            .unwrap_err()
+            .kind,
+        xmlib::ErrorKind::UnexpectedEvent(_)
+    ));
+}
+
+#[test]
+fn tagged_enum() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(tagged)]
+    enum Shape {
+        Empty,
+        Circle(u32),
+        #[xmlib(rename = "sq")]
+        Square(u32),
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Drawing {
+        #[xmlib(value, multiple)]
+        shapes: Vec<Shape>,
+    }
+
+    let input = "<drawing><empty/><circle>3</circle><sq>4</sq></drawing>";
+    let drawing: Drawing = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        drawing,
+        Drawing {
+            shapes: vec![Shape::Empty, Shape::Circle(3), Shape::Square(4)],
+        }
+    );
+    assert_eq!(xmlib::ser::write_to_string(&drawing).unwrap(), input);
+
+    assert_eq!(
+        xmlib::de::from_str::<Shape>("<circle>5</circle>").unwrap(),
+        Shape::Circle(5)
+    );
+    assert!(xmlib::de::from_str::<Drawing>("<drawing><triangle/></drawing>").is_err());
+}
+
+#[test]
+fn tagged_enum_payloads() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "rect", namespace = "urn:shapes")]
+    struct Rect {
+        w: u32,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(tagged)]
+    enum Item {
+        Label(String),
+        #[xmlib(value)]
+        Rect(Rect),
+        Empty,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Drawing {
+        #[xmlib(value, multiple)]
+        items: Vec<Item>,
+    }
+
+    let drawing = Drawing {
+        items: vec![
+            Item::Label(String::from("a < b & c")),
+            Item::Rect(Rect { w: 2 }),
+            Item::Empty,
+        ],
+    };
+    let serialized = xmlib::ser::write_to_string(&drawing).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<drawing><label>a &lt; b &amp; c</label><rect xmlns="urn:shapes" w="2"/><empty/></drawing>"#
+    );
+    assert_eq!(
+        xmlib::de::from_str::<Drawing>(&serialized).unwrap(),
+        drawing
+    );
+
+    // text may be split into multiple events
+    assert_eq!(
+        xmlib::de::from_str::<Item>("<label>a<![CDATA[<b>]]></label>").unwrap(),
+        Item::Label(String::from("a<b>"))
+    );
+    // the element of a `value` variant has to be in its namespace
+    assert!(xmlib::de::from_str::<Drawing>(r#"<drawing><rect w="2"/></drawing>"#).is_err());
+}
+
+#[test]
+fn unit_element() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Options {
+        #[xmlib(element)]
+        flag: Option<()>,
+    }
+
+    assert_eq!(
+        xmlib::de::from_str::<Options>("<options><flag/></options>").unwrap(),
+        Options { flag: Some(()) }
+    );
+    assert_eq!(
+        xmlib::de::from_str::<Options>("<options></options>").unwrap(),
+        Options { flag: None }
+    );
+    assert!(xmlib::de::from_str::<Options>("<options><flag>1</flag></options>").is_err());
+
+    let serialized = xmlib::ser::write_to_string(Options { flag: Some(()) }).unwrap();
+    assert_eq!(serialized, "<options><flag></flag></options>");
+    assert_eq!(
+        xmlib::de::from_str::<Options>(&serialized).unwrap(),
+        Options { flag: Some(()) }
+    );
+    assert_eq!(
+        xmlib::ser::write_to_string(Options { flag: None }).unwrap(),
+        "<options></options>"
+    );
+}
+
+#[test]
+fn network_addresses() {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Server {
+        host: Ipv4Addr,
+        #[xmlib(element)]
+        endpoint: SocketAddr,
+        #[xmlib(default = "IpAddr::V6(Ipv6Addr::UNSPECIFIED)")]
+        bind: IpAddr,
+    }
+
+    let input = r#"<server host="192.168.0.1"><endpoint>[::1]:8080</endpoint></server>"#;
+    let server: Server = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        server,
+        Server {
+            host: Ipv4Addr::new(192, 168, 0, 1),
+            endpoint: SocketAddr::from((Ipv6Addr::LOCALHOST, 8080)),
+            bind: IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        }
+    );
+    assert_eq!(xmlib::ser::write_to_string(&server).unwrap(), input);
+
+    let err = xmlib::de::from_str::<Server>(r#"<server host="localhost"/>"#).unwrap_err();
+    assert!(matches!(err.kind, xmlib::ErrorKind::InvalidType(ref v) if v == "localhost"));
+}
+
+#[test]
+fn shared_strings() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Inner {
+        label: Box<str>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Outer {
+        name: Arc<str>,
+        #[xmlib(element)]
+        title: Rc<str>,
+        #[xmlib(value)]
+        inner: Rc<Inner>,
+    }
+
+    let input = r#"<outer name="a"><title>b</title><inner label="c"/></outer>"#;
+    let outer: Outer = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        outer,
+        Outer {
+            name: Arc::from("a"),
+            title: Rc::from("b"),
+            inner: Rc::new(Inner {
+                label: Box::from("c"),
+            }),
+        }
+    );
+    assert_eq!(xmlib::ser::write_to_string(&outer).unwrap(), input);
+
+    assert!(matches!(
+        Arc::<str>::de_buf(b"\xff").unwrap_err().kind,
+        xmlib::ErrorKind::FromUtf8Error(_)
+    ));
+}
+
+#[test]
+fn asymmetric_rename() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Config {
+        #[xmlib(rename(serialize = "new", deserialize = "old"))]
+        value: u8,
+        #[xmlib(element, rename(deserialize = "legacyTitle"))]
+        title: String,
+    }
+
+    let config: Config =
+        xmlib::de::from_str(r#"<config old="1"><legacyTitle>a</legacyTitle></config>"#).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            value: 1,
+            title: String::from("a"),
+        }
+    );
+    assert_eq!(
+        xmlib::ser::write_to_string(&config).unwrap(),
+        r#"<config new="1"><title>a</title></config>"#
+    );
+
+    assert!(xmlib::de::from_str::<Config>(r#"<config new="1"><title>a</title></config>"#).is_err());
+}
+
+mod hex {
+    use std::io::Write;
+
+    pub fn de_buf(buf: &[u8]) -> Result<u8, xmlib::Error> {
+        std::str::from_utf8(buf)
+            .ok()
+            .and_then(|s| u8::from_str_radix(s, 16).ok())
+            .ok_or_else(|| xmlib::Error {
+                ty_name: String::from("hex"),
+                kind: xmlib::ErrorKind::InvalidType(String::from_utf8_lossy(buf).to_string()),
+            })
+    }
+
+    pub fn ser<W: Write>(
+        value: &u8,
+        writer: &mut xmlib::ser::XmlWriter<W>,
+    ) -> Result<(), xmlib::ser::Error> {
+        write!(writer, "{:02X}", value)?;
+        Ok(())
+    }
+}
+
+#[test]
+fn with_module() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Color {
+        #[xmlib(with = "hex")]
+        red: u8,
+        #[xmlib(with = "hex", default)]
+        alpha: u8,
+        #[xmlib(element, multiple, with = "hex")]
+        channel: Vec<u8>,
+        #[xmlib(value_buf, with = "self::hex")]
+        blue: u8,
+    }
+
+    let input = r#"<color red="FF"><channel>0A</channel><channel>0b</channel>80</color>"#;
+    let color: Color = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        color,
+        Color {
+            red: 255,
+            alpha: 0,
+            channel: vec![10, 11],
+            blue: 128,
+        }
+    );
+    assert_eq!(
+        xmlib::ser::write_to_string(&color).unwrap(),
+        r#"<color red="FF"><channel>0A</channel><channel>0B</channel>80</color>"#
+    );
+
+    let err = xmlib::de::from_str::<Color>(r#"<color red="GG">1</color>"#).unwrap_err();
+    assert_eq!(err.ty_name, "hex");
+}
+
+#[test]
+fn error_path() {
+    #[derive(Debug, Deserialize)]
+    struct Item {
+        #[allow(dead_code)]
+        id: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[xmlib(list)]
+    struct Items(#[allow(dead_code)] Vec<Item>);
+
+    #[derive(Debug, Deserialize)]
+    struct Root {
+        #[xmlib(value)]
+        #[allow(dead_code)]
+        items: Items,
+    }
+
+    let err = xmlib::de::from_str::<Root>(r#"<root><items><item id="1"/><item/></items></root>"#)
+        .unwrap_err();
+    assert_eq!(err.ty_name, "root > items > item");
+    assert!(matches!(err.kind, xmlib::ErrorKind::MissingAttr(ref name) if name == "id"));
+    assert_eq!(
+        err.to_string(),
+        "xml error in type root > items > item: missing attribute: id"
+    );
+}
+
+#[test]
+fn collect_attrs() {
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Style {
+        #[xmlib(default)]
+        id: u32,
+        #[xmlib(collect_attrs)]
+        props: BTreeMap<String, String>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "style")]
+    struct OrderedStyle {
+        #[xmlib(collect_attrs)]
+        props: Vec<(String, String)>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "style")]
+    struct HashedStyle {
+        #[xmlib(collect_attrs)]
+        props: HashMap<String, String>,
+    }
+
+    let input = r#"<style italic="0" id="3" color="&lt;red&gt;" bold="1"/>"#;
+
+    let style: Style = xmlib::de::from_str(input).unwrap();
+    assert_eq!(style.id, 3);
+    assert_eq!(
+        style.props.iter().collect::<Vec<_>>(),
+        [
+            (&"bold".to_string(), &"1".to_string()),
+            (&"color".to_string(), &"<red>".to_string()),
+            (&"italic".to_string(), &"0".to_string()),
+        ]
+    );
+    assert_eq!(
+        xmlib::ser::write_to_string(&style).unwrap(),
+        r#"<style id="3" bold="1" color="&lt;red&gt;" italic="0"/>"#
+    );
+
+    let style: OrderedStyle = xmlib::de::from_str(input).unwrap();
+    let props: Vec<_> = style
+        .props
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    assert_eq!(
+        props,
+        [
+            ("italic", "0"),
+            ("id", "3"),
+            ("color", "<red>"),
+            ("bold", "1")
+        ]
+    );
+    assert_eq!(xmlib::ser::write_to_string(&style).unwrap(), input);
+
+    let style: HashedStyle = xmlib::de::from_str(input).unwrap();
+    assert_eq!(style.props.len(), 4);
+    assert_eq!(style.props["color"], "<red>");
+    let serialized = xmlib::ser::write_to_string(&style).unwrap();
+    assert_eq!(
+        xmlib::de::from_str::<HashedStyle>(&serialized).unwrap(),
+        style
+    );
+
+    // the last value of duplicates is kept
+    let input = r#"<style a="1" b="2" a="3"/>"#;
+    let style: OrderedStyle = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        style.props,
+        [
+            ("a".to_string(), "3".to_string()),
+            ("b".to_string(), "2".to_string())
+        ]
+    );
+    let style: Style = xmlib::de::from_str(input).unwrap();
+    assert_eq!(style.props["a"], "3");
+    let style: HashedStyle = xmlib::de::from_str(input).unwrap();
+    assert_eq!(style.props["a"], "3");
+}
+
+#[test]
+fn keyed_children() {
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Sheet {
+        name: String,
+        rows: u32,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Workbook {
+        #[xmlib(value, multiple, key = "name")]
+        sheets: BTreeMap<String, Sheet>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "workbook")]
+    struct HashedWorkbook {
+        #[xmlib(value, multiple, key = "name", wrapper = "sheets")]
+        sheets: HashMap<String, Sheet>,
+    }
+
+    let input = r#"<workbook><sheet name="Q2" rows="3"/><sheet name="Q1" rows="7"/><sheet name="Q3" rows="0"/></workbook>"#;
+    let workbook: Workbook = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        workbook.sheets.keys().collect::<Vec<_>>(),
+        ["Q1", "Q2", "Q3"]
+    );
+    assert_eq!(workbook.sheets["Q1"].rows, 7);
+
+    // the output is sorted by the keys, independent of the input order
+    let expected = r#"<workbook><sheet name="Q1" rows="7"/><sheet name="Q2" rows="3"/><sheet name="Q3" rows="0"/></workbook>"#;
+    assert_eq!(xmlib::ser::write_to_string(&workbook).unwrap(), expected);
+    let deserialized: Workbook = xmlib::de::from_str(expected).unwrap();
+    assert_eq!(
+        xmlib::ser::write_to_string(&deserialized).unwrap(),
+        expected
+    );
+
+    let input = r#"<workbook><sheets><sheet name="a" rows="1"/><sheet name="b" rows="2"/></sheets></workbook>"#;
+    let workbook: HashedWorkbook = xmlib::de::from_str(input).unwrap();
+    assert_eq!(workbook.sheets.len(), 2);
+    assert_eq!(workbook.sheets["b"].rows, 2);
+    let serialized = xmlib::ser::write_to_string(&workbook).unwrap();
+    assert_eq!(
+        xmlib::de::from_str::<HashedWorkbook>(&serialized).unwrap(),
+        workbook
+    );
+
+    let input = r#"<workbook><sheet name="Q1" rows="1"/><sheet name="Q2" rows="2"/><sheet name="Q1" rows="3"/></workbook>"#;
+    let err = xmlib::de::from_str::<Workbook>(input).unwrap_err();
+    match err.kind {
+        xmlib::ErrorKind::DuplicateKey {
+            ref name,
+            ref key,
+            first,
+            duplicate,
+        } => {
+            assert_eq!(name, "sheets");
+            assert_eq!(key, r#""Q1""#);
+            assert_eq!(&input[..first], r#"<workbook><sheet name="Q1" rows="1"/>"#);
+            assert!(input[..duplicate].ends_with(r#"<sheet name="Q1" rows="3"/>"#));
+        }
+        kind => panic!("unexpected error {:?}", kind),
+    }
+}
+
+#[test]
+fn missing_child() {
+    #[derive(Debug, Deserialize)]
+    struct Inner {}
+
+    #[derive(Debug, Deserialize)]
+    struct Outer {
+        #[allow(dead_code)]
+        id: u32,
+        #[xmlib(value)]
+        #[allow(dead_code)]
+        inner: Inner,
+        #[xmlib(element, default)]
+        #[allow(dead_code)]
+        width: u32,
+        #[xmlib(element)]
+        #[allow(dead_code)]
+        height: u32,
+    }
+
+    let err =
+        xmlib::de::from_str::<Outer>(r#"<outer id="1"><height>2</height></outer>"#).unwrap_err();
+    assert_eq!(err.ty_name, "outer");
+    assert!(matches!(err.kind, xmlib::ErrorKind::MissingChild(ref name) if name == "inner"));
+    assert_eq!(
+        err.to_string(),
+        "xml error in type outer: missing child element: inner"
+    );
+
+    let err = xmlib::de::from_str::<Outer>(r#"<outer id="1"><inner/></outer>"#).unwrap_err();
+    assert!(matches!(err.kind, xmlib::ErrorKind::MissingChild(ref name) if name == "height"));
+
+    let err =
+        xmlib::de::from_str::<Outer>(r#"<outer><inner/><height>2</height></outer>"#).unwrap_err();
+    assert!(matches!(err.kind, xmlib::ErrorKind::MissingAttr(ref name) if name == "id"));
+
+    assert!(
+        xmlib::de::from_str::<Outer>(r#"<outer id="1"><inner/><height>2</height></outer>"#).is_ok()
+    );
+}
+
+#[test]
+fn array_children() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "v")]
+    struct Cell {
+        x: f32,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Transform {
+        #[xmlib(value, multiple)]
+        cells: [Cell; 3],
+        #[xmlib(element, multiple, rename = "offset", wrapper = "offsets")]
+        offsets: [i32; 2],
+    }
+
+    let transform = Transform {
+        cells: [Cell { x: 1.0 }, Cell { x: 0.5 }, Cell { x: -2.0 }],
+        offsets: [4, -4],
+    };
+    let input = r#"<transform><v x="1.0"/><v x="0.5"/><v x="-2.0"/><offsets><offset>4</offset><offset>-4</offset></offsets></transform>"#;
+    assert_eq!(xmlib::ser::write_to_string(&transform).unwrap(), input);
+    assert_eq!(xmlib::de::from_str::<Transform>(input).unwrap(), transform);
+
+    let too_few = r#"<transform><v x="1"/><v x="2"/><offsets><offset>4</offset><offset>-4</offset></offsets></transform>"#;
+    let err = xmlib::de::from_str::<Transform>(too_few).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "xml error in type transform: got 2 children cells, expected at least 3 and at most 3"
+    );
+    assert!(matches!(
+        err.kind,
+        xmlib::ErrorKind::Cardinality {
+            got: 2,
+            min: Some(3),
+            max: Some(3),
+            ..
+        }
+    ));
+
+    let too_many = r#"<transform><v x="1"/><v x="2"/><v x="3"/><offsets><offset>4</offset><offset>-4</offset><offset>0</offset></offsets></transform>"#;
+    let err = xmlib::de::from_str::<Transform>(too_many).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        xmlib::ErrorKind::Cardinality { ref name, got: 3, .. } if name == "offset"
+    ));
+}
+
+#[test]
+fn collect_comments() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Server {
+        port: u16,
+        #[xmlib(collect_comments)]
+        comments: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Config {
+        #[xmlib(collect_comments)]
+        comments: Vec<String>,
+        #[xmlib(value, multiple)]
+        servers: Vec<Server>,
+        #[xmlib(element)]
+        name: String,
+    }
+
+    let input = r#"<config><!-- main config --><server port="80"><!-- plain &amp; http --></server><!-- secure --><server port="443"/><name>test</name></config>"#;
+    let config: Config = xmlib::de::from_str(input).unwrap();
+    assert_eq!(config.comments, [" main config ", " secure "]);
+    assert_eq!(config.servers[0].comments, [" plain &amp; http "]);
+    assert!(config.servers[1].comments.is_empty());
+
+    let serialized = xmlib::ser::write_to_string(&config).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<config><!-- main config --><!-- secure --><server port="80"><!-- plain &amp; http --></server><server port="443"/><name>test</name></config>"#
+    );
+    assert_eq!(xmlib::de::from_str::<Config>(&serialized).unwrap(), config);
+
+    let invalid = Server {
+        port: 1,
+        comments: vec![String::from("a -- b")],
+    };
+    assert!(xmlib::ser::write_to_string(&invalid).is_err());
+}
+
+#[test]
+fn multiple_collections() {
+    use std::collections::{BTreeSet, HashSet, LinkedList, VecDeque};
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    struct Tag {
+        name: String,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Post {
+        #[xmlib(value, multiple, wrapper = "tags")]
+        tags: BTreeSet<Tag>,
+        #[xmlib(element, multiple, rename = "id")]
+        ids: BTreeSet<u32>,
+        #[xmlib(element, multiple, rename = "line", default)]
+        lines: VecDeque<String>,
+        #[xmlib(element, multiple, rename = "word", default)]
+        words: LinkedList<String>,
+        #[xmlib(element, multiple, rename = "flag", default)]
+        flags: HashSet<u8>,
+    }
+
+    let input = r#"<post><tags><tag name="b"/><tag name="a"/><tag name="b"/></tags><id>3</id><id>1</id><id>3</id><line>x</line><line>y</line><word>w</word><flag>1</flag><flag>1</flag></post>"#;
+    let post: Post = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        post.tags
+            .iter()
+            .map(|tag| tag.name.as_str())
+            .collect::<Vec<_>>(),
+        ["a", "b"]
+    );
+    assert_eq!(post.ids.iter().copied().collect::<Vec<_>>(), [1, 3]);
+    assert_eq!(post.lines, ["x", "y"]);
+    assert_eq!(post.words.len(), 1);
+    assert_eq!(post.flags.len(), 1);
+
+    let serialized = xmlib::ser::write_to_string(&post).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<post><tags><tag name="a"/><tag name="b"/></tags><id>1</id><id>3</id><line>x</line><line>y</line><word>w</word><flag>1</flag></post>"#
+    );
+    assert_eq!(xmlib::de::from_str::<Post>(&serialized).unwrap(), post);
+}
+
+#[test]
+fn recursive_boxes() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Node {
+        op: String,
+        #[xmlib(value)]
+        child: Option<Box<Self>>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Tree {
+        #[xmlib(value)]
+        root: Box<Node>,
+        #[xmlib(value, multiple, wrapper = "forest")]
+        others: Vec<Tree>,
+    }
+
+    let leaf = |op: &str| Node::with_default(op.to_string());
+    let node = |op: &str, child: Node| Node {
+        op: op.to_string(),
+        child: Some(Box::new(child)),
+    };
+    let tree = Tree {
+        root: Box::new(node("add", node("mul", node("neg", leaf("x"))))),
+        others: vec![Tree::with_default(Box::new(leaf("y")), Vec::new())],
+    };
+    let serialized = xmlib::ser::write_to_string(&tree).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<tree><node op="add"><node op="mul"><node op="neg"><node op="x"></node></node></node></node><forest><tree><node op="y"></node><forest/></tree></forest></tree>"#
+    );
+    assert_eq!(xmlib::de::from_str::<Tree>(&serialized).unwrap(), tree);
+
+    let node: Node = xmlib::de::from_str(r#"<node op="x"/>"#).unwrap();
+    assert_eq!(node.child, None);
+
+    let err = xmlib::de::from_str::<Tree>("<tree/>").unwrap_err();
+    assert!(matches!(err.kind, xmlib::ErrorKind::MissingChild(ref name) if name == "node"));
+}
+
+#[test]
+fn display_fields() {
+    #[derive(Debug, PartialEq)]
+    struct Version {
+        major: u32,
+        minor: u32,
+        pre: Option<String>,
+    }
+
+    impl std::fmt::Display for Version {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}.{}", self.major, self.minor)?;
+            match &self.pre {
+                Some(pre) => write!(f, "-{}", pre),
+                None => Ok(()),
+            }
+        }
+    }
+
+    impl std::str::FromStr for Version {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let (version, pre) = match s.split_once('-') {
+                Some((version, pre)) => (version, Some(String::from(pre))),
+                None => (s, None),
+            };
+            let (major, minor) = version.split_once('.').ok_or("missing minor")?;
+            Ok(Version {
+                major: major.parse().map_err(|_| "invalid major")?,
+                minor: minor.parse().map_err(|_| "invalid minor")?,
+                pre,
+            })
+        }
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Package {
+        #[xmlib(display)]
+        version: Version,
+        #[xmlib(display, element, multiple)]
+        requires: Vec<Version>,
+        #[xmlib(display, value_buf)]
+        latest: Version,
+    }
+
+    let input = r#"<package version="1.2-rc"><requires>0.9</requires><requires>0.10-a&lt;b</requires>2.0</package>"#;
+    let package: Package = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        package,
+        Package {
+            version: Version {
+                major: 1,
+                minor: 2,
+                pre: Some(String::from("rc")),
+            },
+            requires: vec![
+                Version {
+                    major: 0,
+                    minor: 9,
+                    pre: None,
+                },
+                Version {
+                    major: 0,
+                    minor: 10,
+                    pre: Some(String::from("a<b")),
+                },
+            ],
+            latest: Version {
+                major: 2,
+                minor: 0,
+                pre: None,
+            },
+        }
+    );
+    assert_eq!(xmlib::ser::write_to_string(package).unwrap(), input);
+
+    let err = xmlib::de::from_str::<Package>(r#"<package version="1">2.0</package>"#).unwrap_err();
+    assert!(
+        matches!(err.kind, xmlib::ErrorKind::InvalidType(ref s) if s.contains("missing minor"))
+    );
+}
+
+#[test]
+fn tag_attr_enum() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "node")]
+    struct Circle {
+        r: u32,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(rename = "node")]
+    struct Rect {
+        w: u32,
+        #[xmlib(element)]
+        label: String,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(tag_attr = "type")]
+    enum Shape {
+        Circle(Circle),
+        #[xmlib(rename = "rectangle")]
+        Rect(Rect),
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Drawing {
+        #[xmlib(value, multiple)]
+        nodes: Vec<Shape>,
+    }
+
+    let input = r#"<drawing><node type="circle" r="1"/><node w="2" type="rectangle"><label>a</label></node></drawing>"#;
+    let drawing: Drawing = xmlib::de::from_str(input).unwrap();
+    assert_eq!(
+        drawing,
+        Drawing {
+            nodes: vec![
+                Shape::Circle(Circle { r: 1 }),
+                Shape::Rect(Rect {
+                    w: 2,
+                    label: String::from("a"),
+                }),
+            ],
+        }
+    );
+    assert_eq!(
+        xmlib::ser::write_to_string(drawing).unwrap(),
+        r#"<drawing><node type="circle" r="1"/><node type="rectangle" w="2"><label>a</label></node></drawing>"#
+    );
+
+    let err = xmlib::de::from_str::<Shape>(r#"<node type="square" r="1"/>"#).unwrap_err();
+    assert!(matches!(err.kind, xmlib::ErrorKind::InvalidType(_)));
+    let err = xmlib::de::from_str::<Shape>(r#"<node r="1"/>"#).unwrap_err();
+    assert!(matches!(err.kind, xmlib::ErrorKind::MissingAttr(ref name) if name == "type"));
+}
+
+#[test]
+fn derive_default() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(derive_default)]
+    struct Settings {
+        #[xmlib(default = "8080")]
+        port: u16,
+        #[xmlib(default)]
+        host: String,
+        #[xmlib(element, multiple, default)]
+        plugins: Vec<String>,
+        #[xmlib(value_buf, default)]
+        comment: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    #[xmlib(derive_default, no_constructor)]
+    struct Empty {
+        #[xmlib(default)]
+        verbose: bool,
+    }
+
+    let settings = Settings::default();
+    assert_eq!(
+        settings,
+        Settings {
+            port: 8080,
+            host: String::new(),
+            plugins: Vec::new(),
+            comment: None,
+        }
+    );
+    assert_eq!(Settings::with_default(), settings);
+    assert_eq!(
+        xmlib::de::from_str::<Settings>("<settings/>").unwrap(),
+        settings
+    );
+    assert_eq!(Empty::default(), Empty { verbose: false });
+}
+
+#[test]
+fn unbound_prefix() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Rect {
+        width: u8,
+    }
+
+    // names with declared prefixes are still ignored
+    let input = r#"<rect xmlns:x="urn:x" x:id="1" width="2"><x:meta/></rect>"#;
+    assert_eq!(
+        xmlib::de::from_str::<Rect>(input).unwrap(),
+        Rect { width: 2 }
+    );
+
+    for input in [
+        r#"<rect x:id="1" width="2"/>"#,
+        r#"<rect width="2"><x:meta/></rect>"#,
+    ] {
+        let err = xmlib::de::from_str::<Rect>(input).unwrap_err();
+        assert!(matches!(err.kind, xmlib::ErrorKind::UnboundPrefix(ref prefix) if prefix == "x"));
+    }
+}
+
+#[test]
+fn multiple_value_buf() {
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Poem {
+        #[xmlib(value_buf, multiple)]
+        lines: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize, xmlib_derive::Serialize, PartialEq)]
+    struct Scores {
+        #[xmlib(value_buf, multiple, cdata, min = 1)]
+        values: Vec<u32>,
+    }
+
+    let input = "<poem>Roses &amp; violets<!-- 1 -->\n  <!-- 2 -->are red<![CDATA[<blue>]]></poem>";
+    let poem: Poem = xmlib::de::from_str(input).unwrap();
+    assert_eq!(poem.lines, ["Roses & violets", "are red", "<blue>"]);
+
+    let serialized = xmlib::ser::write_to_string(&poem).unwrap();
+    assert_eq!(
+        serialized,
+        "<poem>Roses &amp; violets<!---->are red<!---->&lt;blue></poem>"
+    );
+    assert_eq!(xmlib::de::from_str::<Poem>(&serialized).unwrap(), poem);
+    assert_eq!(
+        xmlib::de::from_str::<Poem>("<poem/>").unwrap(),
+        Poem { lines: Vec::new() }
+    );
+
+    let scores = Scores { values: vec![1, 2] };
+    let serialized = xmlib::ser::write_to_string(&scores).unwrap();
+    assert_eq!(serialized, "<scores><![CDATA[1]]><![CDATA[2]]></scores>");
+    assert_eq!(xmlib::de::from_str::<Scores>(&serialized).unwrap(), scores);
+    assert!(xmlib::de::from_str::<Scores>("<scores/>").is_err());
+}
+
+#[test]
+fn max_depth() {
+    #[derive(Debug, Deserialize)]
+    struct Node {
+        #[xmlib(value)]
+        child: Option<Box<Node>>,
+    }
+
+    let depth = 10_000;
+    let input = format!("{}{}", "<node>".repeat(depth), "</node>".repeat(depth));
+    let err = xmlib::de::from_str::<Node>(&input).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        xmlib::ErrorKind::DepthLimitExceeded(xmlib::de::DEFAULT_MAX_DEPTH)
+    ));
+    let err = xmlib::de::from_str::<xmlib::Element>(&input).unwrap_err();
+    assert!(matches!(err.kind, xmlib::ErrorKind::DepthLimitExceeded(_)));
+
+    // documents at the limit are still accepted
+    let input = format!("{}{}", "<node>".repeat(10), "</node>".repeat(10));
+    let reader = xmlib::de::XmlReader::from_str(&input).with_max_depth(10);
+    let mut node: Node = xmlib::de::deserialize_single_struct(reader).unwrap();
+    let mut levels = 1;
+    while let Some(child) = node.child {
+        node = *child;
+        levels += 1;
+    }
+    assert_eq!(levels, 10);
+    let reader = xmlib::de::XmlReader::from_str(&input).with_max_depth(9);
+    assert!(xmlib::de::deserialize_single_struct::<_, Node>(reader).is_err());
+}
@@ -0,0 +1,15 @@
+use xmlib_derive::Deserialize;
+
+#[derive(Deserialize)]
+struct Inner<'a> {
+    #[xmlib(borrow)]
+    name: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Outer {
+    #[xmlib(value)]
+    inner: Inner<'static>,
+}
+
+fn main() {}
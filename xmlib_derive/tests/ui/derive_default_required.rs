@@ -0,0 +1,11 @@
+use xmlib_derive::Serialize;
+
+#[derive(Serialize)]
+#[xmlib(derive_default)]
+struct Rectangle {
+    #[xmlib(default)]
+    width: u32,
+    height: u32,
+}
+
+fn main() {}
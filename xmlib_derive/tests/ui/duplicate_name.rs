@@ -0,0 +1,26 @@
+use xmlib_derive::Deserialize;
+
+#[derive(Deserialize)]
+struct Attributes {
+    foo_bar: u8,
+    #[xmlib(rename = "fooBar")]
+    other: u8,
+}
+
+#[derive(Deserialize)]
+struct Elements {
+    #[xmlib(element)]
+    item: u8,
+    #[xmlib(element, multiple, wrapper = "item")]
+    items: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct Namespaced {
+    #[xmlib(element, namespace = "a")]
+    item: u8,
+    #[xmlib(element, namespace = "b", rename = "item")]
+    other: u8,
+}
+
+fn main() {}
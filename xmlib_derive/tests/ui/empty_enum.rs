@@ -0,0 +1,6 @@
+use xmlib_derive::Deserialize;
+
+#[derive(Deserialize)]
+enum Never {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use xmlib_derive::Deserialize;
+
+#[derive(Deserialize)]
+struct Rectangle {
+    #[xmlib(default = "Rectangle::")]
+    width: u32,
+}
+
+fn main() {}
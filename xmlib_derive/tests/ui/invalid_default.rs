@@ -0,0 +1,9 @@
+use xmlib_derive::Deserialize;
+
+#[derive(Deserialize)]
+struct Rectangle {
+    #[xmlib(default = "not valid rust (")]
+    width: u32,
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use xmlib_derive::Deserialize;
+
+#[derive(Deserialize)]
+struct Unknown {
+    #[xmlib(rename(read = "a"))]
+    a: u8,
+}
+
+#[derive(Deserialize)]
+struct Duplicate {
+    #[xmlib(rename(serialize = "a", serialize = "b"))]
+    a: u8,
+}
+
+#[derive(Deserialize)]
+struct Collision {
+    #[xmlib(rename(deserialize = "b"))]
+    a: u8,
+    #[xmlib(rename(serialize = "a"))]
+    b: u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use xmlib_derive::Serialize;
+
+#[derive(Serialize)]
+struct Rectangle {
+    #[xmlib(validate = "is positive")]
+    width: u32,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use xmlib_derive::Deserialize;
+
+#[derive(Deserialize)]
+struct Sheet {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Workbook {
+    #[xmlib(value, multiple, key = "name")]
+    sheets: Vec<Sheet>,
+}
+
+fn main() {}
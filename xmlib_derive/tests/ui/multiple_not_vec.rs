@@ -0,0 +1,9 @@
+use xmlib_derive::Deserialize;
+
+#[derive(Deserialize)]
+struct Outer {
+    #[xmlib(element, multiple)]
+    item: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use xmlib_derive::Serialize;
+
+#[derive(Serialize)]
+#[xmlib = "foo"]
+struct Rectangle {
+    width: u32,
+}
+
+fn main() {}
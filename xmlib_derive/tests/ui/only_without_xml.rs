@@ -0,0 +1,9 @@
+use xmlib_derive::Serialize;
+
+#[derive(Serialize)]
+#[xmlib(ser_only)]
+struct Point {
+    x: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use xmlib_derive::Deserialize;
+
+#[derive(Deserialize)]
+#[xmlib(transparent)]
+struct Size {
+    width: u32,
+    height: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use xmlib_derive::Deserialize;
+
+#[derive(Deserialize)]
+struct Rectangle {
+    #[xmlib(dafault = 3)]
+    width: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use xmlib_derive::Deserialize;
+
+#[derive(Deserialize)]
+enum Shape {
+    #[xmlib(rename = "rect", default)]
+    Rectangle,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use xmlib_derive::Deserialize;
+
+#[derive(Deserialize)]
+struct Rect {
+    w: u32,
+}
+
+#[derive(Deserialize)]
+enum Shape {
+    #[xmlib(value)]
+    Rect(Rect),
+}
+
+fn main() {}
@@ -171,3 +171,552 @@ fn unnamed_struct() {
         A(u8),
     }
 }*/
+
+#[test]
+fn error_contains_type_name() {
+    #[derive(Serialize, Debug)]
+    struct Struct {
+        a: Option<u8>,
+    }
+
+    let mut writer = xmlib::ser::XmlWriter::new(Vec::with_capacity(128)).unwrap();
+    let err = Struct { a: None }.ser(&mut writer).unwrap_err();
+    assert!(matches!(
+        &err,
+        xmlib::ser::Error::Unrepresentable { ty_name, .. } if ty_name == "u8"
+    ));
+    assert!(err.to_string().contains("u8"));
+
+    let err: std::io::Error = err.into();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn validation() {
+    #[derive(Serialize, xmlib_derive::Deserialize, Debug)]
+    struct Square {
+        #[xmlib(validate = "is_positive")]
+        size: i32,
+    }
+
+    #[derive(Serialize, xmlib_derive::Deserialize, Debug)]
+    struct Size(#[xmlib(validate = "is_positive")] i32);
+
+    fn is_positive(size: &i32) -> Result<(), i32> {
+        if *size > 0 {
+            Ok(())
+        } else {
+            Err(*size)
+        }
+    }
+
+    assert_eq!(ser(&Square { size: 42 }).unwrap(), r#"<square size="42"/>"#);
+    assert_eq!(ser(&Size(42)).unwrap(), r#"42"#);
+
+    let mut writer = xmlib::ser::XmlWriter::new(Vec::with_capacity(128)).unwrap();
+    let err = Square { size: -1 }.ser(&mut writer).unwrap_err();
+    assert!(matches!(
+        err,
+        xmlib::ser::Error::Validation { ty_name, message } if ty_name == "size" && message == "-1"
+    ));
+    // nothing should be written if the validation fails
+    assert!(writer.is_empty());
+
+    assert!(xmlib::ser::write_to_string(Size(-1)).is_err());
+
+    assert!(xmlib::de::from_str::<Square>(r#"<square size="-1"/>"#).is_err());
+}
+
+#[test]
+fn cdata() {
+    #[derive(Serialize, xmlib_derive::Deserialize, Debug, PartialEq)]
+    struct Struct {
+        #[xmlib(value_buf, cdata)]
+        b: String,
+    }
+
+    let s = Struct {
+        b: String::from("<a href=\"x\">&amp;</a> ]]> ]]]>"),
+    };
+
+    let serialized = ser(&s).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<struct><![CDATA[<a href="x">&amp;</a> ]]]]><![CDATA[> ]]]]]><![CDATA[>]]></struct>"#
+    );
+
+    let deserialized: Struct = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, s);
+}
+
+#[test]
+fn quote_style() {
+    use xmlib::ser::{QuoteStyle, XmlWriter};
+
+    #[derive(Serialize, xmlib_derive::Deserialize, Debug, PartialEq)]
+    #[xmlib(namespace = "urn:a")]
+    struct Struct {
+        a: String,
+        #[xmlib(element, namespace = "urn:b")]
+        b: u8,
+    }
+
+    let s = Struct {
+        a: String::from("x y"),
+        b: 3,
+    };
+
+    for (style, expected) in [
+        (
+            QuoteStyle::Double,
+            r#"<struct xmlns="urn:a" a="x y"><b xmlns="urn:b">3</b></struct>"#,
+        ),
+        (
+            QuoteStyle::Single,
+            r#"<struct xmlns='urn:a' a='x y'><b xmlns='urn:b'>3</b></struct>"#,
+        ),
+    ] {
+        let mut writer = XmlWriter::new(Vec::new()).unwrap().with_quote_style(style);
+        s.ser(&mut writer).unwrap();
+        let serialized = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(serialized, expected);
+
+        let deserialized: Struct = xmlib::de::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, s);
+    }
+}
+
+#[test]
+fn escaped_attributes() {
+    use xmlib::ser::{QuoteStyle, XmlWriter};
+
+    #[derive(Serialize)]
+    struct Struct {
+        a: String,
+        b: String,
+    }
+
+    let s = Struct {
+        a: String::from("it's"),
+        b: String::from(r#"a"b & <c>"#),
+    };
+
+    for (style, expected) in [
+        (
+            QuoteStyle::Double,
+            r#"<struct a="it's" b="a&quot;b &amp; &lt;c>"/>"#,
+        ),
+        (
+            QuoteStyle::Single,
+            r#"<struct a='it&apos;s' b='a"b &amp; &lt;c>'/>"#,
+        ),
+    ] {
+        let mut writer = XmlWriter::new(Vec::new()).unwrap().with_quote_style(style);
+        s.ser(&mut writer).unwrap();
+        let serialized = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(serialized, expected);
+    }
+}
+
+#[test]
+fn bool_style() {
+    use xmlib::ser::{BoolStyle, XmlWriter};
+
+    #[derive(Serialize, xmlib_derive::Deserialize, Debug, PartialEq)]
+    struct Flags {
+        visible: bool,
+        #[xmlib(default)]
+        locked: bool,
+        #[xmlib(default = "true")]
+        enabled: bool,
+        #[xmlib(bool_format = "digits")]
+        digits: bool,
+        #[xmlib(element)]
+        hidden: bool,
+    }
+
+    let flags = Flags {
+        visible: true,
+        locked: false,
+        enabled: false,
+        digits: true,
+        hidden: false,
+    };
+
+    for (style, expected) in [
+        (
+            BoolStyle::Digits,
+            r#"<flags visible="1" enabled="0" digits="1"><hidden>0</hidden></flags>"#,
+        ),
+        (
+            BoolStyle::Words,
+            r#"<flags visible="true" enabled="false" digits="1"><hidden>false</hidden></flags>"#,
+        ),
+    ] {
+        let mut writer = XmlWriter::new(Vec::new()).unwrap().with_bool_style(style);
+        flags.ser(&mut writer).unwrap();
+        let serialized = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(serialized, expected);
+
+        let deserialized: Flags = xmlib::de::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, flags);
+    }
+    assert_eq!(
+        xmlib::ser::write_to_string(&flags).unwrap(),
+        r#"<flags visible="1" enabled="0" digits="1"><hidden>0</hidden></flags>"#
+    );
+}
+
+#[test]
+fn serializer() {
+    use xmlib::ser::{QuoteStyle, Serializer};
+
+    #[derive(Serialize, xmlib_derive::Deserialize, Debug, PartialEq)]
+    struct Point {
+        x: i32,
+    }
+
+    #[derive(Serialize, xmlib_derive::Deserialize, Debug, PartialEq)]
+    #[xmlib(list)]
+    struct Points(Vec<Point>);
+
+    #[derive(Serialize, xmlib_derive::Deserialize, Debug, PartialEq)]
+    #[xmlib(list, rename = "empty")]
+    struct Empty(Vec<Point>);
+
+    #[derive(Serialize, xmlib_derive::Deserialize, Debug, PartialEq)]
+    struct Label {
+        #[xmlib(value_buf)]
+        text: String,
+        #[xmlib(value)]
+        anchor: Option<Point>,
+    }
+
+    #[derive(Serialize, xmlib_derive::Deserialize, Debug, PartialEq)]
+    struct Shape {
+        #[xmlib(value)]
+        points: Points,
+        #[xmlib(element, multiple, rename = "tag")]
+        tags: Vec<String>,
+        #[xmlib(value, multiple, wrapper = "labels")]
+        labels: Vec<Label>,
+        #[xmlib(value)]
+        empty: Empty,
+        #[xmlib(value, nillable)]
+        origin: Option<Point>,
+    }
+
+    let shape = Shape {
+        points: Points(vec![Point { x: 1 }, Point { x: 2 }]),
+        tags: vec![String::from("a")],
+        labels: vec![Label {
+            text: String::from("text"),
+            anchor: Some(Point { x: 3 }),
+        }],
+        empty: Empty(Vec::new()),
+        origin: None,
+    };
+
+    let serialized = Serializer::new().indent(' ', 2).to_string(&shape).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<shape>
+  <points>
+    <point x="1"/>
+    <point x="2"/>
+  </points>
+  <tag>a</tag>
+  <labels>
+    <label><point x="3"/>text</label>
+  </labels>
+  <empty/>
+  <point xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:nil="true"/>
+</shape>"#
+    );
+    // the indentation is text, which has to be trimmed to read the output back
+    let mut reader = xmlib::de::XmlReader::from_str(&serialized);
+    reader.trim_text(true);
+    let deserialized: Shape = xmlib::de::deserialize_single_struct(reader).unwrap();
+    assert_eq!(deserialized, shape);
+
+    let serialized = Serializer::new()
+        .declaration(true)
+        .indent('\t', 1)
+        .quotes(QuoteStyle::Single)
+        .to_string(&Points(vec![Point { x: 1 }]))
+        .unwrap();
+    assert_eq!(
+        serialized,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<points>\n\t<point x='1'/>\n</points>"
+    );
+    let mut reader = xmlib::de::XmlReader::from_str(&serialized);
+    reader.trim_text(true);
+    let deserialized: Points = xmlib::de::deserialize_single_struct(reader).unwrap();
+    assert_eq!(deserialized, Points(vec![Point { x: 1 }]));
+
+    // the default options write the same as `write_to_vec`
+    assert_eq!(
+        Serializer::new().to_vec(&shape).unwrap(),
+        xmlib::ser::write_to_vec(&shape).unwrap()
+    );
+    let mut out = Vec::new();
+    Serializer::new().to_writer(&mut out, &shape).unwrap();
+    assert_eq!(out, xmlib::ser::write_to_vec(&shape).unwrap());
+}
+
+#[test]
+fn trait_objects() {
+    #[derive(Serialize)]
+    struct Circle {
+        radius: u32,
+    }
+
+    #[derive(Serialize)]
+    struct Square {
+        size: u32,
+    }
+
+    let shapes: Vec<Box<dyn Serialize<Vec<u8>>>> = vec![
+        Box::new(Circle { radius: 1 }),
+        Box::new(Square { size: 2 }),
+        Box::new(Circle { radius: 3 }),
+    ];
+    assert_eq!(
+        ser(&shapes).unwrap(),
+        r#"<circle radius="1"/><square size="2"/><circle radius="3"/>"#
+    );
+
+    let square = Square { size: 4 };
+    let borrowed: Vec<&dyn Serialize<Vec<u8>>> = vec![&square, &shapes[0]];
+    assert_eq!(
+        ser(&borrowed).unwrap(),
+        r#"<square size="4"/><circle radius="1"/>"#
+    );
+}
+
+#[test]
+fn float_format() {
+    #[derive(Serialize, xmlib_derive::Deserialize, Debug, PartialEq)]
+    struct Measurement {
+        #[xmlib(float_format(precision = 2))]
+        value: f64,
+        #[xmlib(float_format(no_exponent))]
+        tiny: f64,
+        #[xmlib(float_format(precision = 3, no_exponent))]
+        huge: f32,
+        #[xmlib(element, multiple, float_format(precision = 1), rename = "sample")]
+        samples: Vec<f64>,
+        #[xmlib(value_buf, float_format(no_exponent))]
+        raw: f64,
+    }
+
+    let measurement = Measurement {
+        value: 0.1 + 0.2,
+        tiny: 1e-7,
+        huge: 1e20,
+        samples: vec![2.675, -0.04, 2.0, f64::INFINITY],
+        raw: -1.5e-10,
+    };
+    let serialized = xmlib::ser::write_to_string(&measurement).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<measurement value="0.3" tiny="0.0000001" huge="100000002004087734272.0"><sample>2.7</sample><sample>0.0</sample><sample>2.0</sample><sample>inf</sample>-0.00000000015</measurement>"#
+    );
+
+    #[derive(Serialize)]
+    struct P {
+        #[xmlib(float_format(no_exponent))]
+        x: f64,
+        #[xmlib(float_format(precision = 4))]
+        y: f32,
+    }
+    for value in [1e300, 1e-300, 6.02214076e23, -1.6e-19] {
+        let p = P {
+            x: value,
+            y: value as f32,
+        };
+        let serialized = xmlib::ser::write_to_string(&p).unwrap();
+        assert!(!serialized.contains(['e', 'E']), "{}", serialized);
+    }
+
+    let deserialized: Measurement = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized.value, 0.3);
+    assert_eq!(deserialized.tiny, 1e-7);
+    assert_eq!(deserialized.raw, -1.5e-10);
+
+    let rounded = Measurement {
+        value: 1.005,
+        tiny: 0.0,
+        huge: 1.0,
+        samples: vec![],
+        raw: 0.0,
+    };
+    assert_eq!(
+        xmlib::ser::write_to_string(&rounded).unwrap(),
+        r#"<measurement value="1.0" tiny="0.0" huge="1.0">0.0</measurement>"#
+    );
+}
+
+#[test]
+fn arrays() {
+    #[derive(Serialize)]
+    struct Child {
+        id: u8,
+    }
+
+    #[derive(Serialize)]
+    struct Parent {
+        #[xmlib(value)]
+        children: [Child; 3],
+    }
+
+    let parent = Parent {
+        children: [Child { id: 1 }, Child { id: 2 }, Child { id: 3 }],
+    };
+    assert_eq!(
+        ser(&parent).unwrap(),
+        r#"<parent><child id="1"/><child id="2"/><child id="3"/></parent>"#
+    );
+    assert_eq!(
+        ser(&[Child { id: 4 }, Child { id: 5 }]).unwrap(),
+        r#"<child id="4"/><child id="5"/>"#
+    );
+    assert_eq!(ser(&[0u8; 0]).unwrap(), "");
+}
+
+#[test]
+fn escaped_value_buf() {
+    #[derive(Debug, Serialize, xmlib_derive::Deserialize, PartialEq)]
+    struct Condition {
+        #[xmlib(value_buf)]
+        text: String,
+    }
+
+    let condition = Condition {
+        text: String::from(r#"a < b && c > "d" ]]> 'e'"#),
+    };
+    let serialized = xmlib::ser::write_to_string(&condition).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<condition>a &lt; b &amp;&amp; c > "d" ]]&gt; 'e'</condition>"#
+    );
+    let deserialized: Condition = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, condition);
+}
+
+#[test]
+fn text_uses_writer_configuration() {
+    #[derive(Serialize)]
+    struct Flag {
+        #[xmlib(value_buf)]
+        on: bool,
+    }
+
+    #[derive(Serialize)]
+    #[xmlib(mixed)]
+    enum Node {
+        #[xmlib(text)]
+        Text(String),
+        Flag(Flag),
+    }
+
+    #[derive(Serialize)]
+    #[xmlib(rename = "p")]
+    struct Paragraph {
+        #[xmlib(mixed)]
+        content: Vec<Node>,
+    }
+
+    let paragraph = Paragraph {
+        content: vec![
+            Node::Text(String::from(r#"a "b" < c "#)),
+            Node::Flag(Flag { on: true }),
+            Node::Text(String::from(" 'd'")),
+        ],
+    };
+    let serialized = xmlib::ser::Serializer::new()
+        .indent(' ', 2)
+        .bools(xmlib::ser::BoolStyle::Words)
+        .to_string(&paragraph)
+        .unwrap();
+    assert_eq!(serialized, r#"<p>a "b" &lt; c <flag>true</flag> 'd'</p>"#);
+}
+
+#[test]
+fn escaped_element() {
+    #[derive(Debug, Serialize, xmlib_derive::Deserialize, PartialEq)]
+    struct Query {
+        #[xmlib(element)]
+        filter: String,
+        #[xmlib(element, multiple)]
+        terms: Vec<String>,
+    }
+
+    let query = Query {
+        filter: String::from("a < b && c"),
+        terms: vec![String::from("<d>"), String::from("e & f")],
+    };
+    let serialized = xmlib::ser::write_to_string(&query).unwrap();
+    assert_eq!(
+        serialized,
+        "<query><filter>a &lt; b &amp;&amp; c</filter>\
+         <terms>&lt;d></terms><terms>e &amp; f</terms></query>"
+    );
+    let deserialized: Query = xmlib::de::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, query);
+}
+
+#[test]
+fn element_name() {
+    #[derive(Debug, Serialize, PartialEq)]
+    #[xmlib(rename = "rect", namespace = "urn:shapes")]
+    struct Rectangle {
+        width: u8,
+    }
+
+    #[derive(Debug, Serialize, PartialEq)]
+    #[xmlib(list, rename = "rects")]
+    struct Rectangles(Vec<Rectangle>);
+
+    type W = Vec<u8>;
+
+    fn start_tag<T: Serialize<W>>() -> Option<String> {
+        T::element_name().map(|name| format!("<{}>", String::from_utf8_lossy(name)))
+    }
+
+    assert_eq!(start_tag::<Rectangle>().as_deref(), Some("<rect>"));
+    assert_eq!(
+        <Rectangle as Serialize<W>>::element_namespace(),
+        Some(&b"urn:shapes"[..])
+    );
+    assert_eq!(start_tag::<Rectangles>().as_deref(), Some("<rects>"));
+    assert_eq!(<Rectangles as Serialize<W>>::element_namespace(), None);
+    assert_eq!(
+        start_tag::<Vec<Box<Rectangle>>>().as_deref(),
+        Some("<rect>")
+    );
+    assert_eq!(start_tag::<u8>(), None);
+}
+
+#[test]
+fn ser_with_attr() {
+    use xmlib::ser::{QuoteStyle, SerializeWithAttr, XmlWriter};
+
+    #[derive(Debug, Serialize, PartialEq)]
+    #[xmlib(rename = "node")]
+    struct Node {
+        r: u32,
+    }
+
+    let mut writer = XmlWriter::new(Vec::new())
+        .unwrap()
+        .with_quote_style(QuoteStyle::Single);
+    Node { r: 1 }
+        .ser_with_attr(&mut writer, Some((b"type", b"it's <a>")))
+        .unwrap();
+    Node { r: 2 }.ser_with_attr(&mut writer, None).unwrap();
+    assert_eq!(
+        writer.into_inner(),
+        &b"<node type='it&apos;s &lt;a>' r='1'/><node r='2'/>"[..]
+    );
+}